@@ -1,32 +1,152 @@
-use crate::types::{Tab, DietPhase}; // ExerciseMetric not directly used yet here
+use crate::backup::{self, BackupReason, Snapshot};
+use crate::catalog;
+use crate::dashboard::{self, DashboardData};
+use crate::csv_io::import::ImportReport;
+use crate::csv_io::CsvError;
+use crate::db_init;
+use crate::db::{self, Database, DbResult, DietCycleSummary, WeightLog};
+use crate::db::exercises::{DuplicateSuggestion, Exercise, ExerciseFilter};
+use crate::db::measurements::Measurement;
+use crate::db::templates::WorkoutTemplate;
+use crate::db::workouts::NewExercise;
+use crate::types::{
+    Tab, AppImportState, CsvImportState, DietCycleEdit, DietPhase, ExerciseLogEntry, LegacyDbMigration, ExerciseMetric, MeasurementKind, NutritionInput, PendingDuplicateWeighIn,
+    WeightLogEdit, WeightLogUndo, WeightProgressFilter, WeightProgressView,
+};
+use crate::smoothing::{self, SmoothingMethod};
+use crate::timer::{IntervalPlan, Timer, TimerMode};
+use crate::tdee;
+use crate::settings::Settings;
+use crate::units::WeightUnit;
+use crate::db::records::{self, PersonalRecord};
+use crate::ui::plot_helpers::PhaseSpan;
+use crate::ui::tabs::{exercise_progress_tab, measurements_tab, weight_progress_tab};
 use eframe::{App, egui};
 use rusqlite::Connection;
-use std::sync::{Arc, Mutex};
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use chrono::NaiveDate;
 use std::sync::mpsc;
-use log::error;
+use log::{error, info};
 
 // Forward declare UI modules that will be called by impl App for MyApp
 // This assumes a src/ui/mod.rs will exist and declare these submodules.
 // pub mod ui; // This might be better in main.rs or lib.rs
 
+// How many weigh-ins the log weight tab lists (and so can edit or delete).
+pub(crate) const RECENT_WEIGHT_LOG_LIMIT: i64 = 14;
+// How many measurements the measurements tab lists.
+pub(crate) const RECENT_MEASUREMENT_LIMIT: i64 = 30;
+// Destructive weight log changes remembered for undo.
+pub(crate) const WEIGHT_LOG_UNDO_LIMIT: usize = 10;
+
 pub struct MyApp {
     pub(crate) active_tab: Tab,
-    pub(crate) db_conn: Option<Arc<Mutex<Connection>>>,
-    pub(crate) log_weight_input_lbs: String,
+    pub(crate) db: Option<Database>,
+    // Where the open database lives; snapshots go in a folder beside it.
+    pub(crate) db_path: Option<PathBuf>,
+    pub(crate) log_weight_input: String,
     pub(crate) show_diet_cycle_popup: bool,
+    pub(crate) pending_legacy_db: Option<LegacyDbMigration>,
     pub(crate) new_diet_phase: DietPhase,
     pub(crate) new_diet_start_date: String, 
     pub(crate) new_diet_planned_end_date: String,
     pub(crate) active_diet_cycle_id: Option<i64>,
+    pub(crate) new_diet_end_previous: bool,
+    pub(crate) new_diet_calorie_target: String,
+    pub(crate) diet_cycles: Vec<DietCycleSummary>,
+    pub(crate) editing_diet_cycle: Option<DietCycleEdit>,
+    pub(crate) end_diet_cycle_date: NaiveDate,
     pub(crate) log_exercise_date: NaiveDate,
+    pub(crate) current_exercises_log: Vec<ExerciseLogEntry>,
+    pub(crate) workout_notes: String,
+    pub(crate) workout_templates: Vec<WorkoutTemplate>,
+    pub(crate) selected_template_id: Option<i64>,
+    pub(crate) new_template_name: String,
+    // Kept after logging so the workout can still be saved as a template.
+    pub(crate) last_logged_workout: Vec<NewExercise>,
     pub(crate) selected_weigh_in_date: NaiveDate, // Added for weigh-in date picker
+    // Exercises that aren't archived, for name autocomplete.
     pub(crate) all_exercises_for_dropdown: Vec<(i64, String)>,
+    // Every exercise with its library attributes, archived ones included.
+    pub(crate) exercise_library: Vec<Exercise>,
+    pub(crate) library_filter: ExerciseFilter,
+    // The exercise being created (id 0) or edited in the library.
+    pub(crate) editing_exercise: Option<Exercise>,
+    pub(crate) new_alias: String,
+    pub(crate) merge_into: Option<i64>,
+    // (from, into), waiting for confirmation.
+    pub(crate) pending_merge: Option<(i64, i64)>,
+    // None until Find Duplicates is pressed.
+    pub(crate) duplicate_suggestions: Option<Vec<DuplicateSuggestion>>,
+    pub(crate) log_exercise_filter: ExerciseFilter,
+    pub(crate) log_exercise_pick: Option<i64>,
+    pub(crate) exercise_progress_filter: ExerciseFilter,
+    pub(crate) records_filter: ExerciseFilter,
     pub(crate) status_message: String,
     pub(crate) last_status_time: Instant,
-    pub(crate) recent_weight_logs: Vec<(String, f64)>,
+    pub(crate) recent_weight_logs: Vec<WeightLog>,
+    pub(crate) editing_weight_log: Option<WeightLogEdit>,
+    pub(crate) pending_weight_log_delete: Option<WeightLog>,
+    pub(crate) pending_duplicate_weigh_in: Option<PendingDuplicateWeighIn>,
+    pub(crate) weight_log_undo_stack: Vec<WeightLogUndo>,
+    pub(crate) nutrition_input: NutritionInput,
+    // Calories per day, plotted by day x.
+    pub(crate) intake_series: Vec<(f64, f64)>,
+    pub(crate) tdee_series: Vec<(f64, f64)>,
+    // (start x, end x, kcal) for every cycle with a calorie target.
+    pub(crate) calorie_target_lines: Vec<(f64, f64, f64)>,
+    pub(crate) active_calorie_target: Option<i64>,
     pub(crate) previous_active_tab: Option<Tab>, // Added to track tab changes for date reset, made pub(crate)
+    pub(crate) weight_progress_filter: WeightProgressFilter,
+    pub(crate) weight_progress_view: WeightProgressView,
+    pub(crate) weight_progress_data: Vec<(f64, f64)>,
+    pub(crate) smoothed_weight_progress_data: Vec<(f64, f64)>,
+    pub(crate) weight_phase_spans: Vec<PhaseSpan>,
+    pub(crate) diet_cycle_choices: Vec<(i64, String)>,
+    pub(crate) weight_smoothing: SmoothingMethod,
+    pub(crate) weight_smoothing_window: usize,
+    pub(crate) exercise_progress_selected_exercise_id: Option<i64>,
+    pub(crate) selected_exercise_metric: ExerciseMetric,
+    pub(crate) exercise_metric_weight_lbs: f64,
+    pub(crate) exercise_progress_data: Vec<(f64, f64)>,
+    pub(crate) smoothed_exercise_progress_data: Vec<(f64, f64)>,
+    pub(crate) exercise_progress_show_raw: bool,
+    pub(crate) exercise_smoothing: SmoothingMethod,
+    pub(crate) exercise_smoothing_window: usize,
+    pub(crate) measurement_date: NaiveDate,
+    // One text buffer per `MeasurementKind::ALL` entry.
+    pub(crate) measurement_inputs: [String; MeasurementKind::ALL.len()],
+    pub(crate) recent_measurements: Vec<Measurement>,
+    pub(crate) latest_measurements: Vec<Measurement>,
+    pub(crate) latest_weight_lbs: Option<f64>,
+    pub(crate) measurement_chart_kind: MeasurementKind,
+    pub(crate) measurement_chart_data: Vec<(f64, f64)>,
+    pub(crate) measurement_phase_spans: Vec<PhaseSpan>,
+    pub(crate) current_personal_records: Vec<PersonalRecord>,
+    pub(crate) personal_record_history: Vec<PersonalRecord>,
+    pub(crate) records_selected_exercise_id: Option<i64>,
+    // Folder the data tab exports into and imports a whole dump from.
+    pub(crate) csv_dir: String,
+    pub(crate) csv_import: CsvImportState,
+    pub(crate) csv_dir_reports: Vec<ImportReport>,
+    pub(crate) app_import: AppImportState,
+    pub(crate) backups: Vec<Snapshot>,
+    pub(crate) restore_path: String,
+    // A restore waiting on the user to confirm it.
+    pub(crate) pending_restore: Option<PathBuf>,
+    pub(crate) dashboard: DashboardData,
+    pub(crate) customizing_dashboard: bool,
+    // Settings being dragged or typed into, saved once the edit ends.
+    pub(crate) settings_draft: Option<Settings>,
+    pub(crate) timer: Option<Timer>,
+    // Shown in the timer panel until dismissed or another timer starts.
+    pub(crate) timer_alert: Option<String>,
+    // What the timer panel's Start button runs.
+    pub(crate) timer_mode: TimerMode,
+    pub(crate) emom_minutes: u32,
+    pub(crate) custom_intervals: IntervalPlan,
+    pub(crate) settings: Settings,
     pub(crate) console_messages: Vec<String>,
     pub(crate) log_receiver: mpsc::Receiver<String>,
 }
@@ -36,19 +156,95 @@ impl Default for MyApp {
         Self {
             active_tab: Tab::default(),
             previous_active_tab: None, // Initialize previous_active_tab
-            db_conn: None,
-            log_weight_input_lbs: String::default(),
+            db: None,
+            db_path: None,
+            log_weight_input: String::default(),
             show_diet_cycle_popup: false,
+            pending_legacy_db: None,
             new_diet_phase: DietPhase::default(),
             new_diet_start_date: String::default(),
             new_diet_planned_end_date: String::default(),
             active_diet_cycle_id: None,
+            new_diet_end_previous: true,
+            new_diet_calorie_target: String::default(),
+            diet_cycles: Vec::default(),
+            editing_diet_cycle: None,
+            end_diet_cycle_date: chrono::Local::now().date_naive(),
             log_exercise_date: chrono::Local::now().date_naive(),
+            current_exercises_log: Vec::default(),
+            workout_notes: String::default(),
+            workout_templates: Vec::default(),
+            selected_template_id: None,
+            new_template_name: String::default(),
+            last_logged_workout: Vec::default(),
             selected_weigh_in_date: chrono::Local::now().date_naive(), // Initialize selected_weigh_in_date
             all_exercises_for_dropdown: Vec::default(),
+            exercise_library: Vec::default(),
+            library_filter: ExerciseFilter::default(),
+            editing_exercise: None,
+            new_alias: String::default(),
+            merge_into: None,
+            pending_merge: None,
+            duplicate_suggestions: None,
+            log_exercise_filter: ExerciseFilter::default(),
+            log_exercise_pick: None,
+            exercise_progress_filter: ExerciseFilter::default(),
+            records_filter: ExerciseFilter::default(),
             status_message: String::default(),
             last_status_time: Instant::now(),
             recent_weight_logs: Vec::default(),
+            editing_weight_log: None,
+            pending_weight_log_delete: None,
+            pending_duplicate_weigh_in: None,
+            weight_log_undo_stack: Vec::default(),
+            nutrition_input: NutritionInput::default(),
+            intake_series: Vec::default(),
+            tdee_series: Vec::default(),
+            calorie_target_lines: Vec::default(),
+            active_calorie_target: None,
+            weight_progress_filter: WeightProgressFilter::default(),
+            weight_progress_view: WeightProgressView::default(),
+            weight_progress_data: Vec::default(),
+            smoothed_weight_progress_data: Vec::default(),
+            weight_phase_spans: Vec::default(),
+            diet_cycle_choices: Vec::default(),
+            weight_smoothing: SmoothingMethod::default(),
+            weight_smoothing_window: smoothing::DEFAULT_WINDOW_DAYS,
+            exercise_progress_selected_exercise_id: None,
+            selected_exercise_metric: ExerciseMetric::default(),
+            exercise_metric_weight_lbs: 135.0,
+            exercise_progress_data: Vec::default(),
+            smoothed_exercise_progress_data: Vec::default(),
+            exercise_progress_show_raw: true,
+            exercise_smoothing: SmoothingMethod::default(),
+            exercise_smoothing_window: smoothing::DEFAULT_WINDOW_DAYS,
+            measurement_date: chrono::Local::now().date_naive(),
+            measurement_inputs: Default::default(),
+            recent_measurements: Vec::default(),
+            latest_measurements: Vec::default(),
+            latest_weight_lbs: None,
+            measurement_chart_kind: MeasurementKind::default(),
+            measurement_chart_data: Vec::default(),
+            measurement_phase_spans: Vec::default(),
+            current_personal_records: Vec::default(),
+            personal_record_history: Vec::default(),
+            records_selected_exercise_id: None,
+            csv_dir: String::default(),
+            csv_import: CsvImportState::default(),
+            csv_dir_reports: Vec::default(),
+            app_import: AppImportState::default(),
+            backups: Vec::default(),
+            restore_path: String::default(),
+            pending_restore: None,
+            dashboard: DashboardData::default(),
+            customizing_dashboard: false,
+            settings_draft: None,
+            timer: None,
+            timer_alert: None,
+            timer_mode: TimerMode::default(),
+            emom_minutes: 10,
+            custom_intervals: IntervalPlan::default(),
+            settings: Settings::default(),
             console_messages: Vec::default(),
             log_receiver: mpsc::channel().1, // Dummy receiver for Default
         }
@@ -63,49 +259,112 @@ impl App for MyApp {
 
         // Call the diet cycle popup renderer from the ui module
         crate::ui::popups::diet_cycle_popup::render(self, ctx);
+        crate::ui::popups::legacy_db_popup::render(self, ctx);
 
+        if self.active_tab == Tab::Dashboard && self.previous_active_tab != Some(Tab::Dashboard) {
+            self.fetch_dashboard();
+        }
         // Logic to reset weigh-in date when LogWeight tab becomes active
-        if self.active_tab == Tab::LogWeight {
-            if self.previous_active_tab.is_none() || self.previous_active_tab != Some(Tab::LogWeight) {
-                self.selected_weigh_in_date = chrono::Local::now().date_naive();
-            }
+        if self.active_tab == Tab::LogWeight && self.previous_active_tab != Some(Tab::LogWeight) {
+            self.selected_weigh_in_date = chrono::Local::now().date_naive();
+            self.fetch_energy_data();
+        }
+        if self.active_tab == Tab::Measurements && self.previous_active_tab != Some(Tab::Measurements) {
+            self.measurement_date = chrono::Local::now().date_naive();
+            self.fetch_measurements();
+        }
+        if self.active_tab == Tab::LogExercise && self.previous_active_tab != Some(Tab::LogExercise) {
+            self.fetch_workout_templates();
+        }
+        // Reload chart data whenever the weight progress tab is opened
+        if self.active_tab == Tab::WeightProgress && self.previous_active_tab != Some(Tab::WeightProgress) {
+            self.fetch_weight_progress_data();
+            self.fetch_energy_data();
+        }
+        if self.active_tab == Tab::ExerciseProgress && self.previous_active_tab != Some(Tab::ExerciseProgress) {
+            self.fetch_exercise_progress_data();
+        }
+        if self.active_tab == Tab::Records && self.previous_active_tab != Some(Tab::Records) {
+            self.fetch_personal_records();
+        }
+        if self.active_tab == Tab::Exercises && self.previous_active_tab != Some(Tab::Exercises) {
+            self.fetch_exercises_for_dropdown();
+        }
+        if self.active_tab == Tab::DietCycles && self.previous_active_tab != Some(Tab::DietCycles) {
+            self.end_diet_cycle_date = chrono::Local::now().date_naive();
+            self.fetch_diet_cycles();
+        }
+        if self.active_tab == Tab::Data && self.previous_active_tab != Some(Tab::Data) {
+            self.fetch_backups();
+        }
+        // Save a setting that was still being edited when its tab was left.
+        if self.active_tab != Tab::Settings
+            && let Some(draft) = self.settings_draft.take()
+        {
+            self.apply_settings(ctx, draft);
         }
         self.previous_active_tab = Some(self.active_tab);
 
 
+        // Bottom panels go in before the central panel; the timer sits above the console.
+        crate::ui::console::render(self, ctx);
+        crate::ui::timer_panel::render(self, ctx);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("LiftMetrics");
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.active_tab, Tab::Dashboard, Tab::Dashboard.to_string());
                 ui.selectable_value(&mut self.active_tab, Tab::LogWeight, Tab::LogWeight.to_string());
+                ui.selectable_value(&mut self.active_tab, Tab::Measurements, Tab::Measurements.to_string());
                 ui.selectable_value(&mut self.active_tab, Tab::LogExercise, Tab::LogExercise.to_string());
                 ui.selectable_value(&mut self.active_tab, Tab::WeightProgress, Tab::WeightProgress.to_string());
                 ui.selectable_value(&mut self.active_tab, Tab::ExerciseProgress, Tab::ExerciseProgress.to_string());
+                ui.selectable_value(&mut self.active_tab, Tab::Records, Tab::Records.to_string());
+                ui.selectable_value(&mut self.active_tab, Tab::Exercises, Tab::Exercises.to_string());
+                ui.selectable_value(&mut self.active_tab, Tab::DietCycles, "Diet Cycles");
+                ui.selectable_value(&mut self.active_tab, Tab::Data, Tab::Data.to_string());
+                ui.selectable_value(&mut self.active_tab, Tab::Settings, Tab::Settings.to_string());
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    crate::ui::unit_picker::render(self, ui);
+                });
             });
             ui.add_space(10.0);
             ui.separator();
             ui.add_space(10.0);
 
             match self.active_tab {
+                Tab::Dashboard => crate::ui::tabs::dashboard_tab::render(self, ui, ctx),
                 Tab::LogWeight => {
                     // selected_weigh_in_date is already reset if tab just became active
                     crate::ui::tabs::log_weight_tab::render(self, ui, ctx);
                 }
+                Tab::Measurements => crate::ui::tabs::measurements_tab::render(self, ui, ctx),
                 Tab::LogExercise => crate::ui::tabs::log_exercise_tab::render(self, ui, ctx),
                 Tab::WeightProgress => crate::ui::tabs::weight_progress_tab::render(self, ui, ctx),
                 Tab::ExerciseProgress => crate::ui::tabs::exercise_progress_tab::render(self, ui, ctx),
+                Tab::Records => crate::ui::tabs::records_tab::render(self, ui, ctx),
+                Tab::Exercises => crate::ui::tabs::exercises_tab::render(self, ui, ctx),
+                Tab::DietCycles => crate::ui::tabs::diet_cycles_tab::render(self, ui, ctx),
+                Tab::Data => crate::ui::tabs::data_tab::render(self, ui, ctx),
+                Tab::Settings => crate::ui::tabs::settings_tab::render(self, ui, ctx),
             }
         });
 
-        // Render the console at the bottom
-        crate::ui::console::render(self, ctx);
-
         // Receive and append log messages from the channel
         while let Ok(message) = self.log_receiver.try_recv() {
             self.console_messages.push(message);
         }
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if self.db.is_some()
+            && let Err(e) = self.back_up(BackupReason::Exit)
+        {
+            error!("failed to back up the database: {}", e);
+        }
+    }
 }
 
 impl MyApp {
@@ -118,56 +377,424 @@ impl MyApp {
         }
     }
 
+    // Pushes a "[STATUS]" line to the console, like the tabs do inline.
+    pub(crate) fn push_status(&mut self, message: impl AsRef<str>) {
+        self.console_messages.push(format!("[STATUS] {}\n", message.as_ref()));
+        self.last_status_time = Instant::now();
+    }
+
+    // Starts a rest countdown for the exercise (matched by name or alias),
+    // using its own rest time or the default. A running interval timer is
+    // left alone.
+    pub(crate) fn start_rest_timer(&mut self, exercise_name: &str) {
+        if self.timer.as_ref().is_some_and(|t| t.mode != TimerMode::Rest && !t.status(Instant::now()).finished) {
+            return;
+        }
+        let name = exercise_name.trim();
+        let exercise = self.exercise_library.iter().find(|e| {
+            e.name.eq_ignore_ascii_case(name) || e.aliases.iter().any(|a| a.eq_ignore_ascii_case(name))
+        });
+        let secs = exercise.and_then(|e| e.rest_secs).unwrap_or(self.settings.default_rest_secs);
+        let label = match exercise.map_or(name, |e| e.name.as_str()) {
+            "" => "Rest timer".to_string(),
+            name => format!("{} rest", name),
+        };
+        self.timer = Some(Timer::rest(label, secs, Instant::now()));
+        self.timer_alert = None;
+    }
+
+    // Runs `f` with the locked connection, flattening lock and database
+    // failures into a user-facing message.
+    pub(crate) fn with_db<T>(&self, f: impl FnOnce(&mut Connection) -> DbResult<T>) -> Result<T, String> {
+        let Some(db) = &self.db else {
+            return Err("no database connection.".to_string());
+        };
+        db.with_conn(f).map_err(|e| e.to_string())
+    }
+
+    // Like with_db, for CSV operations that can also fail on the file itself.
+    pub(crate) fn with_csv<T>(&self, f: impl FnOnce(&mut Connection) -> Result<T, CsvError>) -> Result<T, String> {
+        self.with_db(|conn| Ok(f(conn))).and_then(|result| result.map_err(|e| e.to_string()))
+    }
+
     pub(crate) fn fetch_recent_weight_logs(&mut self) {
-        if let Some(active_cycle_id) = self.active_diet_cycle_id {
-            if let Some(conn_mutex) = &self.db_conn {
-                if let Ok(conn) = conn_mutex.lock() {
-                    let mut stmt = match conn.prepare(
-                        "SELECT log_date, weight_lbs FROM weight_logs 
-                         WHERE diet_cycle_id = ?1 
-                         ORDER BY log_date DESC, id DESC LIMIT 5",
-                    ) {
-                        Ok(s) => s,
-                        Err(e) => {
-                            error!("failed to prepare statement for recent logs: {}", e);
-                            self.status_message = format!("error preparing to fetch recent logs: {}", e);
-                            self.last_status_time = Instant::now();
-                            return;
-                        }
-                    };
-                    let logs_iter = match stmt.query_map(rusqlite::params![active_cycle_id], |row| {
-                        Ok((row.get(0)?, row.get(1)?))
-                    }) {
-                        Ok(iter) => iter,
-                        Err(e) => {
-                            error!("failed to query recent logs: {}", e);
-                            self.status_message = format!("error fetching recent logs: {}", e);
-                            self.last_status_time = Instant::now();
-                            return;
-                        }
-                    };
-
-                    let mut new_logs = Vec::new();
-                    for log_result in logs_iter {
-                        match log_result {
-                            Ok(log_entry) => {
-                                new_logs.push(log_entry);
-                            }
-                            Err(e) => {
-                                error!("error processing recent log row: {}", e);
-                                self.status_message = format!("error processing recent log: {}", e);
-                                self.last_status_time = Instant::now();
-                            }
-                        }
+        let Some(active_cycle_id) = self.active_diet_cycle_id else {
+            self.recent_weight_logs.clear();
+            return;
+        };
+        match self.with_db(|conn| db::weight_logs::recent_for_cycle(conn, active_cycle_id, RECENT_WEIGHT_LOG_LIMIT)) {
+            Ok(logs) => self.recent_weight_logs = logs,
+            Err(e) => {
+                error!("failed to load recent weight logs: {}", e);
+                self.status_message = format!("error fetching recent logs: {}", e);
+                self.last_status_time = Instant::now();
+            }
+        }
+    }
+
+    pub(crate) fn fetch_exercises_for_dropdown(&mut self) {
+        match self.with_db(|conn| db::exercises::list(conn)) {
+            Ok(exercises) => {
+                self.all_exercises_for_dropdown =
+                    exercises.iter().filter(|e| !e.is_archived).map(|e| (e.id, e.name.clone())).collect();
+                self.exercise_library = exercises;
+            }
+            Err(e) => error!("failed to load exercises: {}", e),
+        }
+    }
+
+    pub(crate) fn fetch_dashboard(&mut self) {
+        let (method, window) = (self.settings.smoothing, self.settings.smoothing_window);
+        let today = chrono::Local::now().date_naive();
+        match self.with_db(|conn| dashboard::load(conn, method, window, today)) {
+            Ok(data) => self.dashboard = data,
+            Err(e) => error!("failed to load the dashboard: {}", e),
+        }
+    }
+
+    // Reloads weigh-ins, cycle shading and the cycle chooser for the weight chart.
+    pub(crate) fn fetch_weight_progress_data(&mut self) {
+        // `None` when the active cycle is asked for but there isn't one; the
+        // cycles are still loaded so a past one can be picked instead.
+        let cycle_filter = match self.weight_progress_filter {
+            WeightProgressFilter::ActiveCycle => self.active_diet_cycle_id.map(Some),
+            WeightProgressFilter::Cycle(id) => Some(Some(id)),
+            WeightProgressFilter::AllTime => Some(None),
+        };
+
+        let result = self.with_db(|conn| {
+            let logs = match cycle_filter {
+                Some(cycle_id) => db::weight_logs::list(conn, cycle_id)?,
+                None => Vec::new(),
+            };
+            let cycles = db::diet_cycles::list(conn)?;
+            Ok((logs, cycles))
+        });
+
+        match result {
+            Ok((logs, cycles)) => {
+                let series = weight_progress_tab::weight_series(&logs, self.settings.bodyweight_unit);
+                self.smoothed_weight_progress_data =
+                    smoothing::smooth(&series, self.weight_smoothing, self.weight_smoothing_window);
+                self.weight_progress_data = series;
+                self.weight_phase_spans = weight_progress_tab::phase_spans(&cycles, chrono::Local::now().date_naive());
+                self.diet_cycle_choices = weight_progress_tab::cycle_choices(&cycles);
+            }
+            Err(e) => error!("failed to load weight progress: {}", e),
+        }
+    }
+
+    // Rebuilds the intake and TDEE series from every weigh-in and food log,
+    // plus the calorie targets of each cycle.
+    pub(crate) fn fetch_energy_data(&mut self) {
+        let result = self.with_db(|conn| {
+            let logs = db::weight_logs::list(conn, None)?;
+            let intake = db::nutrition::list(conn)?;
+            let cycles = db::diet_cycles::list(conn)?;
+            Ok((logs, intake, cycles))
+        });
+        match result {
+            Ok((logs, intake, cycles)) => {
+                let weights = weight_progress_tab::weight_series(&logs, WeightUnit::Lbs);
+                let trend = smoothing::smooth(&weights, self.settings.smoothing, self.settings.smoothing_window);
+                self.intake_series = weight_progress_tab::intake_series(&intake);
+                self.tdee_series = tdee::estimate_series(&trend, &self.intake_series);
+                let today = chrono::Local::now().date_naive();
+                self.calorie_target_lines = weight_progress_tab::calorie_target_lines(&cycles, today);
+                self.active_calorie_target = cycles.iter().find(|c| c.is_active).and_then(|c| c.calorie_target);
+            }
+            Err(e) => error!("failed to load nutrition data: {}", e),
+        }
+    }
+
+    // Switches the chart metric to the selected exercise's `default_metric_to_track`.
+    pub(crate) fn apply_default_metric_for_selected_exercise(&mut self) {
+        let Some(exercise_id) = self.exercise_progress_selected_exercise_id else { return };
+        match self.with_db(|conn| db::exercises::get(conn, exercise_id)) {
+            Ok(Some(exercise)) => {
+                if let Some(metric) = exercise.default_metric {
+                    self.selected_exercise_metric = metric;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => error!("failed to load default metric: {}", e),
+        }
+    }
+
+    // Recomputes the per-session metric series for the selected exercise.
+    pub(crate) fn fetch_exercise_progress_data(&mut self) {
+        let Some(exercise_id) = self.exercise_progress_selected_exercise_id else {
+            self.exercise_progress_data.clear();
+            self.smoothed_exercise_progress_data.clear();
+            return;
+        };
+
+        match self.with_db(|conn| db::workouts::exercise_history(conn, exercise_id)) {
+            Ok(sets) => {
+                let mut series = exercise_progress_tab::aggregate_metric(
+                    &sets,
+                    self.selected_exercise_metric,
+                    self.exercise_metric_weight_lbs,
+                    self.settings.e1rm_formula,
+                );
+                if self.selected_exercise_metric.is_weight() {
+                    for point in &mut series {
+                        point.1 = self.settings.lift_unit.convert_lbs(point.1);
                     }
-                    self.recent_weight_logs = new_logs;
-                } else {
-                    self.status_message = "failed to acquire db lock for recent logs.".to_string();
-                    self.last_status_time = Instant::now();
                 }
+                self.smoothed_exercise_progress_data =
+                    smoothing::smooth(&series, self.exercise_smoothing, self.exercise_smoothing_window);
+                self.exercise_progress_data = series;
+            }
+            Err(e) => error!("failed to load exercise progress: {}", e),
+        }
+    }
+
+    // Reloads standing records and the history timeline for the records tab.
+    pub(crate) fn fetch_personal_records(&mut self) {
+        let selected = self.records_selected_exercise_id;
+        let result = self.with_db(|conn| {
+            let current = records::load_current(conn)?;
+            let history = records::load_history(conn, selected, 200)?;
+            Ok((current, history))
+        });
+
+        match result {
+            Ok((current, history)) => {
+                self.current_personal_records = current;
+                self.personal_record_history = history;
+            }
+            Err(e) => error!("failed to load personal records: {}", e),
+        }
+    }
+
+    // Opens the database at `path` and loads the state every tab starts from.
+    pub(crate) fn open_database(&mut self, path: &Path) -> Result<(), String> {
+        let conn = db_init::open(path).map_err(|e| e.to_string())?;
+        info!("using database {}", path.display());
+        self.db = Some(Database::new(conn));
+        self.db_path = Some(path.to_path_buf());
+        self.csv_dir = crate::csv_io::default_dir(path).display().to_string();
+        match self.with_db(catalog::seed_if_new) {
+            Ok(Some(report)) => info!("seeded {} exercises from the built-in catalog", report.added),
+            Ok(None) => {}
+            Err(e) => error!("failed to seed the exercise catalog: {}", e),
+        }
+        self.reload_from_db();
+        if let Err(e) = self.back_up(BackupReason::Startup) {
+            error!("failed to back up the database: {}", e);
+        }
+        self.check_integrity();
+        Ok(())
+    }
+
+    // Loads everything that's read once rather than on tab entry.
+    fn reload_from_db(&mut self) {
+        self.load_settings();
+        self.fetch_active_diet_cycle();
+        self.fetch_exercises_for_dropdown();
+        self.fetch_workout_templates();
+        self.fetch_recent_weight_logs();
+        // The dashboard may already be showing, so it won't be fetched on tab entry.
+        self.fetch_dashboard();
+        // Imported session ids point into the database that was open before.
+        self.csv_import.session_ids.clear();
+    }
+
+    fn backup_dir(&self) -> Result<PathBuf, String> {
+        self.db_path.as_deref().map(backup::dir_for).ok_or_else(|| "no database connection.".to_string())
+    }
+
+    // Snapshots the database and rotates old snapshots of the same kind.
+    pub(crate) fn back_up(&mut self, reason: BackupReason) -> Result<PathBuf, String> {
+        let dir = self.backup_dir()?;
+        let now = chrono::Local::now().naive_local();
+        let path = self
+            .with_db(|conn| Ok(backup::snapshot(conn, &dir, reason, now)))?
+            .map_err(|e| e.to_string())?;
+        match backup::rotate(&dir, self.settings.backup_keep) {
+            Ok(removed) if removed > 0 => info!("removed {} old backups", removed),
+            Ok(_) => {}
+            Err(e) => error!("failed to rotate backups in {}: {}", dir.display(), e),
+        }
+        self.fetch_backups();
+        Ok(path)
+    }
+
+    pub(crate) fn fetch_backups(&mut self) {
+        let Ok(dir) = self.backup_dir() else { return };
+        match backup::list(&dir) {
+            Ok(backups) => self.backups = backups,
+            Err(e) => error!("failed to list backups in {}: {}", dir.display(), e),
+        }
+    }
+
+    // Runs `PRAGMA integrity_check` and reports the result in the console.
+    // Returns whether the database passed.
+    pub(crate) fn check_integrity(&mut self) -> bool {
+        match self.with_db(|conn| Ok(backup::integrity_check(conn)?)) {
+            Ok(report) if backup::is_healthy(&report) => {
+                info!("database integrity check: ok");
+                true
+            }
+            Ok(report) => {
+                for problem in &report {
+                    error!("database integrity check: {}", problem);
+                }
+                self.push_status(format!(
+                    "database integrity check found {} problems; restore a backup from the data tab.",
+                    report.len()
+                ));
+                false
+            }
+            Err(e) => {
+                error!("failed to run the integrity check: {}", e);
+                false
+            }
+        }
+    }
+
+    // Swaps the live database for `source` and reloads everything from it.
+    pub(crate) fn restore_backup(&mut self, source: &Path) -> Result<PathBuf, String> {
+        let dir = self.backup_dir()?;
+        let now = chrono::Local::now().naive_local();
+        let safety = self
+            .with_db(|conn| Ok(backup::restore(conn, source, &dir, now)))?
+            .map_err(|e| e.to_string())?;
+        self.reload_from_db();
+        self.fetch_diet_cycles();
+        self.fetch_energy_data();
+        self.fetch_backups();
+        Ok(safety)
+    }
+
+    // Applies the loaded theme and window size; the window opened with the old database's.
+    pub(crate) fn apply_window_settings(&self, ctx: &egui::Context) {
+        ctx.set_theme(self.settings.theme.preference());
+        ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(self.settings.window_width, self.settings.window_height)));
+    }
+
+    // Reads the stored settings and seeds the chart controls from them.
+    fn load_settings(&mut self) {
+        match self.with_db(|conn| db::settings::load(conn)) {
+            Ok(settings) => self.settings = settings,
+            Err(e) => error!("failed to load settings: {}", e),
+        }
+        log::set_max_level(self.settings.log_level);
+        self.reset_chart_smoothing();
+    }
+
+    fn reset_chart_smoothing(&mut self) {
+        self.weight_smoothing = self.settings.smoothing;
+        self.weight_smoothing_window = self.settings.smoothing_window;
+        self.exercise_smoothing = self.settings.smoothing;
+        self.exercise_smoothing_window = self.settings.smoothing_window;
+    }
+
+    // Saves `settings` and applies whatever changed without a restart.
+    pub(crate) fn apply_settings(&mut self, ctx: &egui::Context, mut settings: Settings) {
+        settings.sanitize();
+        if settings == self.settings {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.settings, settings);
+        let current = self.settings.clone();
+        if let Err(e) = self.with_db(|conn| db::settings::save(conn, &current)) {
+            error!("failed to save settings: {}", e);
+        }
+
+        if current.theme != previous.theme {
+            ctx.set_theme(current.theme.preference());
+        }
+        if current.log_level != previous.log_level {
+            log::set_max_level(current.log_level);
+        }
+        if (current.window_width, current.window_height) != (previous.window_width, previous.window_height) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(egui::vec2(current.window_width, current.window_height)));
+        }
+        let smoothing_changed = (current.smoothing, current.smoothing_window) != (previous.smoothing, previous.smoothing_window);
+        if smoothing_changed {
+            self.reset_chart_smoothing();
+        }
+        if (current.lift_unit, current.bodyweight_unit) != (previous.lift_unit, previous.bodyweight_unit) {
+            // The edit form holds text typed in the old unit.
+            self.editing_weight_log = None;
+        }
+        // Suggestions depend on the scheme, formula and unit they were worded in.
+        let suggestion_inputs = |s: &Settings| (s.progression_scheme, s.progression_increment_lbs, s.e1rm_formula, s.lift_unit);
+        if suggestion_inputs(&current) != suggestion_inputs(&previous) {
+            for entry in &mut self.current_exercises_log {
+                entry.suggestion_for = None;
+            }
+        }
+        if smoothing_changed || current.bodyweight_unit != previous.bodyweight_unit {
+            self.fetch_weight_progress_data();
+        }
+        if smoothing_changed {
+            self.fetch_energy_data();
+        }
+        if smoothing_changed || (current.lift_unit, current.e1rm_formula) != (previous.lift_unit, previous.e1rm_formula) {
+            self.fetch_exercise_progress_data();
+        }
+        if current.length_unit != previous.length_unit {
+            self.fetch_measurements();
+        }
+    }
+
+    // Saves the unit choice and redraws the charts in it.
+    pub(crate) fn set_units(&mut self, ctx: &egui::Context, lift_unit: WeightUnit, bodyweight_unit: WeightUnit) {
+        let settings = Settings { lift_unit, bodyweight_unit, ..self.settings.clone() };
+        self.apply_settings(ctx, settings);
+    }
+
+    // Reads the cycle flagged active; weigh-ins are attached to it.
+    pub(crate) fn fetch_active_diet_cycle(&mut self) {
+        match self.with_db(|conn| db::diet_cycles::active_id(conn)) {
+            Ok(id) => self.active_diet_cycle_id = id,
+            Err(e) => error!("failed to load active diet cycle: {}", e),
+        }
+    }
+
+    // Reloads the measurement list, the latest values with the latest weigh-in
+    // for derived metrics, and the chart for the selected measurement.
+    pub(crate) fn fetch_measurements(&mut self) {
+        let kind = self.measurement_chart_kind;
+        let result = self.with_db(|conn| {
+            let recent = db::measurements::recent(conn, RECENT_MEASUREMENT_LIMIT)?;
+            let latest = db::measurements::latest_each(conn)?;
+            let weight = db::weight_logs::latest(conn)?.map(|log| log.weight_lbs);
+            let history = db::measurements::list(conn, kind)?;
+            let cycles = db::diet_cycles::list(conn)?;
+            Ok((recent, latest, weight, history, cycles))
+        });
+        match result {
+            Ok((recent, latest, weight, history, cycles)) => {
+                self.recent_measurements = recent;
+                self.latest_measurements = latest;
+                self.latest_weight_lbs = weight;
+                self.measurement_chart_data = measurements_tab::measurement_series(&history, self.settings.length_unit);
+                self.measurement_phase_spans = weight_progress_tab::phase_spans(&cycles, chrono::Local::now().date_naive());
+            }
+            Err(e) => error!("failed to load measurements: {}", e),
+        }
+    }
+
+    pub(crate) fn fetch_workout_templates(&mut self) {
+        match self.with_db(|conn| db::templates::list(conn)) {
+            Ok(templates) => {
+                if !templates.iter().any(|t| Some(t.id) == self.selected_template_id) {
+                    self.selected_template_id = templates.first().map(|t| t.id);
+                }
+                self.workout_templates = templates;
             }
-        } else {
-             self.recent_weight_logs.clear();
+            Err(e) => error!("failed to load workout templates: {}", e),
+        }
+    }
+
+    pub(crate) fn fetch_diet_cycles(&mut self) {
+        match self.with_db(|conn| db::diet_cycles::list_summaries(conn)) {
+            Ok(cycles) => self.diet_cycles = cycles,
+            Err(e) => error!("failed to load diet cycles: {}", e),
         }
     }
 }
@@ -0,0 +1,329 @@
+// Database snapshots taken with SQLite's online backup API, so a copy is
+// consistent even while the app holds the connection. Snapshots live in a
+// `backups` folder next to the database and are rotated per kind.
+use chrono::NaiveDateTime;
+use log::info;
+use rusqlite::backup::Progress;
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::migrations::{self, MigrationError};
+
+pub const BACKUP_DIR_NAME: &str = "backups";
+const FILE_PREFIX: &str = "liftmetrics-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+// Every schema version, including pre-migration files, has these.
+const REQUIRED_TABLES: [&str; 5] = ["diet_cycles", "exercise_sets", "exercises", "weight_logs", "workout_sessions"];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum BackupReason {
+    Startup,
+    Exit,
+    PreMigration,
+    PreRestore,
+    Manual,
+}
+
+impl BackupReason {
+    pub const ALL: [BackupReason; 5] = [
+        BackupReason::Startup,
+        BackupReason::Exit,
+        BackupReason::PreMigration,
+        BackupReason::PreRestore,
+        BackupReason::Manual,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            BackupReason::Startup => "Startup",
+            BackupReason::Exit => "Exit",
+            BackupReason::PreMigration => "Before migration",
+            BackupReason::PreRestore => "Before restore",
+            BackupReason::Manual => "Manual",
+        }
+    }
+
+    // The suffix in the snapshot's file name.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            BackupReason::Startup => "startup",
+            BackupReason::Exit => "exit",
+            BackupReason::PreMigration => "pre-migration",
+            BackupReason::PreRestore => "pre-restore",
+            BackupReason::Manual => "manual",
+        }
+    }
+}
+
+impl fmt::Display for BackupReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for BackupReason {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BackupReason::ALL
+            .into_iter()
+            .find(|reason| reason.tag() == s)
+            .ok_or_else(|| format!("unknown backup kind: {}", s))
+    }
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    Io(io::Error),
+    Sqlite(rusqlite::Error),
+    // The file isn't a database this build can restore; the message is user-facing.
+    Invalid(String),
+    Migration(MigrationError),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::Io(e) => write!(f, "{}", e),
+            BackupError::Sqlite(e) => write!(f, "{}", e),
+            BackupError::Invalid(msg) => write!(f, "{}", msg),
+            BackupError::Migration(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<io::Error> for BackupError {
+    fn from(e: io::Error) -> Self {
+        BackupError::Io(e)
+    }
+}
+
+impl From<rusqlite::Error> for BackupError {
+    fn from(e: rusqlite::Error) -> Self {
+        BackupError::Sqlite(e)
+    }
+}
+
+impl From<MigrationError> for BackupError {
+    fn from(e: MigrationError) -> Self {
+        BackupError::Migration(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub taken_at: NaiveDateTime,
+    pub reason: BackupReason,
+    pub size_bytes: u64,
+}
+
+// The backups folder for the database at `db_path`.
+pub fn dir_for(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new("")).join(BACKUP_DIR_NAME)
+}
+
+fn file_name(taken_at: NaiveDateTime, reason: BackupReason) -> String {
+    format!("{}{}-{}.db", FILE_PREFIX, taken_at.format(TIMESTAMP_FORMAT), reason.tag())
+}
+
+// Reads "liftmetrics-20250305-183012-startup.db" back into its time and kind.
+fn parse_file_name(name: &str) -> Option<(NaiveDateTime, BackupReason)> {
+    let stem = name.strip_prefix(FILE_PREFIX)?.strip_suffix(".db")?;
+    let (timestamp, tag) = (stem.get(..15)?, stem.get(15..)?.strip_prefix('-')?);
+    Some((NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?, tag.parse().ok()?))
+}
+
+// Copies the live database into `dir`. Safe to call while `conn` is in use.
+pub fn snapshot(conn: &Connection, dir: &Path, reason: BackupReason, taken_at: NaiveDateTime) -> Result<PathBuf, BackupError> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join(file_name(taken_at, reason));
+    conn.backup(DatabaseName::Main, &path, None)?;
+    info!("backed up database to {}", path.display());
+    Ok(path)
+}
+
+// Every snapshot in `dir`, newest first. A missing folder has none.
+pub fn list(dir: &Path) -> io::Result<Vec<Snapshot>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut snapshots = Vec::new();
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some((taken_at, reason)) = name.to_str().and_then(parse_file_name) else { continue };
+        snapshots.push(Snapshot { path: entry.path(), taken_at, reason, size_bytes: entry.metadata()?.len() });
+    }
+    snapshots.sort_by(|a, b| b.taken_at.cmp(&a.taken_at).then_with(|| b.path.cmp(&a.path)));
+    Ok(snapshots)
+}
+
+// Deletes all but the newest `keep` snapshots of each kind, so frequent
+// startup copies never push out the rarer pre-migration ones. Returns how
+// many were removed.
+pub fn rotate(dir: &Path, keep: usize) -> io::Result<usize> {
+    let snapshots = list(dir)?;
+    let mut removed = 0;
+    for reason in BackupReason::ALL {
+        for old in snapshots.iter().filter(|s| s.reason == reason).skip(keep.max(1)) {
+            fs::remove_file(&old.path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// `PRAGMA integrity_check`; a healthy database reports the single line "ok".
+pub fn integrity_check(conn: &Connection) -> rusqlite::Result<Vec<String>> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check")?;
+    stmt.query_map([], |row| row.get(0))?.collect()
+}
+
+pub fn is_healthy(report: &[String]) -> bool {
+    report.len() == 1 && report[0] == "ok"
+}
+
+// Checks that `path` is an intact LiftMetrics database this build can open,
+// without modifying it. Returns its schema version.
+pub fn validate(path: &Path) -> Result<i64, BackupError> {
+    if !path.is_file() {
+        return Err(BackupError::Invalid(format!("{} is not a file.", path.display())));
+    }
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .map_err(|e| BackupError::Invalid(format!("{} can't be opened: {}", path.display(), e)))?;
+    let report =
+        integrity_check(&conn).map_err(|_| BackupError::Invalid(format!("{} is not a SQLite database.", path.display())))?;
+    if !is_healthy(&report) {
+        return Err(BackupError::Invalid(format!("{} failed the integrity check: {}", path.display(), report.join("; "))));
+    }
+
+    let version = migrations::current_version(&conn)?;
+    if version > migrations::latest_version() {
+        return Err(BackupError::Invalid(format!(
+            "{} has schema version {}, newer than this build supports ({}).",
+            path.display(),
+            version,
+            migrations::latest_version()
+        )));
+    }
+    let mut stmt = conn.prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?1")?;
+    let missing: Vec<&str> = REQUIRED_TABLES.into_iter().filter(|table| !stmt.exists([table]).unwrap_or(false)).collect();
+    if !missing.is_empty() {
+        return Err(BackupError::Invalid(format!(
+            "{} is not a LiftMetrics database (missing {}).",
+            path.display(),
+            missing.join(", ")
+        )));
+    }
+    Ok(version)
+}
+
+// Replaces the live database with `source` after validating it, snapshotting
+// the current data into `dir` first. Older backups are migrated forward.
+// Returns the pre-restore snapshot.
+pub fn restore(conn: &mut Connection, source: &Path, dir: &Path, taken_at: NaiveDateTime) -> Result<PathBuf, BackupError> {
+    validate(source)?;
+    let safety = snapshot(conn, dir, BackupReason::PreRestore, taken_at)?;
+    conn.restore(DatabaseName::Main, source, None::<fn(Progress)>)?;
+    let applied = migrations::run(conn)?;
+    info!("restored database from {} ({} migrations applied)", source.display(), applied);
+    Ok(safety)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::db::test_dir;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    fn exercise_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM exercises", [], |r| r.get(0)).unwrap()
+    }
+
+    #[test]
+    fn names_round_trip() {
+        let name = file_name(at(5, 18), BackupReason::PreMigration);
+        assert_eq!(name, "liftmetrics-20250305-180000-pre-migration.db");
+        assert_eq!(parse_file_name(&name), Some((at(5, 18), BackupReason::PreMigration)));
+        assert_eq!(parse_file_name("liftmetrics-20250305-180000-mystery.db"), None);
+        assert_eq!(parse_file_name("notes.txt"), None);
+    }
+
+    #[test]
+    fn rotates_each_kind_separately() {
+        let dir = test_dir("rotate");
+        let conn = crate::db::test_conn();
+        for day in 1..=4 {
+            snapshot(&conn, &dir, BackupReason::Startup, at(day, 8)).unwrap();
+        }
+        snapshot(&conn, &dir, BackupReason::PreMigration, at(1, 7)).unwrap();
+        fs::write(dir.join("unrelated.db"), "x").unwrap();
+
+        assert_eq!(rotate(&dir, 2).unwrap(), 2);
+        let kept: Vec<_> = list(&dir).unwrap().into_iter().map(|s| (s.taken_at, s.reason)).collect();
+        assert_eq!(
+            kept,
+            vec![(at(4, 8), BackupReason::Startup), (at(3, 8), BackupReason::Startup), (at(1, 7), BackupReason::PreMigration)]
+        );
+        assert!(dir.join("unrelated.db").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn restores_a_snapshot_over_newer_data() {
+        let dir = test_dir("restore");
+        let mut conn = crate::db_init::open(&dir.join("live.db")).unwrap();
+        conn.execute("INSERT INTO exercises (name) VALUES ('Squat')", []).unwrap();
+        let backup = snapshot(&conn, &dir, BackupReason::Manual, at(1, 9)).unwrap();
+        conn.execute("INSERT INTO exercises (name) VALUES ('Bench Press')", []).unwrap();
+        assert!(is_healthy(&integrity_check(&conn).unwrap()));
+
+        assert_eq!(validate(&backup).unwrap(), migrations::latest_version());
+        let safety = restore(&mut conn, &backup, &dir, at(2, 9)).unwrap();
+        assert_eq!(exercise_count(&conn), 1);
+        // The data being replaced is kept.
+        let before = Connection::open(&safety).unwrap();
+        assert_eq!(exercise_count(&before), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_files_that_are_not_backups() {
+        let dir = test_dir("validate");
+        let junk = dir.join("junk.db");
+        fs::write(&junk, "definitely not sqlite, but long enough to not be an empty database file").unwrap();
+        assert!(matches!(validate(&junk), Err(BackupError::Invalid(_))));
+        assert!(validate(&dir.join("missing.db")).is_err());
+
+        let other = dir.join("other.db");
+        Connection::open(&other).unwrap().execute_batch("CREATE TABLE notes (body TEXT);").unwrap();
+        assert!(validate(&other).unwrap_err().to_string().contains("missing"));
+
+        let newer = dir.join("newer.db");
+        let conn = crate::db_init::open(&newer).unwrap();
+        conn.pragma_update(None, "user_version", migrations::latest_version() + 1).unwrap();
+        drop(conn);
+        assert!(validate(&newer).unwrap_err().to_string().contains("newer"));
+
+        // A failed restore leaves the live data alone.
+        let mut live = crate::db_init::open(&dir.join("live.db")).unwrap();
+        live.execute("INSERT INTO exercises (name) VALUES ('Squat')", []).unwrap();
+        assert!(restore(&mut live, &other, &dir, at(1, 9)).is_err());
+        assert_eq!(exercise_count(&live), 1);
+        assert!(list(&dir).unwrap().is_empty());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,131 @@
+use crate::db::measurements::Measurement;
+use crate::types::MeasurementKind;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Only used to pick the Navy formula.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Sex {
+    #[default]
+    Male,
+    Female,
+}
+
+impl Sex {
+    pub const ALL: [Sex; 2] = [Sex::Male, Sex::Female];
+}
+
+impl Display for Sex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Sex {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Sex::ALL
+            .into_iter()
+            .find(|sex| sex.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown sex: {}", s))
+    }
+}
+
+// U.S. Navy circumference method, everything in inches. Women also need hips.
+// `None` when the inputs can't produce a sensible percentage.
+pub fn navy_body_fat(sex: Sex, height_in: f64, waist_in: f64, neck_in: f64, hips_in: Option<f64>) -> Option<f64> {
+    if height_in <= 0.0 {
+        return None;
+    }
+    let estimate = match sex {
+        Sex::Male => {
+            let girth = waist_in - neck_in;
+            if girth <= 0.0 {
+                return None;
+            }
+            86.010 * girth.log10() - 70.041 * height_in.log10() + 36.76
+        }
+        Sex::Female => {
+            let girth = waist_in + hips_in? - neck_in;
+            if girth <= 0.0 {
+                return None;
+            }
+            163.205 * girth.log10() - 97.684 * height_in.log10() - 78.387
+        }
+    };
+    (0.0..75.0).contains(&estimate).then_some(estimate)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BodyFatSource {
+    Logged,
+    Navy,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BodyComposition {
+    pub navy_body_fat: Option<f64>,
+    // The figure lean mass is worked from: a logged body fat percentage if
+    // there is one, otherwise the Navy estimate.
+    pub body_fat: Option<(f64, BodyFatSource)>,
+    pub lean_mass_lbs: Option<f64>,
+    pub fat_mass_lbs: Option<f64>,
+}
+
+// Derives body composition from the latest value of each measurement and the
+// latest weigh-in. A `height_in` of zero means it hasn't been set.
+pub fn derive(latest: &[Measurement], weight_lbs: Option<f64>, sex: Sex, height_in: f64) -> BodyComposition {
+    let value = |kind| latest.iter().find(|m| m.kind == kind).map(|m| m.value);
+    let navy = match (value(MeasurementKind::Waist), value(MeasurementKind::Neck)) {
+        (Some(waist), Some(neck)) => navy_body_fat(sex, height_in, waist, neck, value(MeasurementKind::Hips)),
+        _ => None,
+    };
+    let body_fat = value(MeasurementKind::BodyFat)
+        .map(|bf| (bf, BodyFatSource::Logged))
+        .or(navy.map(|bf| (bf, BodyFatSource::Navy)));
+    let fat_mass = weight_lbs.zip(body_fat).map(|(weight, (bf, _))| weight * bf / 100.0);
+    BodyComposition {
+        navy_body_fat: navy,
+        body_fat,
+        lean_mass_lbs: weight_lbs.zip(fat_mass).map(|(weight, fat)| weight - fat),
+        fat_mass_lbs: fat_mass,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(kind: MeasurementKind, value: f64) -> Measurement {
+        Measurement { id: 0, measured_on: "2025-03-01".into(), kind, value }
+    }
+
+    #[test]
+    fn navy_method_matches_reference_values() {
+        let male = navy_body_fat(Sex::Male, 70.0, 34.0, 15.0, None).unwrap();
+        assert!((male - 17.5).abs() < 0.1, "{}", male);
+        let female = navy_body_fat(Sex::Female, 65.0, 30.0, 13.0, Some(38.0)).unwrap();
+        assert!((female - 28.6).abs() < 0.1, "{}", female);
+        assert!(navy_body_fat(Sex::Female, 65.0, 30.0, 13.0, None).is_none());
+        assert!(navy_body_fat(Sex::Male, 0.0, 34.0, 15.0, None).is_none());
+        assert!(navy_body_fat(Sex::Male, 70.0, 15.0, 15.0, None).is_none());
+    }
+
+    #[test]
+    fn lean_mass_prefers_logged_body_fat() {
+        let tape = [measurement(MeasurementKind::Waist, 34.0), measurement(MeasurementKind::Neck, 15.0)];
+        let navy = derive(&tape, Some(200.0), Sex::Male, 70.0);
+        assert_eq!(navy.body_fat.unwrap().1, BodyFatSource::Navy);
+        assert!((navy.lean_mass_lbs.unwrap() - 200.0 * (1.0 - navy.navy_body_fat.unwrap() / 100.0)).abs() < 1e-9);
+
+        let mut logged = tape.to_vec();
+        logged.push(measurement(MeasurementKind::BodyFat, 20.0));
+        let composition = derive(&logged, Some(200.0), Sex::Male, 70.0);
+        assert_eq!(composition.body_fat, Some((20.0, BodyFatSource::Logged)));
+        assert_eq!(composition.lean_mass_lbs, Some(160.0));
+        assert!(composition.navy_body_fat.is_some());
+
+        assert_eq!(derive(&tape, None, Sex::Male, 0.0), BodyComposition::default());
+    }
+}
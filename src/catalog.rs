@@ -0,0 +1,374 @@
+use crate::db::exercises::{self, Exercise};
+use crate::db::{DbError, DbResult};
+use crate::types::{Equipment, ExerciseCategory, ExerciseMetric, MovementPattern, MuscleGroup};
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+// The built-in list of common lifts, keyed by stable catalog ids.
+const BUNDLED: &str = include_str!("catalog/exercises.csv");
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CatalogEntry {
+    pub id: String,
+    pub name: String,
+    pub category: ExerciseCategory,
+    pub equipment: Equipment,
+    pub movement_pattern: MovementPattern,
+    pub primary_muscles: Vec<MuscleGroup>,
+    pub secondary_muscles: Vec<MuscleGroup>,
+    pub default_metric: ExerciseMetric,
+}
+
+pub fn bundled() -> Result<Vec<CatalogEntry>, String> {
+    parse(BUNDLED)
+}
+
+// Reads catalog CSV: lines starting with '#' are comments, muscles are
+// separated by semicolons. Ids and names must be unique.
+pub fn parse(text: &str) -> Result<Vec<CatalogEntry>, String> {
+    fn field<T: FromStr<Err = String>>(record: &csv::StringRecord, idx: usize) -> Result<T, String> {
+        record.get(idx).unwrap_or_default().trim().parse()
+    }
+    fn muscles(record: &csv::StringRecord, idx: usize) -> Result<Vec<MuscleGroup>, String> {
+        let mut muscles = record
+            .get(idx)
+            .unwrap_or_default()
+            .split(';')
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<MuscleGroup>, String>>()?;
+        muscles.sort();
+        Ok(muscles)
+    }
+
+    let mut reader = csv::ReaderBuilder::new().comment(Some(b'#')).from_reader(text.as_bytes());
+    let mut entries: Vec<CatalogEntry> = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| e.to_string())?;
+        let line = record.position().map_or(0, |p| p.line());
+        let entry = (|| -> Result<CatalogEntry, String> {
+            let id = record.get(0).unwrap_or_default().trim().to_string();
+            let name = record.get(1).unwrap_or_default().trim().to_string();
+            if id.is_empty() || name.is_empty() {
+                return Err("missing catalog id or name".to_string());
+            }
+            Ok(CatalogEntry {
+                id,
+                name,
+                category: field(&record, 2)?,
+                equipment: field(&record, 3)?,
+                movement_pattern: field(&record, 4)?,
+                primary_muscles: muscles(&record, 5)?,
+                secondary_muscles: muscles(&record, 6)?,
+                default_metric: field(&record, 7)?,
+            })
+        })()
+        .map_err(|e| format!("catalog line {}: {}", line, e))?;
+        if entries.iter().any(|e| e.id == entry.id || e.name.eq_ignore_ascii_case(&entry.name)) {
+            return Err(format!("catalog line {}: duplicate entry {}", line, entry.id));
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+// The catalog-managed fields of an exercise, as stored.
+#[derive(Clone, Debug, PartialEq)]
+struct Fields {
+    name: String,
+    category: Option<String>,
+    equipment: Option<String>,
+    movement_pattern: Option<String>,
+    default_metric: Option<String>,
+    primary_muscles: String,
+    secondary_muscles: String,
+}
+
+fn join_muscles(muscles: &[MuscleGroup]) -> String {
+    let mut muscles = muscles.to_vec();
+    muscles.sort();
+    muscles.iter().map(|m| m.to_string()).collect::<Vec<_>>().join(";")
+}
+
+impl Fields {
+    fn of_entry(entry: &CatalogEntry) -> Fields {
+        Fields {
+            name: entry.name.clone(),
+            category: Some(entry.category.to_string()),
+            equipment: Some(entry.equipment.to_string()),
+            movement_pattern: Some(entry.movement_pattern.to_string()),
+            default_metric: Some(entry.default_metric.to_string()),
+            primary_muscles: join_muscles(&entry.primary_muscles),
+            secondary_muscles: join_muscles(&entry.secondary_muscles),
+        }
+    }
+
+    fn of_exercise(exercise: &Exercise) -> Fields {
+        Fields {
+            name: exercise.name.clone(),
+            category: exercise.category.map(|c| c.to_string()),
+            equipment: exercise.equipment.map(|e| e.to_string()),
+            movement_pattern: exercise.movement_pattern.map(|p| p.to_string()),
+            default_metric: exercise.default_metric.map(|m| m.to_string()),
+            primary_muscles: join_muscles(&exercise.primary_muscles),
+            secondary_muscles: join_muscles(&exercise.secondary_muscles),
+        }
+    }
+
+    // Writes these values back onto `exercise`; unparseable ones (there are
+    // none from the catalog) are left unset.
+    fn apply(&self, exercise: &mut Exercise) {
+        let muscles = |joined: &str| joined.split(';').filter_map(|m| m.parse().ok()).collect();
+        exercise.name = self.name.clone();
+        exercise.category = self.category.as_deref().and_then(|c| c.parse().ok());
+        exercise.equipment = self.equipment.as_deref().and_then(|e| e.parse().ok());
+        exercise.movement_pattern = self.movement_pattern.as_deref().and_then(|p| p.parse().ok());
+        exercise.default_metric = self.default_metric.as_deref().and_then(|m| m.parse().ok());
+        exercise.primary_muscles = muscles(&self.primary_muscles);
+        exercise.secondary_muscles = muscles(&self.secondary_muscles);
+    }
+}
+
+fn load_seed(conn: &Connection, catalog_id: &str) -> DbResult<Option<Fields>> {
+    Ok(conn
+        .query_row(
+            "SELECT name, category, equipment, movement_pattern, default_metric_to_track, primary_muscles, secondary_muscles
+             FROM exercise_catalog_seeds WHERE catalog_id = ?1",
+            [catalog_id],
+            |row| {
+                Ok(Fields {
+                    name: row.get(0)?,
+                    category: row.get(1)?,
+                    equipment: row.get(2)?,
+                    movement_pattern: row.get(3)?,
+                    default_metric: row.get(4)?,
+                    primary_muscles: row.get(5)?,
+                    secondary_muscles: row.get(6)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+fn store_seed(conn: &Connection, catalog_id: &str, fields: &Fields) -> DbResult<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO exercise_catalog_seeds
+             (catalog_id, name, category, equipment, movement_pattern, default_metric_to_track, primary_muscles, secondary_muscles)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        rusqlite::params![
+            catalog_id,
+            fields.name,
+            fields.category,
+            fields.equipment,
+            fields.movement_pattern,
+            fields.default_metric,
+            fields.primary_muscles,
+            fields.secondary_muscles
+        ],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncReport {
+    // New exercises created from the catalog.
+    pub added: usize,
+    // Existing exercises with a matching name (or alias) that now follow the catalog.
+    pub linked: usize,
+    // Seeded exercises that took catalog changes.
+    pub updated: usize,
+    // Fields left alone because they were edited since they were seeded.
+    pub kept_edits: usize,
+    // Entries whose exercise was merged away or otherwise removed; not re-added.
+    pub retired: usize,
+    // One line per entry that couldn't be applied.
+    pub notes: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn summary(&self) -> String {
+        format!(
+            "catalog: {} added, {} matched to existing exercises, {} updated, {} edited field(s) kept.",
+            self.added, self.linked, self.updated, self.kept_edits
+        )
+    }
+}
+
+// Brings the library in line with `entries` in one transaction. Each field
+// follows the catalog only while it still holds the value last seeded, so
+// edits made in the library survive later catalog updates. An existing
+// exercise with the entry's name is adopted rather than duplicated: only its
+// unset fields are filled in.
+pub fn sync(conn: &mut Connection, entries: &[CatalogEntry]) -> DbResult<SyncReport> {
+    let tx = conn.transaction()?;
+    let mut report = SyncReport::default();
+    let library: HashMap<i64, Exercise> = exercises::list(&tx)?.into_iter().map(|e| (e.id, e)).collect();
+    let by_catalog: HashMap<&str, &Exercise> =
+        library.values().filter_map(|e| e.catalog_id.as_deref().map(|id| (id, e))).collect();
+
+    for entry in entries {
+        let target = Fields::of_entry(entry);
+        let seed = load_seed(&tx, &entry.id)?;
+        if let Some(existing) = by_catalog.get(entry.id.as_str()) {
+            let current = Fields::of_exercise(existing);
+            let base = seed.unwrap_or_else(|| current.clone());
+            let mut kept = 0;
+            let mut pick = |current: &Option<String>, base: &Option<String>, target: &Option<String>| {
+                if current == base {
+                    target.clone()
+                } else {
+                    kept += usize::from(current != target);
+                    current.clone()
+                }
+            };
+            let mut merged = Fields {
+                name: pick(&Some(current.name.clone()), &Some(base.name.clone()), &Some(target.name.clone())).unwrap_or_default(),
+                category: pick(&current.category, &base.category, &target.category),
+                equipment: pick(&current.equipment, &base.equipment, &target.equipment),
+                movement_pattern: pick(&current.movement_pattern, &base.movement_pattern, &target.movement_pattern),
+                default_metric: pick(&current.default_metric, &base.default_metric, &target.default_metric),
+                primary_muscles: pick(&Some(current.primary_muscles.clone()), &Some(base.primary_muscles), &Some(target.primary_muscles.clone()))
+                    .unwrap_or_default(),
+                secondary_muscles: pick(
+                    &Some(current.secondary_muscles.clone()),
+                    &Some(base.secondary_muscles),
+                    &Some(target.secondary_muscles.clone()),
+                )
+                .unwrap_or_default(),
+            };
+            report.kept_edits += kept;
+            if merged.name != current.name && exercises::find(&tx, &merged.name)?.is_some_and(|id| id != existing.id) {
+                report.notes.push(format!("{}: kept the name {}; {} is taken.", entry.id, current.name, merged.name));
+                merged.name = current.name.clone();
+            }
+            if merged != current {
+                let mut exercise = (*existing).clone();
+                merged.apply(&mut exercise);
+                exercises::write(&tx, &exercise)?;
+                report.updated += 1;
+            }
+        } else if seed.is_some() {
+            report.retired += 1;
+            continue;
+        } else if let Some(id) = exercises::find(&tx, &entry.name)? {
+            let Some(existing) = library.get(&id) else { continue };
+            if existing.catalog_id.is_some() {
+                report.notes.push(format!("{}: {} already follows another catalog entry.", entry.id, existing.name));
+                continue;
+            }
+            let mut exercise = existing.clone();
+            exercise.category = exercise.category.or(Some(entry.category));
+            exercise.equipment = exercise.equipment.or(Some(entry.equipment));
+            exercise.movement_pattern = exercise.movement_pattern.or(Some(entry.movement_pattern));
+            exercise.default_metric = exercise.default_metric.or(Some(entry.default_metric));
+            if exercise.primary_muscles.is_empty() && exercise.secondary_muscles.is_empty() {
+                exercise.primary_muscles = entry.primary_muscles.clone();
+                exercise.secondary_muscles = entry.secondary_muscles.clone();
+            }
+            exercises::write(&tx, &exercise)?;
+            set_catalog_id(&tx, id, &entry.id)?;
+            report.linked += 1;
+        } else {
+            let mut exercise = Exercise::default();
+            target.apply(&mut exercise);
+            let id = exercises::write(&tx, &exercise)?;
+            set_catalog_id(&tx, id, &entry.id)?;
+            report.added += 1;
+        }
+        store_seed(&tx, &entry.id, &target)?;
+    }
+    tx.commit()?;
+    Ok(report)
+}
+
+fn set_catalog_id(conn: &Connection, exercise_id: i64, catalog_id: &str) -> DbResult<()> {
+    conn.execute("UPDATE exercises SET catalog_id = ?2 WHERE id = ?1", rusqlite::params![exercise_id, catalog_id])?;
+    Ok(())
+}
+
+// Seeds the bundled catalog into a database that has never had exercises or a
+// catalog sync, i.e. on first run. Returns None when there was nothing to do.
+pub fn seed_if_new(conn: &mut Connection) -> DbResult<Option<SyncReport>> {
+    let untouched: bool = conn.query_row(
+        "SELECT NOT EXISTS (SELECT 1 FROM exercises) AND NOT EXISTS (SELECT 1 FROM exercise_catalog_seeds)",
+        [],
+        |row| row.get(0),
+    )?;
+    if !untouched {
+        return Ok(None);
+    }
+    let entries = bundled().map_err(DbError::Invalid)?;
+    Ok(Some(sync(conn, &entries)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    const SMALL: &str = "# test catalog\n\
+        catalog_id,name,category,equipment,movement_pattern,primary_muscles,secondary_muscles,default_metric\n\
+        bench-press,Bench Press,Compound,Barbell,HorizontalPush,Chest,Triceps;Shoulders,EstimatedOneRepMax\n\
+        squat,Squat,Compound,Barbell,Squat,Quads,Glutes,EstimatedOneRepMax\n\
+        plank,Plank,Isolation,Bodyweight,Isolation,Core,,TotalReps\n";
+
+    #[test]
+    fn bundled_catalog_parses() {
+        let entries = bundled().unwrap();
+        assert!(entries.len() >= 200);
+        assert!(entries.iter().all(|e| !e.primary_muscles.is_empty()));
+        assert!(parse("catalog_id,name\nx,X\n").is_err());
+        assert!(parse(&format!("{}squat,Back Squat,Compound,Barbell,Squat,Quads,,TopSetWeight\n", SMALL)).is_err());
+    }
+
+    #[test]
+    fn seeds_a_new_database_once() {
+        let mut conn = test_conn();
+        let report = seed_if_new(&mut conn).unwrap().unwrap();
+        assert_eq!(report.added, bundled().unwrap().len());
+        let bench = exercises::get(&conn, exercises::find(&conn, "bench press").unwrap().unwrap()).unwrap().unwrap();
+        assert_eq!(bench.catalog_id.as_deref(), Some("bench-press"));
+        assert_eq!(bench.equipment, Some(Equipment::Barbell));
+        assert_eq!(seed_if_new(&mut conn).unwrap(), None);
+    }
+
+    #[test]
+    fn updates_keep_user_edits() {
+        let mut conn = test_conn();
+        // Existing history under a catalog name is adopted, not duplicated.
+        let squat = exercises::find_or_create(&conn, "squat").unwrap();
+        let entries = parse(SMALL).unwrap();
+        let report = sync(&mut conn, &entries).unwrap();
+        assert_eq!((report.added, report.linked), (2, 1));
+        let adopted = exercises::get(&conn, squat).unwrap().unwrap();
+        assert_eq!((adopted.name.as_str(), adopted.catalog_id.as_deref()), ("squat", Some("squat")));
+        assert_eq!(adopted.primary_muscles, vec![MuscleGroup::Quads]);
+
+        // The user changes bench's equipment and retires the plank.
+        let bench_id = exercises::find(&conn, "Bench Press").unwrap().unwrap();
+        let bench = exercises::get(&conn, bench_id).unwrap().unwrap();
+        exercises::save(&mut conn, &Exercise { equipment: Some(Equipment::Machine), ..bench }).unwrap();
+        let plank = exercises::find(&conn, "Plank").unwrap().unwrap();
+        exercises::merge(&mut conn, plank, squat, crate::e1rm::E1rmFormula::Epley).unwrap();
+
+        // A later catalog renames bench and changes its equipment and muscles.
+        let updated = parse(&SMALL.replace(
+            "bench-press,Bench Press,Compound,Barbell,HorizontalPush,Chest,Triceps;Shoulders",
+            "bench-press,Barbell Bench Press,Compound,Other,HorizontalPush,Chest,Triceps",
+        ))
+        .unwrap();
+        let report = sync(&mut conn, &updated).unwrap();
+        // Bench's equipment, plus the adopted squat's own name and metric.
+        assert_eq!((report.added, report.updated, report.kept_edits, report.retired), (0, 1, 3, 1));
+        let bench = exercises::get(&conn, bench_id).unwrap().unwrap();
+        assert_eq!(bench.name, "Barbell Bench Press");
+        assert_eq!(bench.aliases, vec!["Bench Press"]);
+        assert_eq!(bench.equipment, Some(Equipment::Machine));
+        assert_eq!(bench.secondary_muscles, vec![MuscleGroup::Triceps]);
+        // The plank stays merged into squat, which already follows its own entry.
+        assert_eq!(exercises::find(&conn, "plank").unwrap(), Some(squat));
+        assert_eq!(sync(&mut conn, &updated).unwrap().updated, 0);
+    }
+}
@@ -0,0 +1,401 @@
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::csv_io::{self, export};
+use crate::db::{self, diet_cycles, exercises, weight_logs, workouts};
+use crate::db::workouts::{NewExercise, NewSet};
+use crate::db::records;
+use crate::units;
+
+pub const USAGE: &str = "usage: liftmetrics [--db <path>] [<command>]
+
+without a command the app window opens.
+
+commands:
+  log-weight <weight> [--date <yyyy-mm-dd>] [--replace]
+        log a weigh-in, e.g. 182.4 or 82.5kg; --replace overwrites one
+        already logged that day
+  log-set <exercise> <set>... [--date <yyyy-mm-dd>] [--notes <text>]
+        add sets to the day's workout, written [<sets>x]<reps>x<weight>[@<rpe>],
+        e.g. 5x225@8 or 3x5x100kg
+  cycles list
+        list diet cycles; * marks the active one
+  prs [<exercise>]
+        list current personal records
+  export csv [--dir <path>]
+        write every table as csv, by default into the export folder next to
+        the database
+
+options:
+  --db <path>   use this database file instead of the default location
+  -h, --help    show this message
+
+environment:
+  LIFTMETRICS_DB   database file to use when --db isn't given";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct CliArgs {
+    pub db_path: Option<PathBuf>,
+    pub show_help: bool,
+    // `None` runs the GUI.
+    pub command: Option<Command>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    // The weight is kept as typed so a bare number can take the saved unit.
+    LogWeight { weight: String, date: Option<NaiveDate>, replace: bool },
+    LogSet { exercise: String, sets: Vec<SetSpec>, date: Option<NaiveDate>, notes: Option<String> },
+    ListCycles,
+    Records { exercise: Option<String> },
+    ExportCsv { dir: Option<PathBuf> },
+}
+
+// One `[<sets>x]<reps>x<weight>[@<rpe>]` argument.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SetSpec {
+    pub count: usize,
+    pub reps: i64,
+    pub weight: String,
+    pub rpe: Option<f64>,
+}
+
+impl SetSpec {
+    pub fn parse(text: &str) -> Result<SetSpec, String> {
+        let invalid = || format!("couldn't read set \"{}\"; write it as 5x225, 5x225@8 or 3x5x100kg.", text);
+        let (body, rpe) = match text.split_once('@') {
+            Some((body, rpe)) => match rpe.parse::<f64>() {
+                Ok(r) if (1.0..=10.0).contains(&r) => (body, Some(r)),
+                _ => return Err(format!("set \"{}\": rpe must be between 1 and 10.", text)),
+            },
+            None => (text, None),
+        };
+        let parts: Vec<&str> = body.split(['x', 'X']).collect();
+        let (count, reps, weight) = match parts.as_slice() {
+            [reps, weight] => ("1", *reps, *weight),
+            [count, reps, weight] => (*count, *reps, *weight),
+            _ => return Err(invalid()),
+        };
+        let count = match count.parse::<usize>() {
+            Ok(c) if (1..=50).contains(&c) => c,
+            _ => return Err(invalid()),
+        };
+        let reps = match reps.parse::<i64>() {
+            Ok(r) if r > 0 => r,
+            _ => return Err(format!("set \"{}\": reps must be a positive whole number.", text)),
+        };
+        if weight.is_empty() {
+            return Err(invalid());
+        }
+        Ok(SetSpec { count, reps, weight: weight.to_string(), rpe })
+    }
+}
+
+// Parses everything after the program name. Options may come before or after
+// the command.
+pub fn parse(args: impl IntoIterator<Item = OsString>) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    let mut positional: Vec<String> = Vec::new();
+    let (mut date, mut replace, mut notes, mut dir) = (None, false, None, None);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let text = arg.to_string_lossy().into_owned();
+        let (flag, inline) = match text.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (text.clone(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            let value = match inline.clone() {
+                Some(value) => value,
+                None => args.next().map(|v| v.to_string_lossy().into_owned()).unwrap_or_default(),
+            };
+            if value.is_empty() { Err(format!("{} needs a value", name)) } else { Ok(value) }
+        };
+        match flag.as_str() {
+            "-h" | "--help" => parsed.show_help = true,
+            "--db" => parsed.db_path = Some(PathBuf::from(value("--db").map_err(|_| "--db needs a path")?)),
+            "--date" => {
+                let text = value("--date")?;
+                date = Some(
+                    NaiveDate::parse_from_str(&text, "%Y-%m-%d")
+                        .map_err(|_| format!("couldn't read --date {}; use yyyy-mm-dd", text))?,
+                );
+            }
+            "--replace" => replace = true,
+            "--notes" => notes = Some(value("--notes")?),
+            "--dir" => dir = Some(PathBuf::from(value("--dir")?)),
+            other if other.starts_with('-') && other.len() > 1 && other.parse::<f64>().is_err() => {
+                return Err(format!("unrecognized argument: {}", text));
+            }
+            _ => positional.push(text),
+        }
+    }
+
+    let Some((name, rest)) = positional.split_first() else {
+        if date.is_some() || replace || notes.is_some() || dir.is_some() {
+            return Err("--date, --replace, --notes and --dir need a command".to_string());
+        }
+        return Ok(parsed);
+    };
+    let unused = |flags: &[(&str, bool)]| -> Result<(), String> {
+        match flags.iter().find(|(_, given)| *given) {
+            Some((flag, _)) => Err(format!("{} doesn't apply to {}", flag, name)),
+            None => Ok(()),
+        }
+    };
+    let command = match (name.as_str(), rest) {
+        ("log-weight", [weight]) => {
+            unused(&[("--notes", notes.is_some()), ("--dir", dir.is_some())])?;
+            Command::LogWeight { weight: weight.clone(), date, replace }
+        }
+        ("log-weight", _) => return Err("log-weight needs exactly one weight, e.g. log-weight 182.4".to_string()),
+        ("log-set", [exercise, sets @ ..]) if !sets.is_empty() => {
+            unused(&[("--replace", replace), ("--dir", dir.is_some())])?;
+            let sets = sets.iter().map(|s| SetSpec::parse(s)).collect::<Result<Vec<_>, _>>()?;
+            Command::LogSet { exercise: exercise.trim().to_string(), sets, date, notes }
+        }
+        ("log-set", _) => return Err("log-set needs an exercise and at least one set, e.g. log-set \"Bench Press\" 5x225@8".to_string()),
+        ("cycles", [] | [_]) if rest.first().is_none_or(|sub| sub == "list") => {
+            unused(&[("--date", date.is_some()), ("--replace", replace), ("--notes", notes.is_some()), ("--dir", dir.is_some())])?;
+            Command::ListCycles
+        }
+        ("prs", [] | [_]) => {
+            unused(&[("--date", date.is_some()), ("--replace", replace), ("--notes", notes.is_some()), ("--dir", dir.is_some())])?;
+            Command::Records { exercise: rest.first().cloned() }
+        }
+        ("export", [format]) if format == "csv" => {
+            unused(&[("--date", date.is_some()), ("--replace", replace), ("--notes", notes.is_some())])?;
+            Command::ExportCsv { dir }
+        }
+        ("export", _) => return Err("export needs a format: export csv".to_string()),
+        _ => return Err(format!("unrecognized command: {}", positional.join(" "))),
+    };
+    parsed.command = Some(command);
+    Ok(parsed)
+}
+
+// Runs `command` against the open database, writing its report to `out`.
+// Weights are read and shown in the units saved in the GUI's settings.
+pub fn run(command: &Command, conn: &mut Connection, db_path: &Path, today: NaiveDate, out: &mut impl Write) -> Result<(), String> {
+    let settings = db::settings::load(conn).map_err(|e| e.to_string())?;
+    let write_err = |e: std::io::Error| e.to_string();
+    match command {
+        Command::LogWeight { weight, date, replace } => {
+            let weight_lbs = match units::parse_weight(weight, settings.bodyweight_unit)? {
+                w if w > 0.0 => w,
+                _ => return Err("weight must be a positive number.".to_string()),
+            };
+            let date = date.unwrap_or(today);
+            let log_date = date.format("%Y-%m-%d").to_string();
+            let formatted = settings.bodyweight_unit.format(weight_lbs);
+            if let Some(existing) = weight_logs::find_on_date(conn, &log_date).map_err(|e| e.to_string())? {
+                if !replace {
+                    return Err(format!(
+                        "{} is already logged on {}; pass --replace to overwrite it.",
+                        settings.bodyweight_unit.format(existing.weight_lbs),
+                        log_date
+                    ));
+                }
+                weight_logs::update(conn, &db::WeightLog { weight_lbs, ..existing }).map_err(|e| e.to_string())?;
+                writeln!(out, "replaced the weigh-in on {} with {}.", log_date, formatted).map_err(write_err)?;
+                return Ok(());
+            }
+            let cycle_id = diet_cycles::covering(conn, date, today).map_err(|e| e.to_string())?;
+            weight_logs::insert(conn, cycle_id, &log_date, weight_lbs).map_err(|e| e.to_string())?;
+            let cycle_note = if cycle_id.is_none() { " (outside any diet cycle)" } else { "" };
+            writeln!(out, "logged {} on {}{}.", formatted, log_date, cycle_note).map_err(write_err)?;
+        }
+        Command::LogSet { exercise, sets, date, notes } => {
+            let mut new_sets = Vec::new();
+            for spec in sets {
+                let weight_lbs = match units::parse_weight(&spec.weight, settings.lift_unit) {
+                    Ok(w) if w >= 0.0 => w,
+                    _ => return Err(format!("couldn't read \"{}\" as a weight.", spec.weight)),
+                };
+                let set = NewSet { reps: spec.reps, weight_lbs, rpe: spec.rpe, notes: notes.clone() };
+                new_sets.extend(std::iter::repeat_n(set, spec.count));
+            }
+            let count = new_sets.len();
+            let entry = NewExercise { name: exercise.clone(), sets: new_sets };
+            let date = date.unwrap_or(today);
+            let (_, new_records) =
+                workouts::append(conn, date, &entry, settings.e1rm_formula).map_err(|e| e.to_string())?;
+            writeln!(out, "logged {} set(s) of {} on {}.", count, exercise, date.format("%Y-%m-%d")).map_err(write_err)?;
+            for record in &new_records {
+                writeln!(out, "{}", record.announcement(settings.lift_unit)).map_err(write_err)?;
+            }
+        }
+        Command::ListCycles => {
+            let summaries = diet_cycles::list_summaries(conn).map_err(|e| e.to_string())?;
+            if summaries.is_empty() {
+                writeln!(out, "no diet cycles.").map_err(write_err)?;
+            }
+            let unit = settings.bodyweight_unit;
+            for summary in summaries {
+                let cycle = &summary.cycle;
+                let end = match &cycle.actual_end_date {
+                    Some(end) => end.clone(),
+                    None => format!("{} (planned)", cycle.planned_end_date),
+                };
+                let target = cycle.calorie_target.map(|t| format!("  {} kcal", t)).unwrap_or_default();
+                let weights = match (summary.start_weight_lbs, summary.end_weight_lbs) {
+                    (Some(start), Some(end)) => format!("  {} -> {}", unit.format(start), unit.format(end)),
+                    _ => String::new(),
+                };
+                writeln!(
+                    out,
+                    "{}{:>4}  {:<8} {} to {}{}{}",
+                    if cycle.is_active { "*" } else { " " },
+                    cycle.id,
+                    cycle.phase.to_string(),
+                    cycle.start_date,
+                    end,
+                    target,
+                    weights
+                )
+                .map_err(write_err)?;
+            }
+        }
+        Command::Records { exercise } => {
+            // The name may be an alias; an unknown name simply has no records.
+            let exercise_id = match exercise {
+                Some(name) => Some(exercises::find(conn, name).map_err(|e| e.to_string())?),
+                None => None,
+            };
+            let records = records::load_current(conn).map_err(|e| e.to_string())?;
+            let records: Vec<_> =
+                records.into_iter().filter(|r| exercise_id.is_none_or(|id| id == Some(r.exercise_id))).collect();
+            if records.is_empty() {
+                writeln!(out, "no personal records{}.", exercise.as_ref().map(|n| format!(" for {}", n)).unwrap_or_default())
+                    .map_err(write_err)?;
+            }
+            for record in records {
+                writeln!(
+                    out,
+                    "{:<24} {:<16} {:<14} {}",
+                    record.exercise_name,
+                    record.label(),
+                    record.display_value(settings.lift_unit),
+                    record.achieved_on
+                )
+                .map_err(write_err)?;
+            }
+        }
+        Command::ExportCsv { dir } => {
+            let dir = dir.clone().unwrap_or_else(|| csv_io::default_dir(db_path));
+            let written = export::export_dir(conn, &dir, &settings).map_err(|e| e.to_string())?;
+            for (table, count) in &written {
+                writeln!(out, "{:<24} {} rows", table.file_name(), count).map_err(write_err)?;
+            }
+            writeln!(out, "exported {} files to {}.", written.len(), dir.display()).map_err(write_err)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    fn args(list: &[&str]) -> Vec<OsString> {
+        list.iter().map(OsString::from).collect()
+    }
+
+    fn command(list: &[&str]) -> Command {
+        parse(args(list)).unwrap().command.unwrap()
+    }
+
+    fn run_text(conn: &mut Connection, list: &[&str]) -> Result<String, String> {
+        let mut out = Vec::new();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        run(&command(list), conn, Path::new("/tmp/liftmetrics.db"), today, &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn parses_db_flag_forms() {
+        assert_eq!(parse(args(&[])).unwrap(), CliArgs::default());
+        assert_eq!(parse(args(&["--db", "lifts.db"])).unwrap().db_path, Some("lifts.db".into()));
+        assert_eq!(parse(args(&["--db=/tmp/x.db"])).unwrap().db_path, Some("/tmp/x.db".into()));
+        assert!(parse(args(&["--help"])).unwrap().show_help);
+        assert!(parse(args(&["--db"])).is_err());
+        assert!(parse(args(&["--bogus"])).is_err());
+    }
+
+    #[test]
+    fn parses_commands() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 17);
+        assert_eq!(
+            command(&["log-weight", "182.4", "--date", "2026-10-17"]),
+            Command::LogWeight { weight: "182.4".into(), date, replace: false }
+        );
+        let parsed = parse(args(&["log-set", "Bench Press", "5x225@8", "3x5x100kg", "--db", "x.db"])).unwrap();
+        assert_eq!(parsed.db_path, Some("x.db".into()));
+        assert_eq!(
+            parsed.command,
+            Some(Command::LogSet {
+                exercise: "Bench Press".into(),
+                sets: vec![
+                    SetSpec { count: 1, reps: 5, weight: "225".into(), rpe: Some(8.0) },
+                    SetSpec { count: 3, reps: 5, weight: "100kg".into(), rpe: None },
+                ],
+                date: None,
+                notes: None,
+            })
+        );
+        assert_eq!(command(&["cycles", "list"]), Command::ListCycles);
+        assert_eq!(command(&["prs", "squat"]), Command::Records { exercise: Some("squat".into()) });
+        assert_eq!(command(&["export", "csv", "--dir=out"]), Command::ExportCsv { dir: Some("out".into()) });
+
+        assert!(parse(args(&["log-weight"])).is_err());
+        assert!(parse(args(&["log-set", "Bench", "5x225@11"])).is_err());
+        assert!(parse(args(&["log-set", "Bench", "five"])).is_err());
+        assert!(parse(args(&["log-weight", "180", "--dir", "x"])).is_err());
+        assert!(parse(args(&["log-weight", "180", "--date", "17/10/2026"])).is_err());
+        assert!(parse(args(&["export", "json"])).is_err());
+        assert!(parse(args(&["dance"])).is_err());
+    }
+
+    #[test]
+    fn logs_weights_and_sets() {
+        let mut conn = test_conn();
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2026, m, d).unwrap();
+        diet_cycles::create(&mut conn, crate::types::DietPhase::Cut, day(10, 1), day(12, 1), Some(2200), false, today).unwrap();
+
+        assert_eq!(run_text(&mut conn, &["log-weight", "182.4"]).unwrap(), "logged 182.4 lbs on 2026-10-17.\n");
+        assert!(run_text(&mut conn, &["log-weight", "181"]).unwrap_err().contains("--replace"));
+        run_text(&mut conn, &["log-weight", "82kg", "--replace"]).unwrap();
+        let logged = weight_logs::find_on_date(&conn, "2026-10-17").unwrap().unwrap();
+        assert!((logged.weight_lbs - units::WeightUnit::Kg.to_lbs(82.0)).abs() < 1e-9);
+        assert!(logged.diet_cycle_id.is_some());
+
+        let report = run_text(&mut conn, &["log-set", "Bench Press", "5x225@8", "2x3x245", "--notes", "paused"]).unwrap();
+        assert!(report.starts_with("logged 3 set(s) of Bench Press on 2026-10-17.\n"));
+        assert!(report.contains("first 3RM on Bench Press"));
+        let sets = workouts::last_session_sets(&conn, 1).unwrap();
+        assert_eq!(sets.iter().map(|s| (s.reps, s.weight_lbs)).collect::<Vec<_>>(), vec![(5, 225.0), (3, 245.0), (3, 245.0)]);
+        assert!(sets.iter().all(|s| s.notes.as_deref() == Some("paused")));
+
+        let cycles = run_text(&mut conn, &["cycles", "list"]).unwrap();
+        assert!(cycles.starts_with("*   1  Cut      2026-10-01 to 2026-12-01 (planned)  2200 kcal"));
+        let prs = run_text(&mut conn, &["prs", "bench press"]).unwrap();
+        assert!(prs.lines().any(|line| line.starts_with("Bench Press") && line.contains("3RM") && line.contains("245.0 lbs")));
+        assert_eq!(run_text(&mut conn, &["prs", "Squat"]).unwrap(), "no personal records for Squat.\n");
+    }
+
+    #[test]
+    fn prs_resolve_aliases() {
+        let mut conn = test_conn();
+        run_text(&mut conn, &["log-set", "Bench Press", "3x245"]).unwrap();
+        exercises::add_alias(&conn, 1, "BB Bench").unwrap();
+
+        let prs = run_text(&mut conn, &["prs", "bb bench"]).unwrap();
+        assert!(prs.lines().any(|line| line.starts_with("Bench Press") && line.contains("3RM")), "{}", prs);
+        assert_eq!(run_text(&mut conn, &["prs", "BB Row"]).unwrap(), "no personal records for BB Row.\n");
+    }
+}
@@ -0,0 +1,532 @@
+// Workout history exported by other apps (Strong, Hevy, FitNotes). Rows are
+// grouped into sessions, exercise names are matched onto `exercises` for the
+// user to review, and the result is written like any other logged workout.
+use super::import::{CsvSource, ImportReport, RowOutcome, RowStatus, WEIGHT_TOLERANCE_LBS};
+use super::{CsvError, CsvTable, detect_date_format, detect_unit, normalize_header, parse_date};
+use crate::db::workouts::{self, NewSet};
+use crate::db::{DbResult, exercises};
+use crate::e1rm::E1rmFormula;
+use crate::db::records;
+use crate::units::{self, WeightUnit};
+use chrono::NaiveDate;
+use rusqlite::Connection;
+use std::collections::HashMap;
+
+// Fuzzy matches scoring below this aren't suggested.
+pub const SUGGEST_THRESHOLD: f64 = 0.6;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AppFormat {
+    Strong,
+    Hevy,
+    FitNotes,
+}
+
+impl AppFormat {
+    pub const ALL: [AppFormat; 3] = [AppFormat::Strong, AppFormat::Hevy, AppFormat::FitNotes];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            AppFormat::Strong => "Strong",
+            AppFormat::Hevy => "Hevy",
+            AppFormat::FitNotes => "FitNotes",
+        }
+    }
+
+    // Recognises an export by its header row.
+    pub fn detect(headers: &[String]) -> Option<AppFormat> {
+        let has = |name: &str| headers.iter().any(|h| h.eq_ignore_ascii_case(name));
+        if has("Exercise Name") && has("Set Order") {
+            Some(AppFormat::Strong)
+        } else if has("exercise_title") && has("set_index") {
+            Some(AppFormat::Hevy)
+        } else if has("Exercise") && has("Category") {
+            Some(AppFormat::FitNotes)
+        } else {
+            None
+        }
+    }
+}
+
+// Where each app keeps the fields we read. `None` means the app has no such column.
+struct Columns {
+    date: usize,
+    title: Option<usize>,
+    exercise: usize,
+    order: Option<usize>,
+    set_type: Option<usize>,
+    weight: usize,
+    unit: Option<usize>,
+    reps: usize,
+    rpe: Option<usize>,
+    set_notes: Option<usize>,
+    exercise_notes: Option<usize>,
+    workout_notes: Option<usize>,
+}
+
+impl Columns {
+    fn find(format: AppFormat, headers: &[String]) -> Result<Columns, CsvError> {
+        let optional = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+        let required = |name: &str| {
+            optional(name).ok_or_else(|| CsvError::Invalid(format!("not a {} export: no \"{}\" column.", format.label(), name)))
+        };
+        // "Weight", "weight_kg", "Weight (lbs)"; never the unit column.
+        let weight = headers
+            .iter()
+            .position(|h| {
+                let name = normalize_header(h);
+                name.starts_with("weight") && name != "weightunit"
+            })
+            .ok_or_else(|| CsvError::Invalid(format!("not a {} export: no weight column.", format.label())))?;
+        Ok(match format {
+            AppFormat::Strong => Columns {
+                date: required("Date")?,
+                title: optional("Workout Name"),
+                exercise: required("Exercise Name")?,
+                order: optional("Set Order"),
+                set_type: None,
+                weight,
+                unit: optional("Weight Unit"),
+                reps: required("Reps")?,
+                rpe: optional("RPE"),
+                set_notes: optional("Notes"),
+                exercise_notes: None,
+                workout_notes: optional("Workout Notes"),
+            },
+            AppFormat::Hevy => Columns {
+                date: required("start_time")?,
+                title: optional("title"),
+                exercise: required("exercise_title")?,
+                order: optional("set_index"),
+                set_type: optional("set_type"),
+                weight,
+                unit: None,
+                reps: required("reps")?,
+                rpe: optional("rpe"),
+                set_notes: None,
+                exercise_notes: optional("exercise_notes"),
+                workout_notes: optional("description"),
+            },
+            AppFormat::FitNotes => Columns {
+                date: required("Date")?,
+                title: None,
+                exercise: required("Exercise")?,
+                order: None,
+                set_type: None,
+                weight,
+                unit: None,
+                reps: required("Reps")?,
+                rpe: None,
+                set_notes: optional("Comment"),
+                exercise_notes: None,
+                workout_notes: None,
+            },
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppExercise {
+    pub name: String,
+    // Ordered as the app ordered them.
+    pub sets: Vec<NewSet>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppWorkout {
+    // Line of the workout's first row.
+    pub line: usize,
+    pub date: NaiveDate,
+    pub notes: Option<String>,
+    pub exercises: Vec<AppExercise>,
+}
+
+impl AppWorkout {
+    pub fn set_count(&self) -> usize {
+        self.exercises.iter().map(|e| e.sets.len()).sum()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppExport {
+    pub format: AppFormat,
+    pub date_format: &'static str,
+    pub unit: WeightUnit,
+    pub workouts: Vec<AppWorkout>,
+    // Rows that aren't strength sets (rest timers, cardio) or couldn't be read.
+    pub skipped: Vec<RowOutcome>,
+}
+
+// A set as read, before its exercise's sets are put in order.
+struct RawSet {
+    order: Option<i64>,
+    set: NewSet,
+}
+
+// A workout being read, with its sets grouped by exercise name in first-seen order.
+type PendingWorkout = (AppWorkout, Vec<(String, Vec<RawSet>)>);
+
+// Groups the rows into workouts. Weights without a unit in the header or a
+// unit column are taken to be in `default_unit`.
+pub fn parse(format: AppFormat, source: &CsvSource, default_unit: WeightUnit, month_first: bool) -> Result<AppExport, CsvError> {
+    let columns = Columns::find(format, &source.headers)?;
+    let date_format = detect_date_format(source.column_values(columns.date), month_first)
+        .ok_or_else(|| CsvError::Invalid(format!("couldn't read the dates in this {} export.", format.label())))?;
+    let unit = detect_unit(&source.headers[columns.weight]).unwrap_or(default_unit);
+
+    let mut workouts: Vec<PendingWorkout> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    let mut skipped = Vec::new();
+    for (idx, row) in source.rows.iter().enumerate() {
+        let line = idx + 2;
+        let cell = |col: Option<usize>| col.and_then(|c| row.get(c)).map(|s| s.trim()).filter(|s| !s.is_empty());
+        let mut skip = |reason: &str| {
+            skipped.push(RowOutcome { line, summary: row.join(", "), status: RowStatus::Skipped(reason.to_string()) })
+        };
+
+        let Some(date) = cell(Some(columns.date)).and_then(|d| parse_date(d, date_format)) else {
+            skip("no readable date.");
+            continue;
+        };
+        let Some(exercise) = cell(Some(columns.exercise)) else {
+            skip("no exercise name.");
+            continue;
+        };
+        let order_text = cell(columns.order);
+        if order_text.is_some_and(|o| o.eq_ignore_ascii_case("rest timer")) {
+            skip("rest timer.");
+            continue;
+        }
+        let reps = match cell(Some(columns.reps)).map(str::parse::<f64>) {
+            Some(Ok(reps)) if reps >= 1.0 => reps.round() as i64,
+            _ => {
+                skip("no reps; cardio and timed sets aren't imported.");
+                continue;
+            }
+        };
+        let row_unit = match cell(columns.unit).map(str::parse::<WeightUnit>) {
+            Some(Ok(unit)) => unit,
+            _ => unit,
+        };
+        let weight_lbs = match cell(Some(columns.weight)) {
+            // Bodyweight movements leave the weight blank.
+            None => 0.0,
+            Some(text) => match units::parse_weight(text, row_unit) {
+                Ok(lbs) if lbs >= 0.0 => lbs,
+                _ => {
+                    skip("unreadable weight.");
+                    continue;
+                }
+            },
+        };
+        let rpe = cell(columns.rpe).and_then(|r| r.parse::<f64>().ok()).filter(|r| *r > 0.0 && *r <= 10.0);
+        let set_type = cell(columns.set_type).filter(|t| !t.eq_ignore_ascii_case("normal"));
+        let notes: Vec<&str> = set_type.into_iter().chain(cell(columns.set_notes)).collect();
+        let set = NewSet { reps, weight_lbs, rpe, notes: (!notes.is_empty()).then(|| notes.join("; ")) };
+        // Hevy numbers sets from zero; Strong from one, with "W"/"D" for warm-up and drop sets.
+        let order = order_text.and_then(|o| o.parse::<i64>().ok()).map(|o| if format == AppFormat::Hevy { o + 1 } else { o });
+
+        let title = cell(columns.title);
+        let key = format!("{}|{}", cell(Some(columns.date)).unwrap_or_default(), title.unwrap_or_default());
+        let workout_idx = *by_key.entry(key).or_insert_with(|| {
+            let notes = match (title, cell(columns.workout_notes)) {
+                (Some(title), Some(notes)) => Some(format!("{}: {}", title, notes)),
+                (title, notes) => title.or(notes).map(str::to_string),
+            };
+            workouts.push((AppWorkout { line, date, notes, exercises: Vec::new() }, Vec::new()));
+            workouts.len() - 1
+        });
+        let groups = &mut workouts[workout_idx].1;
+        let group = match groups.iter().position(|(name, _)| name.eq_ignore_ascii_case(exercise)) {
+            Some(pos) => pos,
+            None => {
+                groups.push((exercise.to_string(), Vec::new()));
+                groups.len() - 1
+            }
+        };
+        let mut set = RawSet { order, set };
+        // Exercise notes go on the exercise's first set.
+        if groups[group].1.is_empty()
+            && let Some(exercise_notes) = cell(columns.exercise_notes)
+        {
+            let notes = set.set.notes.take();
+            set.set.notes = Some(match notes {
+                Some(notes) => format!("{}; {}", notes, exercise_notes),
+                None => exercise_notes.to_string(),
+            });
+        }
+        groups[group].1.push(set);
+    }
+
+    let workouts = workouts
+        .into_iter()
+        .map(|(mut workout, groups)| {
+            workout.exercises = groups
+                .into_iter()
+                .map(|(name, mut sets)| {
+                    // Sets without a number keep their place in the file.
+                    let mut position = 0;
+                    let mut keyed: Vec<(i64, NewSet)> = sets
+                        .drain(..)
+                        .map(|raw| {
+                            position += 1;
+                            (raw.order.unwrap_or(position), raw.set)
+                        })
+                        .collect();
+                    keyed.sort_by_key(|(order, _)| *order);
+                    AppExercise { name, sets: keyed.into_iter().map(|(_, set)| set).collect() }
+                })
+                .collect();
+            workout
+        })
+        .collect();
+    Ok(AppExport { format, date_format, unit, workouts, skipped })
+}
+
+// How one exercise name from the export lands in `exercises`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NameMatch {
+    pub source: String,
+    // The existing exercise to log under; `None` creates `source`.
+    pub target: Option<String>,
+    // Similarity of a fuzzy suggestion; `None` for an exact match or no match.
+    pub score: Option<f64>,
+}
+
+impl NameMatch {
+    pub fn needs_review(&self) -> bool {
+        self.score.is_some()
+    }
+}
+
+// One entry per distinct exercise name in the export, alphabetical. Exact
+// (case-insensitive) matches on a name or alias are taken as-is; otherwise the
+// closest existing exercise is suggested for review.
+pub fn match_names(conn: &Connection, export: &AppExport) -> DbResult<Vec<NameMatch>> {
+    // (spelling, the exercise it stands for)
+    let known: Vec<(String, String)> = exercises::list(conn)?
+        .into_iter()
+        .flat_map(|e| {
+            let spellings: Vec<String> = std::iter::once(e.name.clone()).chain(e.aliases.iter().cloned()).collect();
+            spellings.into_iter().map(move |spelling| (spelling, e.name.clone()))
+        })
+        .collect();
+    let mut sources: Vec<&str> = export.workouts.iter().flat_map(|w| w.exercises.iter().map(|e| e.name.as_str())).collect();
+    sources.sort_by_key(|name| name.to_lowercase());
+    sources.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+
+    Ok(sources
+        .into_iter()
+        .map(|source| {
+            if let Some((_, exact)) = known.iter().find(|(spelling, _)| spelling.eq_ignore_ascii_case(source)) {
+                return NameMatch { source: source.to_string(), target: Some(exact.clone()), score: None };
+            }
+            let best = known
+                .iter()
+                .map(|(spelling, name)| (name, exercises::name_similarity(source, spelling)))
+                .filter(|(_, score)| *score >= SUGGEST_THRESHOLD)
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            NameMatch { source: source.to_string(), target: best.map(|(name, _)| name.clone()), score: best.map(|(_, s)| s) }
+        })
+        .collect())
+}
+
+// Writes each workout as a session unless every one of its sets is already
+// logged on that date. Names are resolved through `names`; unlisted names are
+// created as they are. Records are rebuilt afterwards.
+pub fn import(
+    conn: &mut Connection,
+    export: &AppExport,
+    names: &[NameMatch],
+    formula: E1rmFormula,
+    dry_run: bool,
+) -> Result<ImportReport, CsvError> {
+    let resolve = |source: &str| -> String {
+        names
+            .iter()
+            .find(|m| m.source.eq_ignore_ascii_case(source))
+            .and_then(|m| m.target.clone())
+            .unwrap_or_else(|| source.to_string())
+    };
+
+    let tx = conn.transaction()?;
+    let mut rows = export.skipped.clone();
+    for workout in &export.workouts {
+        // Two source names can resolve to one exercise; their sets are kept together.
+        let mut merged: Vec<(String, Vec<&NewSet>)> = Vec::new();
+        for exercise in &workout.exercises {
+            let target = resolve(&exercise.name);
+            match merged.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(&target)) {
+                Some((_, sets)) => sets.extend(&exercise.sets),
+                None => merged.push((target, exercise.sets.iter().collect())),
+            }
+        }
+
+        let date = workout.date.format("%Y-%m-%d").to_string();
+        let summary = format!(
+            "{}{}: {} exercises, {} sets",
+            date,
+            workout.notes.as_deref().map(|n| format!(" {}", n)).unwrap_or_default(),
+            merged.len(),
+            workout.set_count()
+        );
+        if already_logged(&tx, &date, &merged)? {
+            rows.push(RowOutcome { line: workout.line, summary, status: RowStatus::Duplicate });
+            continue;
+        }
+        let session_id = workouts::create_session(&tx, &date, workout.notes.as_deref())?;
+        for (name, sets) in &merged {
+            let exercise_id = exercises::find_or_create(&tx, name)?;
+            for (idx, set) in sets.iter().enumerate() {
+                workouts::insert_set(&tx, session_id, exercise_id, idx as i64 + 1, set)?;
+            }
+        }
+        rows.push(RowOutcome { line: workout.line, summary, status: RowStatus::Added });
+    }
+    rows.sort_by_key(|row| row.line);
+
+    let report = ImportReport { table: CsvTable::ExerciseSets, date_format: export.date_format, unit: export.unit, dry_run, rows };
+    if dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+        if report.added() > 0 {
+            records::rebuild_all(conn, formula)?;
+        }
+    }
+    Ok(report)
+}
+
+// Whether a session on `date` already holds every one of these sets.
+fn already_logged(conn: &Connection, date: &str, merged: &[(String, Vec<&NewSet>)]) -> DbResult<bool> {
+    let Some(session_id) = workouts::session_on(conn, date)? else { return Ok(false) };
+    for (name, sets) in merged {
+        let Some(exercise_id) = exercises::find(conn, name)? else { return Ok(false) };
+        for (idx, set) in sets.iter().enumerate() {
+            if !workouts::set_exists(conn, session_id, exercise_id, idx as i64 + 1, set, WEIGHT_TOLERANCE_LBS)? {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    fn fixture(text: &str) -> CsvSource {
+        CsvSource::from_reader(text.as_bytes()).unwrap()
+    }
+
+    fn strong() -> CsvSource {
+        fixture(include_str!("../../tests/fixtures/strong.csv"))
+    }
+
+    fn hevy() -> CsvSource {
+        fixture(include_str!("../../tests/fixtures/hevy.csv"))
+    }
+
+    fn fitnotes() -> CsvSource {
+        fixture(include_str!("../../tests/fixtures/fitnotes.csv"))
+    }
+
+    #[test]
+    fn detects_each_format() {
+        assert_eq!(AppFormat::detect(&strong().headers), Some(AppFormat::Strong));
+        assert_eq!(AppFormat::detect(&hevy().headers), Some(AppFormat::Hevy));
+        assert_eq!(AppFormat::detect(&fitnotes().headers), Some(AppFormat::FitNotes));
+        assert_eq!(AppFormat::detect(&["date".to_string(), "weight".to_string()]), None);
+    }
+
+    #[test]
+    fn parses_strong_sessions_in_set_order() {
+        let export = parse(AppFormat::Strong, &strong(), WeightUnit::Lbs, false).unwrap();
+        assert_eq!(export.workouts.len(), 2, "the cardio-only workout has no sets");
+        assert_eq!(export.skipped.len(), 2);
+
+        let push = &export.workouts[0];
+        assert_eq!(push.notes.as_deref(), Some("Push Day: Felt strong"));
+        let bench = &push.exercises[0];
+        assert_eq!(bench.name, "Bench Press (Barbell)");
+        assert_eq!(bench.sets.iter().map(|s| s.rpe).collect::<Vec<_>>(), vec![None, Some(8.0), Some(9.0)]);
+        assert_eq!(bench.sets[2].notes.as_deref(), Some("grindy"));
+
+        // Listed out of order in the file.
+        let squat = &export.workouts[1].exercises[0];
+        assert_eq!(squat.sets.iter().map(|s| s.weight_lbs).collect::<Vec<_>>(), vec![225.0, 245.0]);
+    }
+
+    #[test]
+    fn parses_hevy_kilos_set_types_and_notes() {
+        let export = parse(AppFormat::Hevy, &hevy(), WeightUnit::Lbs, false).unwrap();
+        assert_eq!(export.unit, WeightUnit::Kg);
+        assert_eq!(export.workouts.len(), 2);
+        assert_eq!(export.skipped.len(), 1, "the timed plank");
+
+        let upper = &export.workouts[0];
+        assert_eq!(upper.date, NaiveDate::from_ymd_opt(2024, 1, 8).unwrap());
+        assert_eq!(upper.notes.as_deref(), Some("Upper A: Deload week"));
+        let bench = &upper.exercises[0].sets;
+        assert_eq!(bench[0].notes.as_deref(), Some("warmup; Paused reps"));
+        assert_eq!(bench[2].notes.as_deref(), Some("failure"));
+        assert!((bench[1].weight_lbs - WeightUnit::Kg.to_lbs(82.5)).abs() < 1e-9);
+        assert_eq!(export.workouts[1].notes.as_deref(), Some("Lower A"));
+    }
+
+    #[test]
+    fn parses_fitnotes_by_date_in_file_order() {
+        let export = parse(AppFormat::FitNotes, &fitnotes(), WeightUnit::Lbs, false).unwrap();
+        assert_eq!(export.unit, WeightUnit::Kg);
+        assert_eq!(export.workouts.len(), 2);
+        assert_eq!(export.skipped.len(), 1);
+        let bench = &export.workouts[0].exercises[0];
+        assert_eq!(bench.sets.len(), 3);
+        assert_eq!(bench.sets[1].notes.as_deref(), Some("top set"));
+        assert_eq!(export.workouts[0].notes, None);
+    }
+
+    #[test]
+    fn suggests_close_exercise_names() {
+        assert!(exercises::name_similarity("Flat Barbell Bench Press", "Bench Press (Barbell)") > 0.8);
+        assert!(exercises::name_similarity("Lat Pulldown", "Squat") < SUGGEST_THRESHOLD);
+
+        let conn = test_conn();
+        exercises::find_or_create(&conn, "Bench Press (Barbell)").unwrap();
+        exercises::find_or_create(&conn, "lat pulldown").unwrap();
+        let export = parse(AppFormat::FitNotes, &fitnotes(), WeightUnit::Lbs, false).unwrap();
+        let names = match_names(&conn, &export).unwrap();
+        let sources: Vec<_> = names.iter().map(|m| m.source.as_str()).collect();
+        assert_eq!(sources, vec!["Barbell Squat", "Flat Barbell Bench Press", "Lat Pulldown"]);
+        assert_eq!(names[0].target, None);
+        assert_eq!(names[1].target.as_deref(), Some("Bench Press (Barbell)"));
+        assert!(names[1].needs_review());
+        assert_eq!((names[2].target.as_deref(), names[2].score), (Some("lat pulldown"), None));
+    }
+
+    #[test]
+    fn imports_once_and_skips_repeat_imports() {
+        let mut conn = test_conn();
+        let export = parse(AppFormat::Strong, &strong(), WeightUnit::Lbs, false).unwrap();
+        let mut names = match_names(&conn, &export).unwrap();
+        // Reviewed: log the Romanian deadlift under a shorter name.
+        names.iter_mut().find(|m| m.source.starts_with("Romanian")).unwrap().target = Some("RDL".into());
+
+        let preview = import(&mut conn, &export, &names, E1rmFormula::Epley, true).unwrap();
+        assert_eq!((preview.added(), preview.skipped()), (2, 2));
+        assert!(workouts::list_sessions(&conn).unwrap().is_empty());
+
+        let report = import(&mut conn, &export, &names, E1rmFormula::Epley, false).unwrap();
+        assert_eq!(report.added(), 2);
+        assert!(exercises::find(&conn, "RDL").unwrap().is_some());
+        let bench = exercises::find(&conn, "bench press (barbell)").unwrap().unwrap();
+        let sets = workouts::last_session_sets(&conn, bench).unwrap();
+        assert_eq!(sets.iter().map(|s| (s.set_order, s.reps)).collect::<Vec<_>>(), vec![(1, 10), (2, 5), (3, 5)]);
+        assert!(!records::load_current(&conn).unwrap().is_empty());
+
+        let again = import(&mut conn, &export, &names, E1rmFormula::Epley, false).unwrap();
+        assert_eq!((again.added(), again.duplicates()), (0, 2));
+        assert_eq!(workouts::list_sessions(&conn).unwrap().len(), 2);
+    }
+}
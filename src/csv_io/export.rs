@@ -0,0 +1,95 @@
+use super::{CsvError, CsvTable};
+use crate::db::{diet_cycles, exercises, weight_logs, workouts};
+use crate::settings::Settings;
+use crate::units::WeightUnit;
+use rusqlite::Connection;
+use std::io::Write;
+use std::path::Path;
+
+// Writes one table with an `id` column followed by `table.fields()`. Weigh-ins
+// use the bodyweight unit and sets the lift unit. Returns the row count.
+pub fn export_table(conn: &Connection, table: CsvTable, writer: impl Write, settings: &Settings) -> Result<usize, CsvError> {
+    let mut out = csv::Writer::from_writer(writer);
+    let mut header = vec!["id"];
+    header.extend(table.fields().iter().map(|f| f.header()));
+    out.write_record(&header)?;
+
+    let mut count = 0;
+    match table {
+        CsvTable::DietCycles => {
+            for cycle in diet_cycles::list(conn)? {
+                out.write_record([
+                    cycle.id.to_string(),
+                    cycle.phase.to_string(),
+                    cycle.start_date,
+                    cycle.planned_end_date,
+                    cycle.actual_end_date.unwrap_or_default(),
+                    (cycle.is_active as i64).to_string(),
+                    cycle.calorie_target.map(|t| t.to_string()).unwrap_or_default(),
+                    cycle.notes.unwrap_or_default(),
+                ])?;
+                count += 1;
+            }
+        }
+        CsvTable::WeightLogs => {
+            let unit = settings.bodyweight_unit;
+            for log in weight_logs::list(conn, None)? {
+                out.write_record([log.id.to_string(), log.log_date, weight_value(log.weight_lbs, unit), unit.to_string()])?;
+                count += 1;
+            }
+        }
+        CsvTable::Exercises => {
+            for exercise in exercises::list(conn)? {
+                out.write_record([
+                    exercise.id.to_string(),
+                    exercise.name,
+                    exercise.description.unwrap_or_default(),
+                    exercise.default_metric.map(|m| m.to_string()).unwrap_or_default(),
+                ])?;
+                count += 1;
+            }
+        }
+        CsvTable::WorkoutSessions => {
+            for session in workouts::list_sessions(conn)? {
+                out.write_record([session.id.to_string(), session.session_date, session.notes.unwrap_or_default()])?;
+                count += 1;
+            }
+        }
+        CsvTable::ExerciseSets => {
+            let unit = settings.lift_unit;
+            for (date, exercise, set) in workouts::list_sets(conn)? {
+                out.write_record([
+                    set.id.to_string(),
+                    set.workout_session_id.to_string(),
+                    date,
+                    exercise,
+                    set.set_order.to_string(),
+                    set.reps.to_string(),
+                    weight_value(set.weight_lbs, unit),
+                    unit.to_string(),
+                    set.rpe.map(|r| r.to_string()).unwrap_or_default(),
+                    set.notes.unwrap_or_default(),
+                ])?;
+                count += 1;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(count)
+}
+
+// Writes every table into `dir` under its `file_name`, creating the directory.
+pub fn export_dir(conn: &Connection, dir: &Path, settings: &Settings) -> Result<Vec<(CsvTable, usize)>, CsvError> {
+    std::fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for table in CsvTable::ALL {
+        let file = std::fs::File::create(dir.join(table.file_name()))?;
+        written.push((table, export_table(conn, table, file, settings)?));
+    }
+    Ok(written)
+}
+
+// Plain decimal (stone included) to three places, so it reads back within tolerance.
+fn weight_value(lbs: f64, unit: WeightUnit) -> String {
+    ((unit.convert_lbs(lbs) * 1000.0).round() / 1000.0).to_string()
+}
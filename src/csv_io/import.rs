@@ -0,0 +1,540 @@
+use super::{CsvError, CsvTable, DATE_FORMATS, Field, detect_date_format, detect_unit, normalize_header, parse_date};
+use crate::db::workouts::NewSet;
+use crate::db::{DbError, DbResult, DietCycle, diet_cycles, exercises, weight_logs, workouts};
+use crate::e1rm::E1rmFormula;
+use crate::db::records;
+use crate::types::{DietPhase, ExerciseMetric};
+use crate::units::{self, WeightUnit};
+use rusqlite::Connection;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+// Weights closer than this count as the same weigh-in or set.
+pub const WEIGHT_TOLERANCE_LBS: f64 = 0.05;
+
+// A parsed file: its header row and every record as text.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CsvSource {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvSource {
+    pub fn read(path: &Path) -> Result<Self, CsvError> {
+        Self::from_reader(std::fs::File::open(path)?)
+    }
+
+    pub fn from_reader(reader: impl Read) -> Result<Self, CsvError> {
+        let mut csv = csv::ReaderBuilder::new().flexible(true).trim(csv::Trim::All).from_reader(reader);
+        // Spreadsheet exports often lead with a byte order mark.
+        let headers = csv.headers()?.iter().map(|h| h.trim_start_matches('\u{feff}').to_string()).collect();
+        let mut rows = Vec::new();
+        for record in csv.records() {
+            rows.push(record?.iter().map(str::to_string).collect());
+        }
+        Ok(CsvSource { headers, rows })
+    }
+
+    pub fn column_values(&self, col: usize) -> impl Iterator<Item = &str> + Clone {
+        self.rows.iter().map(move |row| row.get(col).map_or("", String::as_str))
+    }
+}
+
+// The source column for each of `table.fields()`, in order.
+pub type ColumnMapping = Vec<Option<usize>>;
+
+// Exported session id to local session id, filled in as a sessions file is
+// imported so a sets file imported after it lands in the same sessions.
+pub type SessionIds = HashMap<i64, i64>;
+
+// Exact header names win over aliases, so "name" maps to `Name` even when an
+// "exercise" column is also present.
+pub fn auto_map(table: CsvTable, headers: &[String]) -> ColumnMapping {
+    table
+        .fields()
+        .iter()
+        .map(|field| {
+            let exact = normalize_header(field.header());
+            headers
+                .iter()
+                .position(|h| normalize_header(h) == exact)
+                .or_else(|| headers.iter().position(|h| field.matches_header(h)))
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImportOptions {
+    // `None` detects it from the first mapped date column.
+    pub date_format: Option<&'static str>,
+    // Chosen by the user; beats the unit named in the weight header.
+    pub unit: Option<WeightUnit>,
+    // For weights whose header, row and value don't name a unit.
+    pub default_unit: WeightUnit,
+    // Breaks the tie when dates could be day- or month-first.
+    pub month_first: bool,
+    // Personal records are rebuilt with this after sets are imported.
+    pub formula: E1rmFormula,
+    // Runs every check and insert, then rolls back.
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowStatus {
+    Added,
+    Duplicate,
+    Skipped(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RowOutcome {
+    // 1-based line in the file, counting the header.
+    pub line: usize,
+    pub summary: String,
+    pub status: RowStatus,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportReport {
+    pub table: CsvTable,
+    pub date_format: &'static str,
+    pub unit: WeightUnit,
+    pub dry_run: bool,
+    pub rows: Vec<RowOutcome>,
+}
+
+impl ImportReport {
+    pub fn added(&self) -> usize {
+        self.rows.iter().filter(|r| r.status == RowStatus::Added).count()
+    }
+
+    pub fn duplicates(&self) -> usize {
+        self.rows.iter().filter(|r| r.status == RowStatus::Duplicate).count()
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.rows.iter().filter(|r| matches!(r.status, RowStatus::Skipped(_))).count()
+    }
+
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} {}, {} duplicate, {} skipped",
+            self.table.label().to_lowercase(),
+            if self.dry_run { "would add" } else { "added" },
+            self.added(),
+            self.duplicates(),
+            self.skipped()
+        )
+    }
+}
+
+// The format for the table's first mapped date column.
+pub fn resolve_date_format(
+    table: CsvTable,
+    source: &CsvSource,
+    mapping: &[Option<usize>],
+    month_first: bool,
+) -> Result<&'static str, CsvError> {
+    let date_col = table.fields().iter().zip(mapping).find_map(|(field, col)| col.filter(|_| field.is_date()));
+    let Some(col) = date_col else { return Ok(DATE_FORMATS[0]) };
+    // An empty table (e.g. an export with no cycles yet) has nothing to detect.
+    if source.column_values(col).all(|v| v.trim().is_empty()) {
+        return Ok(DATE_FORMATS[0]);
+    }
+    detect_date_format(source.column_values(col), month_first).ok_or_else(|| {
+        let header = source.headers.get(col).map_or("date", String::as_str);
+        CsvError::Invalid(format!("couldn't work out the date format of the \"{}\" column; pick one.", header))
+    })
+}
+
+// The unit named by the mapped weight column's header, if any.
+pub fn column_unit(table: CsvTable, source: &CsvSource, mapping: &[Option<usize>]) -> Option<WeightUnit> {
+    let idx = table.fields().iter().position(|f| *f == Field::Weight)?;
+    detect_unit(source.headers.get(mapping[idx]?)?)
+}
+
+// Imports every row it can in one transaction. Rows that fail validation are
+// reported as skipped; database failures abort the whole file. `sessions` is
+// only updated when the import is committed.
+pub fn import(
+    conn: &mut Connection,
+    table: CsvTable,
+    source: &CsvSource,
+    mapping: &[Option<usize>],
+    options: &ImportOptions,
+    sessions: &mut SessionIds,
+) -> Result<ImportReport, CsvError> {
+    for (field, col) in table.fields().iter().zip(mapping) {
+        if col.is_none() && table.is_required(*field) {
+            return Err(CsvError::Invalid(format!("map a column to \"{}\" first.", field.header())));
+        }
+    }
+    let date_format = match options.date_format {
+        Some(format) => format,
+        None => resolve_date_format(table, source, mapping, options.month_first)?,
+    };
+    let unit = options.unit.or_else(|| column_unit(table, source, mapping)).unwrap_or(options.default_unit);
+    let today = chrono::Local::now().date_naive();
+
+    // Exports lead with the row's own id; sessions are matched up through it.
+    let id_col = source.headers.iter().position(|h| normalize_header(h) == "id");
+
+    let tx = conn.transaction()?;
+    let mut rows = Vec::new();
+    let mut set_counters = HashMap::new();
+    let mut session_ids = sessions.clone();
+    for (idx, record) in source.rows.iter().enumerate() {
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+        let id = id_col.and_then(|col| record.get(col)).and_then(|cell| cell.trim().parse().ok());
+        let row = Row { fields: table.fields(), record, mapping, date_format, unit, id };
+        let result = match table {
+            CsvTable::DietCycles => import_diet_cycle(&tx, &row, today),
+            CsvTable::WeightLogs => import_weight_log(&tx, &row, today),
+            CsvTable::Exercises => import_exercise(&tx, &row),
+            CsvTable::WorkoutSessions => import_session(&tx, &row, &mut session_ids),
+            CsvTable::ExerciseSets => import_set(&tx, &row, &mut set_counters, &mut session_ids),
+        };
+        let (status, summary) = match result {
+            Ok(done) => done,
+            Err(e @ (DbError::Invalid(_) | DbError::Overlap { .. })) => (RowStatus::Skipped(e.to_string()), record.join(", ")),
+            Err(e) => return Err(e.into()),
+        };
+        rows.push(RowOutcome { line: idx + 2, summary, status });
+    }
+
+    let report = ImportReport { table, date_format, unit, dry_run: options.dry_run, rows };
+    if options.dry_run {
+        tx.rollback()?;
+    } else {
+        tx.commit()?;
+        *sessions = session_ids;
+        if table == CsvTable::ExerciseSets && report.added() > 0 {
+            records::rebuild_all(conn, options.formula)?;
+        }
+    }
+    Ok(report)
+}
+
+// Imports each table's `file_name` found in `dir`, mapping columns automatically.
+pub fn import_dir(conn: &mut Connection, dir: &Path, options: &ImportOptions) -> Result<Vec<ImportReport>, CsvError> {
+    let mut reports = Vec::new();
+    let mut sessions = SessionIds::new();
+    for table in CsvTable::ALL {
+        let path = dir.join(table.file_name());
+        if !path.exists() {
+            continue;
+        }
+        let source = CsvSource::read(&path)?;
+        let mapping = auto_map(table, &source.headers);
+        reports.push(import(conn, table, &source, &mapping, options, &mut sessions)?);
+    }
+    if reports.is_empty() {
+        return Err(CsvError::Invalid(format!("no exported csv files found in {}.", dir.display())));
+    }
+    Ok(reports)
+}
+
+struct Row<'a> {
+    fields: &'static [Field],
+    record: &'a [String],
+    mapping: &'a [Option<usize>],
+    date_format: &'static str,
+    // The column's unit; a `unit` cell or a suffix on the value overrides it.
+    unit: WeightUnit,
+    // The exported `id`, when the file has one.
+    id: Option<i64>,
+}
+
+impl Row<'_> {
+    fn text(&self, field: Field) -> Option<&str> {
+        let idx = self.fields.iter().position(|f| *f == field)?;
+        let cell = self.record.get(self.mapping[idx]?)?.trim();
+        (!cell.is_empty()).then_some(cell)
+    }
+
+    fn required(&self, field: Field) -> DbResult<&str> {
+        self.text(field).ok_or_else(|| DbError::Invalid(format!("missing {}.", field.header())))
+    }
+
+    // The date in stored (ISO) form.
+    fn date(&self, field: Field) -> DbResult<Option<String>> {
+        let Some(text) = self.text(field) else { return Ok(None) };
+        match parse_date(text, self.date_format) {
+            Some(date) => Ok(Some(date.format("%Y-%m-%d").to_string())),
+            None => Err(DbError::Invalid(format!("can't read {} \"{}\" as {}.", field.header(), text, self.date_format))),
+        }
+    }
+
+    fn required_date(&self, field: Field) -> DbResult<String> {
+        self.date(field)?.ok_or_else(|| DbError::Invalid(format!("missing {}.", field.header())))
+    }
+
+    fn number<T: FromStr>(&self, field: Field) -> DbResult<Option<T>> {
+        let Some(text) = self.text(field) else { return Ok(None) };
+        text.parse()
+            .map(Some)
+            .map_err(|_| DbError::Invalid(format!("{} \"{}\" isn't a number.", field.header(), text)))
+    }
+
+    fn row_unit(&self) -> DbResult<WeightUnit> {
+        match self.text(Field::Unit) {
+            Some(text) => text.parse().map_err(DbError::Invalid),
+            None => Ok(self.unit),
+        }
+    }
+
+    fn weight_lbs(&self) -> DbResult<f64> {
+        units::parse_weight(self.required(Field::Weight)?, self.row_unit()?).map_err(DbError::Invalid)
+    }
+}
+
+type RowResult = DbResult<(RowStatus, String)>;
+
+fn import_diet_cycle(conn: &Connection, row: &Row, today: chrono::NaiveDate) -> RowResult {
+    let phase: DietPhase = row.required(Field::Phase)?.parse().map_err(DbError::Invalid)?;
+    let start_date = row.required_date(Field::StartDate)?;
+    let summary = format!("{} from {}", phase, start_date);
+    if diet_cycles::find(conn, phase, &start_date)?.is_some() {
+        return Ok((RowStatus::Duplicate, summary));
+    }
+    let calorie_target = match row.text(Field::CalorieTarget) {
+        Some(text) => diet_cycles::parse_calorie_target(text).map_err(DbError::Invalid)?,
+        None => None,
+    };
+    let cycle = DietCycle {
+        id: 0,
+        phase,
+        start_date,
+        planned_end_date: row.required_date(Field::PlannedEndDate)?,
+        actual_end_date: row.date(Field::ActualEndDate)?,
+        notes: row.text(Field::Notes).map(str::to_string),
+        is_active: row.text(Field::Active).is_some_and(|t| matches!(t.to_lowercase().as_str(), "1" | "true" | "yes")),
+        calorie_target,
+    };
+    diet_cycles::insert_existing(conn, &cycle, today)?;
+    Ok((RowStatus::Added, summary))
+}
+
+fn import_weight_log(conn: &Connection, row: &Row, today: chrono::NaiveDate) -> RowResult {
+    let date = row.required_date(Field::Date)?;
+    let weight_lbs = row.weight_lbs()?;
+    if weight_lbs <= 0.0 {
+        return Err(DbError::Invalid("weight must be a positive number.".to_string()));
+    }
+    let summary = format!("{} {}", date, row.row_unit()?.format(weight_lbs));
+    if weight_logs::has_close_match(conn, &date, weight_lbs, WEIGHT_TOLERANCE_LBS)? {
+        return Ok((RowStatus::Duplicate, summary));
+    }
+    let cycle_id = match parse_date(&date, "%Y-%m-%d") {
+        Some(day) => diet_cycles::covering(conn, day, today)?,
+        None => None,
+    };
+    weight_logs::insert(conn, cycle_id, &date, weight_lbs)?;
+    Ok((RowStatus::Added, summary))
+}
+
+fn import_exercise(conn: &Connection, row: &Row) -> RowResult {
+    let name = row.required(Field::Name)?;
+    if exercises::find(conn, name)?.is_some() {
+        return Ok((RowStatus::Duplicate, name.to_string()));
+    }
+    let metric = row.text(Field::DefaultMetric).map(str::parse::<ExerciseMetric>).transpose().map_err(DbError::Invalid)?;
+    exercises::create(conn, name, row.text(Field::Description), metric)?;
+    Ok((RowStatus::Added, name.to_string()))
+}
+
+fn import_session(conn: &Connection, row: &Row, sessions: &mut SessionIds) -> RowResult {
+    let date = row.required_date(Field::Date)?;
+    let notes = row.text(Field::Notes);
+    let summary = match notes {
+        Some(notes) => format!("{}: {}", date, notes),
+        None => date.clone(),
+    };
+    let (status, session_id) = match workouts::find_session(conn, &date, notes)? {
+        Some(id) => (RowStatus::Duplicate, id),
+        None => (RowStatus::Added, workouts::create_session(conn, &date, notes)?),
+    };
+    if let Some(id) = row.id {
+        sessions.insert(id, session_id);
+    }
+    Ok((status, summary))
+}
+
+// The local session for a set. With an exported session id that no sessions
+// file has mapped yet, it takes the first session on the date that no other
+// id has claimed, so two sessions on one day stay apart. Without the column,
+// every set on a date goes to that day's first session.
+fn set_session(conn: &Connection, date: &str, exported_id: Option<i64>, sessions: &mut SessionIds) -> DbResult<i64> {
+    let Some(exported_id) = exported_id else {
+        return match workouts::session_on(conn, date)? {
+            Some(id) => Ok(id),
+            None => workouts::create_session(conn, date, None),
+        };
+    };
+    if let Some(id) = sessions.get(&exported_id) {
+        return Ok(*id);
+    }
+    let unclaimed = workouts::sessions_on(conn, date)?.into_iter().find(|id| !sessions.values().any(|v| v == id));
+    let session_id = match unclaimed {
+        Some(id) => id,
+        None => workouts::create_session(conn, date, None)?,
+    };
+    sessions.insert(exported_id, session_id);
+    Ok(session_id)
+}
+
+// Without a set order column, sets are numbered in file order per session and exercise.
+fn import_set(
+    conn: &Connection,
+    row: &Row,
+    counters: &mut HashMap<(String, String), i64>,
+    sessions: &mut SessionIds,
+) -> RowResult {
+    let date = row.required_date(Field::Date)?;
+    let name = row.required(Field::Exercise)?;
+    let reps: i64 = row.number(Field::Reps)?.ok_or_else(|| DbError::Invalid("missing reps.".to_string()))?;
+    if reps <= 0 {
+        return Err(DbError::Invalid("reps must be a positive whole number.".to_string()));
+    }
+    let weight_lbs = row.weight_lbs()?;
+    if weight_lbs < 0.0 {
+        return Err(DbError::Invalid("weight can't be negative.".to_string()));
+    }
+    let rpe: Option<f64> = row.number(Field::Rpe)?;
+    if rpe.is_some_and(|r| !(1.0..=10.0).contains(&r)) {
+        return Err(DbError::Invalid("rpe must be between 1 and 10.".to_string()));
+    }
+    let exported_session: Option<i64> = row.number(Field::SessionId)?;
+    let session_key = exported_session.map_or_else(|| date.clone(), |id| id.to_string());
+    let counter = counters.entry((session_key, name.to_lowercase())).or_insert(0);
+    *counter += 1;
+    let set_order = row.number(Field::SetOrder)?.unwrap_or(*counter);
+
+    let set = NewSet { reps, weight_lbs, rpe, notes: row.text(Field::Notes).map(str::to_string) };
+    let summary = format!("{} {} #{}: {} x {}", date, name, set_order, reps, row.row_unit()?.format(weight_lbs));
+    let session_id = set_session(conn, &date, exported_session, sessions)?;
+    let exercise_id = exercises::find_or_create(conn, name)?;
+    if workouts::set_exists(conn, session_id, exercise_id, set_order, &set, WEIGHT_TOLERANCE_LBS)? {
+        return Ok((RowStatus::Duplicate, summary));
+    }
+    workouts::insert_set(conn, session_id, exercise_id, set_order, &set)?;
+    Ok((RowStatus::Added, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::csv_io::export;
+    use crate::db::{test_conn, test_dir};
+    use crate::settings::Settings;
+
+    fn options(dry_run: bool) -> ImportOptions {
+        ImportOptions {
+            date_format: None,
+            unit: None,
+            default_unit: WeightUnit::Lbs,
+            month_first: false,
+            formula: E1rmFormula::Epley,
+            dry_run,
+        }
+    }
+
+    fn source(text: &str) -> CsvSource {
+        CsvSource::from_reader(text.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn maps_columns_detects_units_and_skips_duplicates() {
+        let mut conn = test_conn();
+        weight_logs::insert(&conn, None, "2025-03-01", 180.0).unwrap();
+        let file = source("Log Date,Weight (kg),Comment\n01/03/2025,81.65,\n02/03/2025,81.2,\n03/03/2025,heavy,\n");
+        let mapping = auto_map(CsvTable::WeightLogs, &file.headers);
+        assert_eq!(mapping, vec![Some(0), Some(1), None]);
+
+        let preview = import(&mut conn, CsvTable::WeightLogs, &file, &mapping, &options(true), &mut SessionIds::new()).unwrap();
+        assert_eq!(preview.date_format, "%d/%m/%Y");
+        assert_eq!(preview.unit, WeightUnit::Kg);
+        assert_eq!((preview.added(), preview.duplicates(), preview.skipped()), (1, 1, 1));
+        assert_eq!(preview.rows[2].line, 4);
+        assert_eq!(weight_logs::list(&conn, None).unwrap().len(), 1, "dry run leaves the database alone");
+
+        let report = import(&mut conn, CsvTable::WeightLogs, &file, &mapping, &options(false), &mut SessionIds::new()).unwrap();
+        assert_eq!(report.added(), 1);
+        let logs = weight_logs::list(&conn, None).unwrap();
+        assert_eq!(logs[1].log_date, "2025-03-02");
+        assert!((logs[1].weight_lbs - units::WeightUnit::Kg.to_lbs(81.2)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn export_round_trips_through_import() {
+        let mut conn = test_conn();
+        let today = chrono::NaiveDate::from_ymd_opt(2025, 4, 1).unwrap();
+        let start = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let planned = chrono::NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        diet_cycles::create(&mut conn, DietPhase::Cut, start, planned, Some(2100), false, today).unwrap();
+        weight_logs::insert(&conn, Some(1), "2025-01-05", 200.0).unwrap();
+        let sets = vec![workouts::NewExercise {
+            name: "Squat".into(),
+            sets: vec![NewSet { reps: 5, weight_lbs: 225.0, rpe: Some(8.0), notes: None }],
+        }];
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        workouts::insert(&mut conn, day, Some("legs"), &sets, E1rmFormula::Epley).unwrap();
+
+        let settings = Settings { bodyweight_unit: WeightUnit::Stone, lift_unit: WeightUnit::Kg, ..Settings::default() };
+        let dir = test_dir("csv");
+        let written = export::export_dir(&conn, &dir, &settings).unwrap();
+        assert!(written.iter().all(|(_, count)| *count == 1));
+
+        // Everything already exists, so the same database sees only duplicates.
+        for report in import_dir(&mut conn, &dir, &options(false)).unwrap() {
+            assert_eq!((report.added(), report.duplicates(), report.skipped()), (0, 1, 0), "{}", report.summary());
+        }
+
+        let mut fresh = test_conn();
+        let reports = import_dir(&mut fresh, &dir, &options(false)).unwrap();
+        assert!(reports.iter().all(|r| r.added() == 1), "{:?}", reports);
+        let logs = weight_logs::list(&fresh, None).unwrap();
+        assert_eq!(logs[0].diet_cycle_id, Some(1));
+        assert!((logs[0].weight_lbs - 200.0).abs() < WEIGHT_TOLERANCE_LBS);
+        let cycle = &diet_cycles::list(&fresh).unwrap()[0];
+        assert_eq!((cycle.calorie_target, cycle.is_active), (Some(2100), true));
+        let squat = exercises::find(&fresh, "squat").unwrap().unwrap();
+        assert_eq!(workouts::last_session_sets(&fresh, squat).unwrap()[0].rpe, Some(8.0));
+        assert!(!records::load_current(&fresh).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn sets_keep_to_their_own_session_on_round_trip() {
+        let mut conn = test_conn();
+        let day = chrono::NaiveDate::from_ymd_opt(2025, 2, 3).unwrap();
+        let lift = |name: &str, weight| {
+            vec![workouts::NewExercise { name: name.into(), sets: vec![NewSet { reps: 5, weight_lbs: weight, rpe: None, notes: None }] }]
+        };
+        workouts::insert(&mut conn, day, Some("morning"), &lift("Squat", 225.0), E1rmFormula::Epley).unwrap();
+        workouts::insert(&mut conn, day, Some("evening"), &lift("Bench Press", 185.0), E1rmFormula::Epley).unwrap();
+
+        let dir = test_dir("csv-sessions");
+        export::export_dir(&conn, &dir, &Settings::default()).unwrap();
+        let mut fresh = test_conn();
+        import_dir(&mut fresh, &dir, &options(false)).unwrap();
+
+        let sessions = workouts::list_sessions(&fresh).unwrap();
+        assert_eq!(sessions.iter().map(|s| s.notes.as_deref()).collect::<Vec<_>>(), vec![Some("morning"), Some("evening")]);
+        let by_session: Vec<_> =
+            workouts::list_sets(&fresh).unwrap().into_iter().map(|(_, name, set)| (set.workout_session_id, name)).collect();
+        assert_eq!(by_session, vec![(sessions[0].id, "Squat".to_string()), (sessions[1].id, "Bench Press".to_string())]);
+
+        // The sets file alone still keeps the two sessions apart.
+        let mut sets_only = test_conn();
+        let file = CsvSource::read(&dir.join(CsvTable::ExerciseSets.file_name())).unwrap();
+        let mapping = auto_map(CsvTable::ExerciseSets, &file.headers);
+        import(&mut sets_only, CsvTable::ExerciseSets, &file, &mapping, &options(false), &mut SessionIds::new()).unwrap();
+        assert_eq!(workouts::sessions_on(&sets_only, "2025-02-03").unwrap().len(), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
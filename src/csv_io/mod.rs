@@ -0,0 +1,306 @@
+// CSV export and import for the core tables. Exports always write ISO dates
+// and weights in the user's units with a `unit` column, so an export imports
+// back cleanly; imports map columns by header and detect dates and units.
+pub mod apps;
+pub mod export;
+pub mod import;
+
+use crate::db::DbError;
+use crate::units::WeightUnit;
+use chrono::NaiveDate;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CsvTable {
+    DietCycles,
+    WeightLogs,
+    Exercises,
+    WorkoutSessions,
+    ExerciseSets,
+}
+
+impl CsvTable {
+    // Import order for a directory: cycles before the weigh-ins they hold,
+    // sessions before their sets.
+    pub const ALL: [CsvTable; 5] = [
+        CsvTable::DietCycles,
+        CsvTable::WeightLogs,
+        CsvTable::Exercises,
+        CsvTable::WorkoutSessions,
+        CsvTable::ExerciseSets,
+    ];
+
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            CsvTable::DietCycles => "diet_cycles.csv",
+            CsvTable::WeightLogs => "weight_logs.csv",
+            CsvTable::Exercises => "exercises.csv",
+            CsvTable::WorkoutSessions => "workout_sessions.csv",
+            CsvTable::ExerciseSets => "exercise_sets.csv",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CsvTable::DietCycles => "Diet cycles",
+            CsvTable::WeightLogs => "Weigh-ins",
+            CsvTable::Exercises => "Exercises",
+            CsvTable::WorkoutSessions => "Workout sessions",
+            CsvTable::ExerciseSets => "Exercise sets",
+        }
+    }
+
+    // Columns written on export (after `id`) and mapped on import.
+    pub fn fields(&self) -> &'static [Field] {
+        match self {
+            CsvTable::DietCycles => &[
+                Field::Phase,
+                Field::StartDate,
+                Field::PlannedEndDate,
+                Field::ActualEndDate,
+                Field::Active,
+                Field::CalorieTarget,
+                Field::Notes,
+            ],
+            CsvTable::WeightLogs => &[Field::Date, Field::Weight, Field::Unit],
+            CsvTable::Exercises => &[Field::Name, Field::Description, Field::DefaultMetric],
+            CsvTable::WorkoutSessions => &[Field::Date, Field::Notes],
+            CsvTable::ExerciseSets => &[
+                Field::SessionId,
+                Field::Date,
+                Field::Exercise,
+                Field::SetOrder,
+                Field::Reps,
+                Field::Weight,
+                Field::Unit,
+                Field::Rpe,
+                Field::Notes,
+            ],
+        }
+    }
+
+    pub fn is_required(&self, field: Field) -> bool {
+        match self {
+            CsvTable::DietCycles => matches!(field, Field::Phase | Field::StartDate | Field::PlannedEndDate),
+            CsvTable::WeightLogs => matches!(field, Field::Date | Field::Weight),
+            CsvTable::Exercises => field == Field::Name,
+            CsvTable::WorkoutSessions => field == Field::Date,
+            CsvTable::ExerciseSets => matches!(field, Field::Date | Field::Exercise | Field::Reps | Field::Weight),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Field {
+    Date,
+    Weight,
+    Unit,
+    Phase,
+    StartDate,
+    PlannedEndDate,
+    ActualEndDate,
+    Active,
+    CalorieTarget,
+    Notes,
+    Name,
+    Description,
+    DefaultMetric,
+    Exercise,
+    SetOrder,
+    Reps,
+    Rpe,
+    // The exported id of the session a set belongs to.
+    SessionId,
+}
+
+impl Field {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Field::Date => "date",
+            Field::Weight => "weight",
+            Field::Unit => "unit",
+            Field::Phase => "phase",
+            Field::StartDate => "start_date",
+            Field::PlannedEndDate => "planned_end_date",
+            Field::ActualEndDate => "actual_end_date",
+            Field::Active => "is_active",
+            Field::CalorieTarget => "calorie_target",
+            Field::Notes => "notes",
+            Field::Name => "name",
+            Field::Description => "description",
+            Field::DefaultMetric => "default_metric",
+            Field::Exercise => "exercise",
+            Field::SetOrder => "set_order",
+            Field::Reps => "reps",
+            Field::Rpe => "rpe",
+            Field::SessionId => "session_id",
+        }
+    }
+
+    // Other headers that mean the same column, in `normalize_header` form.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Field::Date => &["logdate", "sessiondate", "workoutdate", "day"],
+            Field::Weight => &["bodyweight", "weightlbs", "weightkg", "load"],
+            Field::Unit => &["units", "weightunit"],
+            Field::Phase => &["dietphase", "type"],
+            Field::StartDate => &["start", "started"],
+            Field::PlannedEndDate => &["plannedend", "targetend"],
+            Field::ActualEndDate => &["actualend", "enddate", "ended"],
+            Field::Active => &["active"],
+            Field::CalorieTarget => &["calories", "kcal", "target"],
+            Field::Notes => &["note", "comment", "comments"],
+            Field::Name => &["exercise", "exercisename"],
+            Field::Description => &["desc"],
+            Field::DefaultMetric => &["metric", "defaultmetrictotrack"],
+            Field::Exercise => &["exercisename", "name", "lift"],
+            Field::SetOrder => &["set", "setnumber", "setno", "order"],
+            Field::Reps => &["rep", "repetitions"],
+            Field::Rpe => &[],
+            Field::SessionId => &["workoutsessionid", "workoutid"],
+        }
+    }
+
+    pub fn is_date(&self) -> bool {
+        matches!(self, Field::Date | Field::StartDate | Field::PlannedEndDate | Field::ActualEndDate)
+    }
+
+    // Whether `header` (as written in a file) names this field.
+    pub fn matches_header(&self, header: &str) -> bool {
+        let normalized = normalize_header(header);
+        normalized == normalize_header(self.header()) || self.aliases().contains(&normalized.as_str())
+    }
+}
+
+// Lowercase alphanumerics with any bracketed part dropped: "Weight (kg)" -> "weight".
+pub fn normalize_header(header: &str) -> String {
+    let mut out = String::new();
+    let mut depth = 0;
+    for c in header.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ if depth == 0 && c.is_alphanumeric() => out.extend(c.to_lowercase()),
+            _ => {}
+        }
+    }
+    out
+}
+
+// A unit named anywhere in a header, e.g. "weight_kg" or "Weight (lbs)".
+pub fn detect_unit(header: &str) -> Option<WeightUnit> {
+    header
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .filter(|word| !word.is_empty())
+        .find_map(|word| word.parse().ok())
+}
+
+// Formats tried when detecting a date column, as chrono patterns.
+pub const DATE_FORMATS: [&str; 8] =
+    ["%Y-%m-%d", "%Y/%m/%d", "%d/%m/%Y", "%m/%d/%Y", "%d.%m.%Y", "%d-%m-%Y", "%m-%d-%Y", "%d %b %Y"];
+
+// e.g. "dd/mm/yyyy" for "%d/%m/%Y".
+pub fn date_format_label(format: &str) -> String {
+    format.replace("%Y", "yyyy").replace("%m", "mm").replace("%d", "dd").replace("%b", "mon")
+}
+
+// Where exports go unless told otherwise: an `export` folder next to the database.
+pub fn default_dir(db_path: &Path) -> PathBuf {
+    db_path.parent().unwrap_or(Path::new("")).join("export")
+}
+
+// Reads `text` with `format`, ignoring a trailing time such as " 18:32:10",
+// "T18:32" or ", 18:32".
+pub fn parse_date(text: &str, format: &str) -> Option<NaiveDate> {
+    let (date, rest) = NaiveDate::parse_and_remainder(text.trim(), format).ok()?;
+    (rest.is_empty() || rest.starts_with([' ', 'T', ','])).then_some(date)
+}
+
+// The first format every non-empty value parses with. Day-first and
+// month-first both fit when no day is past the 12th; `month_first` breaks the tie.
+pub fn detect_date_format<'a>(values: impl Iterator<Item = &'a str> + Clone, month_first: bool) -> Option<&'static str> {
+    let mut formats = DATE_FORMATS.to_vec();
+    if month_first {
+        formats.sort_by_key(|f| !f.starts_with("%m"));
+    }
+    formats.into_iter().find(|format| {
+        let mut values = values.clone().map(str::trim).filter(|v| !v.is_empty()).peekable();
+        values.peek().is_some() && values.all(|v| parse_date(v, format).is_some())
+    })
+}
+
+#[derive(Debug)]
+pub enum CsvError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    Db(DbError),
+    // A problem with the file as a whole; the message is user-facing.
+    Invalid(String),
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Io(e) => write!(f, "{}", e),
+            CsvError::Csv(e) => write!(f, "{}", e),
+            CsvError::Db(e) => write!(f, "{}", e),
+            CsvError::Invalid(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+impl From<std::io::Error> for CsvError {
+    fn from(e: std::io::Error) -> Self {
+        CsvError::Io(e)
+    }
+}
+
+impl From<csv::Error> for CsvError {
+    fn from(e: csv::Error) -> Self {
+        CsvError::Csv(e)
+    }
+}
+
+impl From<DbError> for CsvError {
+    fn from(e: DbError) -> Self {
+        CsvError::Db(e)
+    }
+}
+
+impl From<rusqlite::Error> for CsvError {
+    fn from(e: rusqlite::Error) -> Self {
+        CsvError::Db(e.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_headers_and_units() {
+        assert!(Field::Weight.matches_header("Weight (kg)"));
+        assert!(Field::Date.matches_header("Log Date"));
+        assert!(Field::SetOrder.matches_header("Set #"));
+        assert!(!Field::Reps.matches_header("weight"));
+        assert_eq!(detect_unit("Weight (kg)"), Some(WeightUnit::Kg));
+        assert_eq!(detect_unit("weight_lbs"), Some(WeightUnit::Lbs));
+        assert_eq!(detect_unit("weight"), None);
+    }
+
+    #[test]
+    fn detects_date_formats() {
+        let iso = ["2025-03-01", "2025-03-14 18:02:11", ""];
+        assert_eq!(detect_date_format(iso.iter().copied(), false), Some("%Y-%m-%d"));
+        let day_first = ["01/03/2025", "14/03/2025"];
+        assert_eq!(detect_date_format(day_first.iter().copied(), true), Some("%d/%m/%Y"));
+        let ambiguous = ["01/03/2025", "02/03/2025"];
+        assert_eq!(detect_date_format(ambiguous.iter().copied(), false), Some("%d/%m/%Y"));
+        assert_eq!(detect_date_format(ambiguous.iter().copied(), true), Some("%m/%d/%Y"));
+        assert_eq!(detect_date_format(["soon"].iter().copied(), false), None);
+        assert_eq!(parse_date("1 Mar 2025", "%d %b %Y"), NaiveDate::from_ymd_opt(2025, 3, 1));
+    }
+}
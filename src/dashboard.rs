@@ -0,0 +1,299 @@
+use crate::db::{DbResult, diet_cycles, weight_logs};
+use crate::db::workouts::{self, WorkoutSummary};
+use crate::db::records::{self, PersonalRecord};
+use crate::smoothing::{self, SmoothingMethod};
+use crate::types::DietPhase;
+use chrono::NaiveDate;
+use log::error;
+use rusqlite::Connection;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Days of weigh-ins the sparkline covers, ending at the latest one.
+pub const SPARKLINE_DAYS: i64 = 30;
+// Weeks shown in the training frequency widget, ending this week.
+pub const FREQUENCY_WEEKS: usize = 8;
+pub const RECENT_RECORD_LIMIT: i64 = 5;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum DashboardWidget {
+    Weight,
+    Sparkline,
+    DietCycle,
+    LastWorkout,
+    TrainingFrequency,
+    RecentRecords,
+}
+
+impl DashboardWidget {
+    pub const ALL: [DashboardWidget; 6] = [
+        DashboardWidget::Weight,
+        DashboardWidget::Sparkline,
+        DashboardWidget::DietCycle,
+        DashboardWidget::LastWorkout,
+        DashboardWidget::TrainingFrequency,
+        DashboardWidget::RecentRecords,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DashboardWidget::Weight => "Current weight",
+            DashboardWidget::Sparkline => "Weight trend",
+            DashboardWidget::DietCycle => "Diet cycle",
+            DashboardWidget::LastWorkout => "Last workout",
+            DashboardWidget::TrainingFrequency => "Training frequency",
+            DashboardWidget::RecentRecords => "Recent PRs",
+        }
+    }
+}
+
+impl Display for DashboardWidget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for DashboardWidget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DashboardWidget::ALL
+            .into_iter()
+            .find(|widget| widget.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown dashboard widget: {}", s))
+    }
+}
+
+// Which widgets the dashboard shows, in order. Stored as a comma-separated
+// list with hidden widgets prefixed by '-', e.g. "Weight,-Sparkline,DietCycle".
+#[derive(Clone, Debug, PartialEq)]
+pub struct DashboardLayout {
+    // (widget, visible); every widget appears exactly once.
+    pub widgets: Vec<(DashboardWidget, bool)>,
+}
+
+impl Default for DashboardLayout {
+    fn default() -> Self {
+        DashboardLayout { widgets: DashboardWidget::ALL.into_iter().map(|w| (w, true)).collect() }
+    }
+}
+
+impl DashboardLayout {
+    pub fn visible(&self) -> impl Iterator<Item = DashboardWidget> + '_ {
+        self.widgets.iter().filter(|(_, visible)| *visible).map(|(widget, _)| *widget)
+    }
+
+    // Swaps the widget at `idx` with its neighbour; out of range does nothing.
+    pub fn move_up(&mut self, idx: usize) {
+        if idx > 0 && idx < self.widgets.len() {
+            self.widgets.swap(idx - 1, idx);
+        }
+    }
+
+    pub fn move_down(&mut self, idx: usize) {
+        if idx + 1 < self.widgets.len() {
+            self.widgets.swap(idx, idx + 1);
+        }
+    }
+}
+
+impl Display for DashboardLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self
+            .widgets
+            .iter()
+            .map(|(widget, visible)| if *visible { widget.to_string() } else { format!("-{}", widget) })
+            .collect();
+        write!(f, "{}", entries.join(","))
+    }
+}
+
+// Widgets missing from the stored list (e.g. added in a later version) are
+// appended, visible.
+impl FromStr for DashboardLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut widgets: Vec<(DashboardWidget, bool)> = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (name, visible) = match entry.strip_prefix('-') {
+                Some(name) => (name, false),
+                None => (entry, true),
+            };
+            let widget: DashboardWidget = name.parse()?;
+            if widgets.iter().any(|(w, _)| *w == widget) {
+                return Err(format!("dashboard widget listed twice: {}", widget));
+            }
+            widgets.push((widget, visible));
+        }
+        for widget in DashboardWidget::ALL {
+            if !widgets.iter().any(|(w, _)| *w == widget) {
+                widgets.push((widget, true));
+            }
+        }
+        Ok(DashboardLayout { widgets })
+    }
+}
+
+// All weights below are in lbs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightSummary {
+    pub logged_on: NaiveDate,
+    pub latest_lbs: f64,
+    pub trend_lbs: f64,
+    // Trend now minus the trend at the last weigh-in at least a week earlier.
+    pub change_7d_lbs: Option<f64>,
+    // (days since epoch, lbs) over the last `SPARKLINE_DAYS`.
+    pub recent: Vec<(f64, f64)>,
+    pub recent_trend: Vec<(f64, f64)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CycleStatus {
+    pub phase: DietPhase,
+    // 1 on the start date.
+    pub day: i64,
+    pub planned_days: i64,
+    // Negative once the planned end has passed.
+    pub days_remaining: i64,
+    pub calorie_target: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DashboardData {
+    pub weight: Option<WeightSummary>,
+    pub cycle: Option<CycleStatus>,
+    pub last_workout: Option<WorkoutSummary>,
+    // Workout days per week, oldest first; the last entry is the 7 days ending today.
+    pub weekly_sessions: Vec<usize>,
+    pub recent_records: Vec<PersonalRecord>,
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+pub fn load(conn: &Connection, smoothing: SmoothingMethod, window_days: usize, today: NaiveDate) -> DbResult<DashboardData> {
+    Ok(DashboardData {
+        weight: weight_summary(conn, smoothing, window_days)?,
+        cycle: cycle_status(conn, today)?,
+        last_workout: workouts::latest_session_summary(conn)?,
+        weekly_sessions: weekly_sessions(conn, today)?,
+        recent_records: records::load_history(conn, None, RECENT_RECORD_LIMIT)?,
+    })
+}
+
+fn weight_summary(conn: &Connection, smoothing: SmoothingMethod, window_days: usize) -> DbResult<Option<WeightSummary>> {
+    let mut series = Vec::new();
+    for log in weight_logs::list(conn, None)? {
+        match parse_date(&log.log_date) {
+            Some(date) => series.push((smoothing::date_to_x(date), log.weight_lbs)),
+            None => error!("skipping weight log with bad date {}", log.log_date),
+        }
+    }
+    let trend = smoothing::smooth(&series, smoothing, window_days);
+    let (Some(&(last_x, latest_lbs)), Some(&(_, trend_lbs))) = (series.last(), trend.last()) else { return Ok(None) };
+    let Some(logged_on) = smoothing::x_to_date(last_x) else { return Ok(None) };
+    let change_7d_lbs = trend.iter().rev().find(|(x, _)| *x <= last_x - 7.0).map(|(_, earlier)| trend_lbs - earlier);
+    let recent_from = last_x - SPARKLINE_DAYS as f64;
+    Ok(Some(WeightSummary {
+        logged_on,
+        latest_lbs,
+        trend_lbs,
+        change_7d_lbs,
+        recent: series.iter().copied().filter(|(x, _)| *x >= recent_from).collect(),
+        recent_trend: trend.iter().copied().filter(|(x, _)| *x >= recent_from).collect(),
+    }))
+}
+
+fn cycle_status(conn: &Connection, today: NaiveDate) -> DbResult<Option<CycleStatus>> {
+    let Some(active_id) = diet_cycles::active_id(conn)? else { return Ok(None) };
+    let Some(cycle) = diet_cycles::list(conn)?.into_iter().find(|c| c.id == active_id) else { return Ok(None) };
+    let (Some(start), Some(planned_end)) = (parse_date(&cycle.start_date), parse_date(&cycle.planned_end_date)) else {
+        return Ok(None);
+    };
+    Ok(Some(CycleStatus {
+        phase: cycle.phase,
+        day: (today - start).num_days() + 1,
+        planned_days: (planned_end - start).num_days(),
+        days_remaining: (planned_end - today).num_days(),
+        calorie_target: cycle.calorie_target,
+    }))
+}
+
+fn weekly_sessions(conn: &Connection, today: NaiveDate) -> DbResult<Vec<usize>> {
+    let first_day = today - chrono::Duration::days(7 * FREQUENCY_WEEKS as i64 - 1);
+    let mut weeks = vec![0; FREQUENCY_WEEKS];
+    for date in workouts::session_dates_between(conn, first_day, today)? {
+        let weeks_ago = ((today - date).num_days() / 7) as usize;
+        if weeks_ago < FREQUENCY_WEEKS {
+            weeks[FREQUENCY_WEEKS - 1 - weeks_ago] += 1;
+        }
+    }
+    Ok(weeks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+    use crate::db::workouts::{ExerciseSummary, NewExercise, NewSet};
+    use crate::e1rm::E1rmFormula;
+
+    fn day(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    #[test]
+    fn layout_round_trips_and_fills_in_missing_widgets() {
+        let mut layout = DashboardLayout::default();
+        layout.move_down(0);
+        layout.widgets[2].1 = false;
+        let stored = layout.to_string();
+        assert_eq!(stored, "Sparkline,Weight,-DietCycle,LastWorkout,TrainingFrequency,RecentRecords");
+        assert_eq!(stored.parse::<DashboardLayout>().unwrap(), layout);
+
+        let partial: DashboardLayout = "RecentRecords,-Weight".parse().unwrap();
+        assert_eq!(partial.widgets.len(), DashboardWidget::ALL.len());
+        assert_eq!(partial.visible().next(), Some(DashboardWidget::RecentRecords));
+        assert!(!partial.visible().any(|w| w == DashboardWidget::Weight));
+        assert!("Weight,Weight".parse::<DashboardLayout>().is_err());
+        assert!("Horoscope".parse::<DashboardLayout>().is_err());
+    }
+
+    #[test]
+    fn summarizes_weight_cycle_and_training() {
+        let mut conn = test_conn();
+        let today = day(20);
+        let cycle = diet_cycles::create(&mut conn, DietPhase::Cut, day(1), day(31), None, false, today).unwrap();
+        for (d, lbs) in [(1, 200.0), (6, 199.0), (13, 198.0), (20, 196.0)] {
+            weight_logs::insert(&conn, Some(cycle), &day(d).format("%Y-%m-%d").to_string(), lbs).unwrap();
+        }
+        let set = |reps, weight_lbs| NewSet { reps, weight_lbs, rpe: None, notes: None };
+        for d in [2, 4, 16, 18] {
+            let workout = vec![NewExercise { name: "Squat".into(), sets: vec![set(5, 225.0), set(5, 235.0)] }];
+            workouts::insert(&mut conn, day(d), None, &workout, E1rmFormula::Epley).unwrap();
+        }
+
+        let data = load(&conn, SmoothingMethod::Sma, 1, today).unwrap();
+        let weight = data.weight.unwrap();
+        assert_eq!((weight.logged_on, weight.latest_lbs), (day(20), 196.0));
+        // A one-day window makes the trend the raw weights.
+        assert_eq!(weight.change_7d_lbs, Some(-2.0));
+        assert_eq!(weight.recent.len(), 4);
+
+        let cycle = data.cycle.unwrap();
+        assert_eq!((cycle.phase, cycle.day, cycle.planned_days, cycle.days_remaining), (DietPhase::Cut, 20, 30, 11));
+
+        let workout = data.last_workout.unwrap();
+        assert_eq!(workout.date, "2024-03-18");
+        assert_eq!(workout.exercises, vec![ExerciseSummary { name: "Squat".into(), sets: 2, top_weight_lbs: 235.0 }]);
+        assert_eq!(workout.volume_lbs, 2300.0);
+        assert_eq!(data.weekly_sessions.len(), FREQUENCY_WEEKS);
+        assert_eq!(&data.weekly_sessions[FREQUENCY_WEEKS - 3..], &[2, 0, 2]);
+        assert!(!data.recent_records.is_empty());
+
+        let empty = load(&test_conn(), SmoothingMethod::Ema, 7, today).unwrap();
+        assert_eq!(empty, DashboardData { weekly_sessions: vec![0; FREQUENCY_WEEKS], ..DashboardData::default() });
+    }
+}
@@ -0,0 +1,422 @@
+use super::{DbError, DbResult};
+use crate::types::DietPhase;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Row};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DietCycle {
+    pub id: i64,
+    pub phase: DietPhase,
+    pub start_date: String,
+    pub planned_end_date: String,
+    pub actual_end_date: Option<String>,
+    pub notes: Option<String>,
+    pub is_active: bool,
+    // Daily calories aimed for during the cycle.
+    pub calorie_target: Option<i64>,
+}
+
+// A cycle plus its first and last weigh-in, for the cycle manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DietCycleSummary {
+    pub cycle: DietCycle,
+    pub start_weight_lbs: Option<f64>,
+    pub end_weight_lbs: Option<f64>,
+    pub first_log_date: Option<String>,
+    pub last_log_date: Option<String>,
+}
+
+impl DietCycleSummary {
+    // Days from start to the actual end (or today while the cycle is ongoing).
+    pub fn duration_days(&self, today: NaiveDate) -> Option<i64> {
+        let start = parse_date(&self.cycle.start_date)?;
+        let end = self.cycle.actual_end_date.as_deref().and_then(parse_date).unwrap_or(today);
+        Some((end - start).num_days())
+    }
+
+    // Average change per week between the first and last weigh-in of the cycle.
+    pub fn weekly_rate_lbs(&self) -> Option<f64> {
+        let first = parse_date(self.first_log_date.as_deref()?)?;
+        let last = parse_date(self.last_log_date.as_deref()?)?;
+        let days = (last - first).num_days();
+        if days <= 0 {
+            return None;
+        }
+        Some((self.end_weight_lbs? - self.start_weight_lbs?) / days as f64 * 7.0)
+    }
+}
+
+const CYCLE_COLUMNS: &str =
+    "dc.id, dc.phase, dc.start_date, dc.planned_end_date, dc.actual_end_date, dc.notes, dc.is_active, dc.calorie_target";
+
+fn from_row(row: &Row) -> rusqlite::Result<DietCycle> {
+    let phase: String = row.get(1)?;
+    let phase = phase.parse::<DietPhase>().map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+    })?;
+    Ok(DietCycle {
+        id: row.get(0)?,
+        phase,
+        start_date: row.get(2)?,
+        planned_end_date: row.get(3)?,
+        actual_end_date: row.get(4)?,
+        notes: row.get(5)?,
+        is_active: row.get(6)?,
+        calorie_target: row.get(7)?,
+    })
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d").ok()
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+fn required_date(s: &str, field: &str) -> DbResult<NaiveDate> {
+    parse_date(s).ok_or_else(|| DbError::Invalid(format!("invalid {}. use yyyy-mm-dd.", field)))
+}
+
+pub fn active_id(conn: &Connection) -> DbResult<Option<i64>> {
+    Ok(conn.query_row("SELECT id FROM diet_cycles WHERE is_active = 1", [], |row| row.get(0)).optional()?)
+}
+
+// Every cycle, oldest first.
+pub fn list(conn: &Connection) -> DbResult<Vec<DietCycle>> {
+    let mut stmt = conn.prepare(&format!("SELECT {} FROM diet_cycles dc ORDER BY dc.start_date, dc.id", CYCLE_COLUMNS))?;
+    let rows = stmt.query_map([], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// Every cycle, newest first, with its first and last weigh-in.
+pub fn list_summaries(conn: &Connection) -> DbResult<Vec<DietCycleSummary>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {},
+                (SELECT weight_lbs FROM weight_logs WHERE diet_cycle_id = dc.id ORDER BY log_date ASC, id ASC LIMIT 1),
+                (SELECT weight_lbs FROM weight_logs WHERE diet_cycle_id = dc.id ORDER BY log_date DESC, id DESC LIMIT 1),
+                (SELECT MIN(log_date) FROM weight_logs WHERE diet_cycle_id = dc.id),
+                (SELECT MAX(log_date) FROM weight_logs WHERE diet_cycle_id = dc.id)
+         FROM diet_cycles dc
+         ORDER BY dc.start_date DESC, dc.id DESC",
+        CYCLE_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(DietCycleSummary {
+            cycle: from_row(row)?,
+            start_weight_lbs: row.get(8)?,
+            end_weight_lbs: row.get(9)?,
+            first_log_date: row.get(10)?,
+            last_log_date: row.get(11)?,
+        })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// A cycle occupies [start, end) where end is its actual end date, or for an
+// ongoing cycle the later of its planned end and today. Ending one cycle on a
+// date and starting the next on that same date is therefore not an overlap.
+fn occupied_range(start: NaiveDate, planned_end: NaiveDate, actual_end: Option<NaiveDate>, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+    let end = actual_end.unwrap_or_else(|| planned_end.max(today));
+    (start, end.max(start))
+}
+
+// Returns an error naming the first other cycle whose range intersects [start, end).
+pub fn check_overlap(
+    conn: &Connection,
+    start: NaiveDate,
+    end: NaiveDate,
+    exclude_id: Option<i64>,
+    today: NaiveDate,
+) -> DbResult<()> {
+    for other in list(conn)? {
+        if Some(other.id) == exclude_id {
+            continue;
+        }
+        let (Some(other_start), Some(other_planned)) = (parse_date(&other.start_date), parse_date(&other.planned_end_date)) else {
+            continue;
+        };
+        let other_actual = other.actual_end_date.as_deref().and_then(parse_date);
+        let (other_start, other_end) = occupied_range(other_start, other_planned, other_actual, today);
+        if start < other_end && other_start < end {
+            return Err(DbError::Overlap { id: other.id, phase: other.phase.to_string(), start_date: other.start_date });
+        }
+    }
+    Ok(())
+}
+
+// Inserts a new cycle and makes it active. With `end_previous`, any ongoing
+// cycle that started earlier is ended on the new start date first.
+pub fn create(
+    conn: &mut Connection,
+    phase: DietPhase,
+    start: NaiveDate,
+    planned_end: NaiveDate,
+    calorie_target: Option<i64>,
+    end_previous: bool,
+    today: NaiveDate,
+) -> DbResult<i64> {
+    if planned_end <= start {
+        return Err(DbError::Invalid("planned end date must be after the start date.".to_string()));
+    }
+    check_calorie_target(calorie_target)?;
+    let tx = conn.transaction()?;
+    if end_previous {
+        tx.execute(
+            "UPDATE diet_cycles SET actual_end_date = ?1 WHERE actual_end_date IS NULL AND start_date < ?1",
+            [format_date(start)],
+        )?;
+    }
+    let (_, end) = occupied_range(start, planned_end, None, today);
+    check_overlap(&tx, start, end, None, today)?;
+    tx.execute("UPDATE diet_cycles SET is_active = 0 WHERE is_active = 1", [])?;
+    tx.execute(
+        "INSERT INTO diet_cycles (phase, start_date, planned_end_date, calorie_target, is_active) VALUES (?1, ?2, ?3, ?4, 1)",
+        rusqlite::params![phase.to_string(), format_date(start), format_date(planned_end), calorie_target],
+    )?;
+    let id = tx.last_insert_rowid();
+    tx.commit()?;
+    Ok(id)
+}
+
+// Writes a cycle read from elsewhere, e.g. a CSV import. Dates and target are
+// checked like `create`, but no other cycle is ended. It only becomes active
+// if it's flagged active, still open, and no other cycle is.
+pub fn insert_existing(conn: &Connection, cycle: &DietCycle, today: NaiveDate) -> DbResult<i64> {
+    let invalid = |what: &str, value: &str| DbError::Invalid(format!("bad {}: {}", what, value));
+    let start = parse_date(&cycle.start_date).ok_or_else(|| invalid("start date", &cycle.start_date))?;
+    let planned_end =
+        parse_date(&cycle.planned_end_date).ok_or_else(|| invalid("planned end date", &cycle.planned_end_date))?;
+    let actual_end = match cycle.actual_end_date.as_deref() {
+        Some(text) => Some(parse_date(text).ok_or_else(|| invalid("end date", text))?),
+        None => None,
+    };
+    if planned_end <= start {
+        return Err(DbError::Invalid("planned end date must be after the start date.".to_string()));
+    }
+    if actual_end.is_some_and(|end| end < start) {
+        return Err(DbError::Invalid("actual end date can't be before the start date.".to_string()));
+    }
+    check_calorie_target(cycle.calorie_target)?;
+    let (_, end) = occupied_range(start, planned_end, actual_end, today);
+    check_overlap(conn, start, end, None, today)?;
+    let is_active = cycle.is_active && actual_end.is_none() && active_id(conn)?.is_none();
+    conn.execute(
+        "INSERT INTO diet_cycles (phase, start_date, planned_end_date, actual_end_date, notes, calorie_target, is_active)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![
+            cycle.phase.to_string(),
+            format_date(start),
+            format_date(planned_end),
+            actual_end.map(format_date),
+            cycle.notes,
+            cycle.calorie_target,
+            is_active
+        ],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// A cycle with this phase starting on this date.
+pub fn find(conn: &Connection, phase: DietPhase, start_date: &str) -> DbResult<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM diet_cycles WHERE phase = ?1 AND start_date = ?2",
+            rusqlite::params![phase.to_string(), start_date],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+// The cycle whose occupied range includes `date`, for attaching weigh-ins.
+pub fn covering(conn: &Connection, date: NaiveDate, today: NaiveDate) -> DbResult<Option<i64>> {
+    for cycle in list(conn)?.into_iter().rev() {
+        let (Some(start), Some(planned)) = (parse_date(&cycle.start_date), parse_date(&cycle.planned_end_date)) else {
+            continue;
+        };
+        let actual = cycle.actual_end_date.as_deref().and_then(parse_date);
+        let (start, end) = occupied_range(start, planned, actual, today);
+        if start <= date && date < end {
+            return Ok(Some(cycle.id));
+        }
+    }
+    Ok(None)
+}
+
+// Reads a typed calorie target; blank means none.
+pub fn parse_calorie_target(text: &str) -> Result<Option<i64>, String> {
+    let text = text.trim().trim_end_matches("kcal").trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    text.parse::<i64>().map(Some).map_err(|_| "calorie target must be a whole number of kcal.".to_string())
+}
+
+fn check_calorie_target(target: Option<i64>) -> DbResult<()> {
+    match target {
+        Some(kcal) if !(500..=10_000).contains(&kcal) => {
+            Err(DbError::Invalid("calorie target should be between 500 and 10000 kcal.".to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+// Writes phase, dates, notes and calorie target back. `is_active` is left alone unless the
+// cycle now has an end date, which deactivates it.
+pub fn update(conn: &mut Connection, cycle: &DietCycle, today: NaiveDate) -> DbResult<()> {
+    let start = required_date(&cycle.start_date, "start date")?;
+    let planned_end = required_date(&cycle.planned_end_date, "planned end date")?;
+    let actual_end = match cycle.actual_end_date.as_deref() {
+        Some(text) => Some(required_date(text, "actual end date")?),
+        None => None,
+    };
+    if planned_end <= start {
+        return Err(DbError::Invalid("planned end date must be after the start date.".to_string()));
+    }
+    if actual_end.is_some_and(|end| end < start) {
+        return Err(DbError::Invalid("actual end date can't be before the start date.".to_string()));
+    }
+    check_calorie_target(cycle.calorie_target)?;
+
+    let tx = conn.transaction()?;
+    let (_, end) = occupied_range(start, planned_end, actual_end, today);
+    check_overlap(&tx, start, end, Some(cycle.id), today)?;
+    tx.execute(
+        "UPDATE diet_cycles SET phase = ?1, start_date = ?2, planned_end_date = ?3, actual_end_date = ?4, notes = ?5,
+             calorie_target = ?7, is_active = CASE WHEN ?4 IS NULL THEN is_active ELSE 0 END
+         WHERE id = ?6",
+        rusqlite::params![
+            cycle.phase.to_string(),
+            format_date(start),
+            format_date(planned_end),
+            actual_end.map(format_date),
+            cycle.notes,
+            cycle.id,
+            cycle.calorie_target
+        ],
+    )?;
+    tx.commit()?;
+    Ok(())
+}
+
+pub fn end(conn: &Connection, id: i64, end_date: NaiveDate) -> DbResult<()> {
+    let start: String = conn.query_row("SELECT start_date FROM diet_cycles WHERE id = ?1", [id], |row| row.get(0))?;
+    if parse_date(&start).is_some_and(|start| end_date < start) {
+        return Err(DbError::Invalid("end date can't be before the cycle's start date.".to_string()));
+    }
+    conn.execute(
+        "UPDATE diet_cycles SET actual_end_date = ?1, is_active = 0 WHERE id = ?2",
+        rusqlite::params![format_date(end_date), id],
+    )?;
+    Ok(())
+}
+
+// Ended cycles can't be made active; reopen them instead.
+pub fn set_active(conn: &mut Connection, id: i64) -> DbResult<()> {
+    let ended: bool =
+        conn.query_row("SELECT actual_end_date IS NOT NULL FROM diet_cycles WHERE id = ?1", [id], |row| row.get(0))?;
+    if ended {
+        return Err(DbError::Invalid("that cycle has ended; reopen it first.".to_string()));
+    }
+    let tx = conn.transaction()?;
+    tx.execute("UPDATE diet_cycles SET is_active = 0 WHERE is_active = 1", [])?;
+    tx.execute("UPDATE diet_cycles SET is_active = 1 WHERE id = ?1", [id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Clears the end date and makes the cycle active, as long as it wouldn't then
+// run into a later cycle.
+pub fn reopen(conn: &mut Connection, id: i64, today: NaiveDate) -> DbResult<()> {
+    let (start, planned_end): (String, String) = conn.query_row(
+        "SELECT start_date, planned_end_date FROM diet_cycles WHERE id = ?1",
+        [id],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    let (Some(start), Some(planned_end)) = (parse_date(&start), parse_date(&planned_end)) else {
+        return Err(DbError::Invalid("cycle has malformed dates; edit it first.".to_string()));
+    };
+
+    let tx = conn.transaction()?;
+    let (_, end) = occupied_range(start, planned_end, None, today);
+    check_overlap(&tx, start, end, Some(id), today)?;
+    tx.execute("UPDATE diet_cycles SET is_active = 0 WHERE is_active = 1", [])?;
+    tx.execute("UPDATE diet_cycles SET actual_end_date = NULL, is_active = 1 WHERE id = ?1", [id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{test_conn, weight_logs};
+
+    fn date(s: &str) -> NaiveDate {
+        parse_date(s).unwrap()
+    }
+
+    fn get(conn: &Connection, id: i64) -> DietCycle {
+        list(conn).unwrap().into_iter().find(|c| c.id == id).unwrap()
+    }
+
+    #[test]
+    fn create_ends_previous_and_switches_active() {
+        let mut conn = test_conn();
+        let today = date("2025-06-01");
+        let first = create(&mut conn, DietPhase::Cut, date("2025-01-01"), date("2025-04-01"), None, true, today).unwrap();
+
+        // An ongoing cycle occupies up to today, so a new one needs the old one ended.
+        let err = create(&mut conn, DietPhase::Bulk, date("2025-05-01"), date("2025-09-01"), None, false, today).unwrap_err();
+        assert!(matches!(err, DbError::Overlap { id, .. } if id == first));
+
+        let second = create(&mut conn, DietPhase::Bulk, date("2025-05-01"), date("2025-09-01"), None, true, today).unwrap();
+        assert_eq!(active_id(&conn).unwrap(), Some(second));
+        assert_eq!(get(&conn, first).actual_end_date.as_deref(), Some("2025-05-01"));
+    }
+
+    #[test]
+    fn edits_and_reopen_respect_other_cycles() {
+        let mut conn = test_conn();
+        let today = date("2025-06-01");
+        let first = create(&mut conn, DietPhase::Cut, date("2025-01-01"), date("2025-04-01"), None, true, today).unwrap();
+        end(&conn, first, date("2025-03-01")).unwrap();
+        let second = create(&mut conn, DietPhase::Maintain, date("2025-03-01"), date("2025-05-01"), None, true, today).unwrap();
+
+        // Stretching the first cycle past the second's start is refused.
+        let mut edit = get(&conn, first);
+        edit.actual_end_date = Some("2025-03-15".into());
+        assert!(matches!(update(&mut conn, &edit, today), Err(DbError::Overlap { .. })));
+        edit.actual_end_date = Some("2025-02-20".into());
+        edit.notes = Some("stalled".into());
+        edit.calorie_target = Some(100);
+        assert!(matches!(update(&mut conn, &edit, today), Err(DbError::Invalid(_))));
+        edit.calorie_target = Some(2200);
+        update(&mut conn, &edit, today).unwrap();
+        assert_eq!(get(&conn, first).notes.as_deref(), Some("stalled"));
+        assert_eq!(get(&conn, first).calorie_target, Some(2200));
+
+        assert!(reopen(&mut conn, first, today).is_err());
+        end(&conn, second, date("2025-05-01")).unwrap();
+        assert_eq!(active_id(&conn).unwrap(), None);
+        assert!(matches!(set_active(&mut conn, first), Err(DbError::Invalid(_))));
+        assert_eq!(active_id(&conn).unwrap(), None);
+        reopen(&mut conn, second, today).unwrap();
+        set_active(&mut conn, second).unwrap();
+        assert_eq!(active_id(&conn).unwrap(), Some(second));
+    }
+
+    #[test]
+    fn summaries_include_weight_change() {
+        let mut conn = test_conn();
+        let today = date("2025-02-01");
+        let id = create(&mut conn, DietPhase::Cut, date("2025-01-01"), date("2025-03-01"), None, true, today).unwrap();
+        weight_logs::insert(&conn, Some(id), "2025-01-15", 198.0).unwrap();
+        weight_logs::insert(&conn, Some(id), "2025-01-01", 200.0).unwrap();
+
+        let summary = &list_summaries(&conn).unwrap()[0];
+        assert_eq!(summary.start_weight_lbs, Some(200.0));
+        assert_eq!(summary.end_weight_lbs, Some(198.0));
+        assert_eq!(summary.duration_days(today), Some(31));
+        assert!((summary.weekly_rate_lbs().unwrap() + 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,599 @@
+use super::{records, DbError, DbResult};
+use crate::e1rm::E1rmFormula;
+use crate::types::{Equipment, ExerciseCategory, ExerciseMetric, MovementPattern, MuscleGroup};
+use rusqlite::{Connection, OptionalExtension, Row};
+use log::error;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Exercise {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub default_metric: Option<ExerciseMetric>,
+    pub category: Option<ExerciseCategory>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub primary_muscles: Vec<MuscleGroup>,
+    pub secondary_muscles: Vec<MuscleGroup>,
+    // Hidden from pickers; history is kept.
+    pub is_archived: bool,
+    // Other spellings that resolve to this exercise, alphabetical.
+    pub aliases: Vec<String>,
+    // Stable id of the bundled catalog entry it came from or was matched to.
+    pub catalog_id: Option<String>,
+    // Rest between sets; None uses the default from settings.
+    pub rest_secs: Option<u32>,
+}
+
+impl Exercise {
+    pub fn works(&self, muscle: MuscleGroup) -> bool {
+        self.primary_muscles.contains(&muscle) || self.secondary_muscles.contains(&muscle)
+    }
+}
+
+// What the library and the exercise pickers narrow the list down to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ExerciseFilter {
+    // Matched against the name, aliases and description, ignoring case.
+    pub search: String,
+    pub category: Option<ExerciseCategory>,
+    // Either primary or secondary.
+    pub muscle: Option<MuscleGroup>,
+    pub equipment: Option<Equipment>,
+    pub movement_pattern: Option<MovementPattern>,
+    pub include_archived: bool,
+}
+
+impl ExerciseFilter {
+    pub fn matches(&self, exercise: &Exercise) -> bool {
+        let search = self.search.trim().to_lowercase();
+        let text_matches = search.is_empty()
+            || exercise.name.to_lowercase().contains(&search)
+            || exercise.aliases.iter().any(|a| a.to_lowercase().contains(&search))
+            || exercise.description.as_deref().is_some_and(|d| d.to_lowercase().contains(&search));
+        text_matches
+            && (self.include_archived || !exercise.is_archived)
+            && self.category.is_none_or(|c| exercise.category == Some(c))
+            && self.muscle.is_none_or(|m| exercise.works(m))
+            && self.equipment.is_none_or(|e| exercise.equipment == Some(e))
+            && self.movement_pattern.is_none_or(|p| exercise.movement_pattern == Some(p))
+    }
+
+    // Whether anything beyond the defaults is narrowing the list.
+    pub fn is_active(&self) -> bool {
+        *self != ExerciseFilter { include_archived: self.include_archived, ..ExerciseFilter::default() }
+    }
+}
+
+const EXERCISE_COLUMNS: &str =
+    "id, name, description, default_metric_to_track, category, equipment, movement_pattern, is_archived, catalog_id, rest_seconds";
+
+// Unknown stored values are logged and read as unset.
+fn parse_column<T: FromStr<Err = String>>(stored: Option<String>) -> Option<T> {
+    stored.and_then(|s| match s.parse::<T>() {
+        Ok(value) => Some(value),
+        Err(e) => {
+            error!("{}", e);
+            None
+        }
+    })
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<Exercise> {
+    Ok(Exercise {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        description: row.get(2)?,
+        default_metric: parse_column(row.get(3)?),
+        category: parse_column(row.get(4)?),
+        equipment: parse_column(row.get(5)?),
+        movement_pattern: parse_column(row.get(6)?),
+        primary_muscles: Vec::new(),
+        secondary_muscles: Vec::new(),
+        is_archived: row.get(7)?,
+        aliases: Vec::new(),
+        catalog_id: row.get(8)?,
+        rest_secs: row.get(9)?,
+    })
+}
+
+// Fills in each exercise's muscles from `exercise_muscles`.
+fn load_muscles(conn: &Connection, exercises: &mut [Exercise]) -> DbResult<()> {
+    let mut stmt = conn.prepare("SELECT exercise_id, muscle, role FROM exercise_muscles")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))?;
+    let index: HashMap<i64, usize> = exercises.iter().enumerate().map(|(idx, e)| (e.id, idx)).collect();
+    for row in rows {
+        let (exercise_id, muscle, role) = row?;
+        let (Some(idx), Some(muscle)) = (index.get(&exercise_id), parse_column::<MuscleGroup>(Some(muscle))) else {
+            continue;
+        };
+        let exercise = &mut exercises[*idx];
+        match role.as_str() {
+            "Primary" => exercise.primary_muscles.push(muscle),
+            _ => exercise.secondary_muscles.push(muscle),
+        }
+    }
+    for exercise in exercises {
+        exercise.primary_muscles.sort();
+        exercise.secondary_muscles.sort();
+    }
+    Ok(())
+}
+
+// Fills in each exercise's aliases from `exercise_aliases`.
+fn load_aliases(conn: &Connection, exercises: &mut [Exercise]) -> DbResult<()> {
+    let mut stmt = conn.prepare("SELECT exercise_id, alias FROM exercise_aliases ORDER BY alias COLLATE NOCASE")?;
+    let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?;
+    let index: HashMap<i64, usize> = exercises.iter().enumerate().map(|(idx, e)| (e.id, idx)).collect();
+    for row in rows {
+        let (exercise_id, alias) = row?;
+        if let Some(idx) = index.get(&exercise_id) {
+            exercises[*idx].aliases.push(alias);
+        }
+    }
+    Ok(())
+}
+
+// Every exercise, archived ones included. Alphabetical, ignoring case.
+pub fn list(conn: &Connection) -> DbResult<Vec<Exercise>> {
+    let sql = format!("SELECT {} FROM exercises ORDER BY name COLLATE NOCASE", EXERCISE_COLUMNS);
+    let mut stmt = conn.prepare(&sql)?;
+    let mut exercises = stmt.query_map([], from_row)?.collect::<rusqlite::Result<Vec<_>>>()?;
+    load_muscles(conn, &mut exercises)?;
+    load_aliases(conn, &mut exercises)?;
+    Ok(exercises)
+}
+
+pub fn get(conn: &Connection, id: i64) -> DbResult<Option<Exercise>> {
+    let sql = format!("SELECT {} FROM exercises WHERE id = ?1", EXERCISE_COLUMNS);
+    let Some(exercise) = conn.query_row(&sql, [id], from_row).optional()? else { return Ok(None) };
+    let mut exercises = [exercise];
+    load_muscles(conn, &mut exercises)?;
+    load_aliases(conn, &mut exercises)?;
+    let [exercise] = exercises;
+    Ok(Some(exercise))
+}
+
+// Case-insensitive lookup by name or alias.
+pub fn find(conn: &Connection, name: &str) -> DbResult<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM exercises WHERE name = ?1 UNION SELECT exercise_id FROM exercise_aliases WHERE alias = ?1",
+            [name.trim()],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+pub fn create(
+    conn: &Connection,
+    name: &str,
+    description: Option<&str>,
+    default_metric: Option<ExerciseMetric>,
+) -> DbResult<i64> {
+    conn.execute(
+        "INSERT INTO exercises (name, description, default_metric_to_track) VALUES (?1, ?2, ?3)",
+        rusqlite::params![name, description, default_metric.map(|m| m.to_string())],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// Looks the name up case-insensitively, creating the exercise (tracking the
+// default metric) when it doesn't exist yet.
+pub fn find_or_create(conn: &Connection, name: &str) -> DbResult<i64> {
+    match find(conn, name)? {
+        Some(id) => Ok(id),
+        None => create(conn, name, None, Some(ExerciseMetric::default())),
+    }
+}
+
+// Writes the library fields of `exercise`, inserting it when `id` is 0.
+// Names must be non-empty and unique ignoring case, aliases included. A
+// renamed exercise keeps its old name as an alias. Returns the id.
+pub fn save(conn: &mut Connection, exercise: &Exercise) -> DbResult<i64> {
+    let tx = conn.transaction()?;
+    let id = write(&tx, exercise)?;
+    tx.commit()?;
+    Ok(id)
+}
+
+// `save` without its own transaction, for callers that already hold one.
+pub fn write(tx: &Connection, exercise: &Exercise) -> DbResult<i64> {
+    let name = exercise.name.trim();
+    if name.is_empty() {
+        return Err(DbError::Invalid("exercise name can't be empty.".to_string()));
+    }
+    if find(tx, name)?.is_some_and(|id| id != exercise.id) {
+        return Err(DbError::Invalid(format!("an exercise or alias named \"{}\" already exists.", name)));
+    }
+    let old_name = match exercise.id {
+        0 => None,
+        id => tx.query_row("SELECT name FROM exercises WHERE id = ?1", [id], |row| row.get::<_, String>(0)).optional()?,
+    };
+    let description = exercise.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+
+    let id = if exercise.id == 0 {
+        create(tx, name, description, exercise.default_metric)?
+    } else {
+        tx.execute(
+            "UPDATE exercises SET name = ?2, description = ?3, default_metric_to_track = ?4 WHERE id = ?1",
+            rusqlite::params![exercise.id, name, description, exercise.default_metric.map(|m| m.to_string())],
+        )?;
+        // Renaming to one of its own aliases retires that alias.
+        tx.execute("DELETE FROM exercise_aliases WHERE exercise_id = ?1 AND alias = ?2", rusqlite::params![exercise.id, name])?;
+        if let Some(old_name) = old_name.filter(|old| !old.eq_ignore_ascii_case(name)) {
+            tx.execute(
+                "INSERT INTO exercise_aliases (exercise_id, alias) VALUES (?1, ?2)",
+                rusqlite::params![exercise.id, old_name],
+            )?;
+        }
+        exercise.id
+    };
+    tx.execute(
+        "UPDATE exercises SET category = ?2, equipment = ?3, movement_pattern = ?4, is_archived = ?5, rest_seconds = ?6
+         WHERE id = ?1",
+        rusqlite::params![
+            id,
+            exercise.category.map(|c| c.to_string()),
+            exercise.equipment.map(|e| e.to_string()),
+            exercise.movement_pattern.map(|p| p.to_string()),
+            exercise.is_archived,
+            exercise.rest_secs
+        ],
+    )?;
+    tx.execute("DELETE FROM exercise_muscles WHERE exercise_id = ?1", [id])?;
+    let roles = exercise.primary_muscles.iter().map(|m| (m, "Primary"));
+    // A muscle listed as both counts as primary.
+    let secondary = exercise.secondary_muscles.iter().filter(|m| !exercise.primary_muscles.contains(m));
+    for (muscle, role) in roles.chain(secondary.map(|m| (m, "Secondary"))) {
+        tx.execute(
+            "INSERT OR IGNORE INTO exercise_muscles (exercise_id, muscle, role) VALUES (?1, ?2, ?3)",
+            rusqlite::params![id, muscle.to_string(), role],
+        )?;
+    }
+    Ok(id)
+}
+
+pub fn set_archived(conn: &Connection, id: i64, archived: bool) -> DbResult<()> {
+    conn.execute("UPDATE exercises SET is_archived = ?2 WHERE id = ?1", rusqlite::params![id, archived])?;
+    Ok(())
+}
+
+// Adds another spelling for an exercise. It must not already be a name or an
+// alias, ignoring case.
+pub fn add_alias(conn: &Connection, exercise_id: i64, alias: &str) -> DbResult<()> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return Err(DbError::Invalid("alias can't be empty.".to_string()));
+    }
+    if find(conn, alias)?.is_some() {
+        return Err(DbError::Invalid(format!("an exercise or alias named \"{}\" already exists.", alias)));
+    }
+    conn.execute("INSERT INTO exercise_aliases (exercise_id, alias) VALUES (?1, ?2)", rusqlite::params![exercise_id, alias])?;
+    Ok(())
+}
+
+pub fn remove_alias(conn: &Connection, exercise_id: i64, alias: &str) -> DbResult<()> {
+    conn.execute("DELETE FROM exercise_aliases WHERE exercise_id = ?1 AND alias = ?2", rusqlite::params![exercise_id, alias])?;
+    Ok(())
+}
+
+// Sets logged against the exercise, across all sessions.
+pub fn set_count(conn: &Connection, exercise_id: i64) -> DbResult<i64> {
+    Ok(conn.query_row("SELECT COUNT(*) FROM exercise_sets WHERE exercise_id = ?1", [exercise_id], |row| row.get(0))?)
+}
+
+// Folds `from_id` into `into_id` in one transaction: its sets, template slots
+// and aliases move over, its name becomes an alias, and attributes the target
+// leaves unset are taken from it. Sets that land in a session where the
+// target was already logged are numbered after the target's. Records are
+// rebuilt afterwards. Returns the number of sets moved.
+pub fn merge(conn: &mut Connection, from_id: i64, into_id: i64, formula: E1rmFormula) -> DbResult<usize> {
+    if from_id == into_id {
+        return Err(DbError::Invalid("can't merge an exercise into itself.".to_string()));
+    }
+    let (Some(from), Some(_)) = (get(conn, from_id)?, get(conn, into_id)?) else {
+        return Err(DbError::Invalid("exercise not found.".to_string()));
+    };
+
+    let tx = conn.transaction()?;
+    tx.execute(
+        "UPDATE exercise_sets SET set_order = set_order + (
+             SELECT COALESCE(MAX(target.set_order), 0) FROM exercise_sets target
+             WHERE target.workout_session_id = exercise_sets.workout_session_id AND target.exercise_id = ?2
+         ) WHERE exercise_id = ?1",
+        [from_id, into_id],
+    )?;
+    let moved = tx.execute("UPDATE exercise_sets SET exercise_id = ?2 WHERE exercise_id = ?1", [from_id, into_id])?;
+    tx.execute("UPDATE template_exercises SET exercise_id = ?2 WHERE exercise_id = ?1", [from_id, into_id])?;
+    tx.execute(
+        "UPDATE exercises SET
+             description = COALESCE(description, ?2),
+             default_metric_to_track = COALESCE(default_metric_to_track, ?3),
+             category = COALESCE(category, ?4),
+             equipment = COALESCE(equipment, ?5),
+             movement_pattern = COALESCE(movement_pattern, ?6),
+             rest_seconds = COALESCE(rest_seconds, ?7)
+         WHERE id = ?1",
+        rusqlite::params![
+            into_id,
+            from.description,
+            from.default_metric.map(|m| m.to_string()),
+            from.category.map(|c| c.to_string()),
+            from.equipment.map(|e| e.to_string()),
+            from.movement_pattern.map(|p| p.to_string()),
+            from.rest_secs
+        ],
+    )?;
+    // The target keeps its own muscles unless it has none.
+    tx.execute(
+        "UPDATE exercise_muscles SET exercise_id = ?2
+         WHERE exercise_id = ?1 AND NOT EXISTS (SELECT 1 FROM exercise_muscles WHERE exercise_id = ?2)",
+        [from_id, into_id],
+    )?;
+    tx.execute("DELETE FROM exercise_muscles WHERE exercise_id = ?1", [from_id])?;
+    tx.execute("UPDATE exercise_aliases SET exercise_id = ?2 WHERE exercise_id = ?1", [from_id, into_id])?;
+    tx.execute("DELETE FROM personal_records WHERE exercise_id = ?1", [from_id])?;
+    tx.execute("DELETE FROM exercises WHERE id = ?1", [from_id])?;
+    // Keeps catalog updates flowing to the merged exercise.
+    tx.execute(
+        "UPDATE exercises SET catalog_id = COALESCE(catalog_id, ?2) WHERE id = ?1",
+        rusqlite::params![into_id, from.catalog_id],
+    )?;
+    tx.execute("INSERT INTO exercise_aliases (exercise_id, alias) VALUES (?1, ?2)", rusqlite::params![into_id, from.name])?;
+    tx.commit()?;
+
+    records::rebuild_all(conn, formula)?;
+    Ok(moved)
+}
+
+// Dice coefficient over character bigrams of the names' sorted words, so
+// "Flat Barbell Bench Press" is close to "Bench Press (Barbell)".
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    fn bigrams(name: &str) -> Vec<(char, char)> {
+        let mut words = name_words(name);
+        words.sort();
+        let chars: Vec<char> = words.join(" ").chars().collect();
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    }
+    let (a, mut b) = (bigrams(a), bigrams(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let total = a.len() + b.len();
+    let mut shared = 0;
+    for pair in a {
+        if let Some(pos) = b.iter().position(|other| *other == pair) {
+            b.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+fn name_words(name: &str) -> Vec<String> {
+    name.to_lowercase().split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).map(str::to_string).collect()
+}
+
+// Pairs scoring at least this are reported as likely duplicates.
+const DUPLICATE_THRESHOLD: f64 = 0.6;
+// Score given when every word of one name appears in the other, so "Bench"
+// is flagged against "Bench Press" even though they differ in length. Words
+// of two letters or fewer ("BB", "DB") are left out of the comparison.
+const CONTAINED_SCORE: f64 = 0.75;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DuplicateSuggestion {
+    // The one with more logged sets; the suggested merge target.
+    pub keep: Exercise,
+    pub keep_sets: i64,
+    pub merge: Exercise,
+    pub merge_sets: i64,
+    pub score: f64,
+}
+
+// Pairs of exercises whose names (or aliases) look alike, best matches first.
+pub fn suggest_duplicates(conn: &Connection) -> DbResult<Vec<DuplicateSuggestion>> {
+    let library = list(conn)?;
+    let counts: HashMap<i64, i64> = {
+        let mut stmt = conn.prepare("SELECT exercise_id, COUNT(*) FROM exercise_sets GROUP BY exercise_id")?;
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?.collect::<rusqlite::Result<_>>()?
+    };
+    let spellings = |e: &Exercise| -> Vec<String> { std::iter::once(e.name.clone()).chain(e.aliases.iter().cloned()).collect() };
+    let score = |a: &str, b: &str| -> f64 {
+        let significant = |name: &str| -> Vec<String> { name_words(name).into_iter().filter(|w| w.len() > 2).collect() };
+        let (a_words, b_words) = (significant(a), significant(b));
+        let contained = !a_words.is_empty()
+            && !b_words.is_empty()
+            && (a_words.iter().all(|w| b_words.contains(w)) || b_words.iter().all(|w| a_words.contains(w)));
+        let similarity = name_similarity(a, b);
+        if contained { similarity.max(CONTAINED_SCORE) } else { similarity }
+    };
+
+    let mut suggestions = Vec::new();
+    for (idx, a) in library.iter().enumerate() {
+        for b in &library[idx + 1..] {
+            let best = spellings(a)
+                .iter()
+                .flat_map(|x| spellings(b).into_iter().map(move |y| score(x, &y)))
+                .fold(0.0, f64::max);
+            if best < DUPLICATE_THRESHOLD {
+                continue;
+            }
+            let (a_sets, b_sets) = (counts.get(&a.id).copied().unwrap_or(0), counts.get(&b.id).copied().unwrap_or(0));
+            let (keep, keep_sets, merge, merge_sets) =
+                if b_sets > a_sets { (b, b_sets, a, a_sets) } else { (a, a_sets, b, b_sets) };
+            suggestions.push(DuplicateSuggestion {
+                keep: keep.clone(),
+                keep_sets,
+                merge: merge.clone(),
+                merge_sets,
+                score: best,
+            });
+        }
+    }
+    suggestions.sort_by(|a, b| b.score.total_cmp(&a.score));
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    #[test]
+    fn find_or_create_matches_case_insensitively() {
+        let conn = test_conn();
+        conn.execute("INSERT INTO exercises (name, default_metric_to_track) VALUES ('Squat', 'Weight')", []).unwrap();
+        let squat = find_or_create(&conn, "squat").unwrap();
+        let row = find_or_create(&conn, "Row").unwrap();
+        assert_ne!(squat, row);
+
+        let names: Vec<_> = list(&conn).unwrap().into_iter().map(|e| e.name).collect();
+        assert_eq!(names, vec!["Row", "Squat"]);
+        // The legacy "Weight" metric name still maps to top set weight.
+        assert_eq!(get(&conn, squat).unwrap().unwrap().default_metric, Some(ExerciseMetric::TopSetWeight));
+    }
+
+    #[test]
+    fn saves_library_attributes_and_filters_by_them() {
+        let mut conn = test_conn();
+        let squat = find_or_create(&conn, "Squat").unwrap();
+        let bench = Exercise {
+            name: " Bench Press ".into(),
+            description: Some("flat, competition grip".into()),
+            category: Some(ExerciseCategory::Compound),
+            equipment: Some(Equipment::Barbell),
+            movement_pattern: Some(MovementPattern::HorizontalPush),
+            primary_muscles: vec![MuscleGroup::Chest],
+            secondary_muscles: vec![MuscleGroup::Triceps, MuscleGroup::Shoulders, MuscleGroup::Chest],
+            rest_secs: Some(180),
+            ..Exercise::default()
+        };
+        let id = save(&mut conn, &bench).unwrap();
+        let saved = get(&conn, id).unwrap().unwrap();
+        assert_eq!(saved.name, "Bench Press");
+        assert_eq!(saved.rest_secs, Some(180));
+        assert_eq!(saved.secondary_muscles, vec![MuscleGroup::Shoulders, MuscleGroup::Triceps]);
+
+        assert!(save(&mut conn, &Exercise { name: "squat".into(), ..Exercise::default() }).is_err());
+        assert!(save(&mut conn, &Exercise { id: squat, name: " ".into(), ..Exercise::default() }).is_err());
+        save(&mut conn, &Exercise { primary_muscles: vec![MuscleGroup::Triceps], ..saved.clone() }).unwrap();
+        assert_eq!(get(&conn, id).unwrap().unwrap().primary_muscles, vec![MuscleGroup::Triceps]);
+
+        let library = list(&conn).unwrap();
+        let names = |filter: &ExerciseFilter| -> Vec<String> {
+            library.iter().filter(|e| filter.matches(e)).map(|e| e.name.clone()).collect()
+        };
+        assert_eq!(names(&ExerciseFilter::default()).len(), 2);
+        assert_eq!(names(&ExerciseFilter { search: "GRIP".into(), ..ExerciseFilter::default() }), vec!["Bench Press"]);
+        assert_eq!(names(&ExerciseFilter { muscle: Some(MuscleGroup::Shoulders), ..ExerciseFilter::default() }), vec!["Bench Press"]);
+        assert!(names(&ExerciseFilter { equipment: Some(Equipment::Dumbbell), ..ExerciseFilter::default() }).is_empty());
+
+        set_archived(&conn, squat, true).unwrap();
+        let library = list(&conn).unwrap();
+        let visible: Vec<_> = library.iter().filter(|e| ExerciseFilter::default().matches(e)).collect();
+        assert_eq!(visible.len(), 1);
+        let all = ExerciseFilter { include_archived: true, ..ExerciseFilter::default() };
+        assert!(!all.is_active());
+        assert_eq!(library.iter().filter(|e| all.matches(e)).count(), 2);
+    }
+
+    #[test]
+    fn aliases_resolve_and_renames_keep_the_old_name() {
+        let mut conn = test_conn();
+        let bench = find_or_create(&conn, "Bench Press").unwrap();
+        let squat = find_or_create(&conn, "Squat").unwrap();
+        add_alias(&conn, bench, " BB Bench ").unwrap();
+        assert_eq!(find(&conn, "bb bench").unwrap(), Some(bench));
+        assert_eq!(find_or_create(&conn, "BB BENCH").unwrap(), bench);
+        assert!(add_alias(&conn, squat, "bench press").is_err());
+        assert!(add_alias(&conn, squat, "bb bench").is_err());
+        assert!(save(&mut conn, &Exercise { id: squat, name: "BB Bench".into(), ..Exercise::default() }).is_err());
+
+        let mut exercise = get(&conn, bench).unwrap().unwrap();
+        assert_eq!(exercise.aliases, vec!["BB Bench"]);
+        exercise.name = "Barbell Bench Press".into();
+        save(&mut conn, &exercise).unwrap();
+        assert_eq!(get(&conn, bench).unwrap().unwrap().aliases, vec!["BB Bench", "Bench Press"]);
+        assert_eq!(find(&conn, "Bench Press").unwrap(), Some(bench));
+
+        // Renaming to an alias swaps the two.
+        exercise.name = "Bench Press".into();
+        save(&mut conn, &exercise).unwrap();
+        assert_eq!(get(&conn, bench).unwrap().unwrap().aliases, vec!["Barbell Bench Press", "BB Bench"]);
+        let search = ExerciseFilter { search: "bb".into(), ..ExerciseFilter::default() };
+        assert!(search.matches(&get(&conn, bench).unwrap().unwrap()));
+
+        remove_alias(&conn, bench, "bb bench").unwrap();
+        assert_eq!(find(&conn, "BB Bench").unwrap(), None);
+    }
+
+    #[test]
+    fn merge_moves_history_and_rebuilds_records() {
+        use crate::db::workouts::{self, NewExercise, NewSet};
+        use chrono::NaiveDate;
+        let mut conn = test_conn();
+        let set = |reps, weight_lbs| NewSet { reps, weight_lbs, rpe: None, notes: None };
+        let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let both = vec![
+            NewExercise { name: "Bench Press".into(), sets: vec![set(5, 185.0), set(5, 185.0)] },
+            NewExercise { name: "Bench".into(), sets: vec![set(3, 205.0)] },
+        ];
+        let (session, _) = workouts::insert(&mut conn, day(1), None, &both, E1rmFormula::Epley).unwrap();
+        let only_bench = vec![NewExercise { name: "Bench".into(), sets: vec![set(8, 155.0)] }];
+        workouts::insert(&mut conn, day(4), None, &only_bench, E1rmFormula::Epley).unwrap();
+        let (from, into) = (find(&conn, "Bench").unwrap().unwrap(), find(&conn, "Bench Press").unwrap().unwrap());
+        let source = get(&conn, from).unwrap().unwrap();
+        save(&mut conn, &Exercise { category: Some(ExerciseCategory::Compound), ..source }).unwrap();
+
+        assert!(merge(&mut conn, into, into, E1rmFormula::Epley).is_err());
+        assert_eq!(merge(&mut conn, from, into, E1rmFormula::Epley).unwrap(), 2);
+
+        assert_eq!(get(&conn, from).unwrap(), None);
+        let merged = get(&conn, into).unwrap().unwrap();
+        assert_eq!(merged.aliases, vec!["Bench"]);
+        assert_eq!(merged.category, Some(ExerciseCategory::Compound));
+        assert_eq!(find(&conn, "bench").unwrap(), Some(into));
+        let orders: Vec<i64> = conn
+            .prepare("SELECT set_order FROM exercise_sets WHERE workout_session_id = ?1 AND exercise_id = ?2 ORDER BY set_order")
+            .unwrap()
+            .query_map([session.id, into], |row| row.get(0))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap();
+        assert_eq!(orders, vec![1, 2, 3]);
+        assert_eq!(set_count(&conn, into).unwrap(), 4);
+        let records = records::load_current(&conn).unwrap();
+        assert!(!records.is_empty());
+        assert!(records.iter().all(|r| r.exercise_id == into));
+    }
+
+    #[test]
+    fn suggests_duplicates_keeping_the_one_with_more_history() {
+        let conn = test_conn();
+        let bench = find_or_create(&conn, "Bench").unwrap();
+        let bench_press = find_or_create(&conn, "Bench Press").unwrap();
+        let bb_bench = find_or_create(&conn, "BB Bench").unwrap();
+        find_or_create(&conn, "Lat Pulldown").unwrap();
+        find_or_create(&conn, "Squat").unwrap();
+        conn.execute("INSERT INTO workout_sessions (session_date) VALUES ('2024-03-01')", []).unwrap();
+        for _ in 0..3 {
+            conn.execute(
+                "INSERT INTO exercise_sets (workout_session_id, exercise_id, set_order, reps, weight_lbs) VALUES (1, ?1, 1, 5, 135)",
+                [bench_press],
+            )
+            .unwrap();
+        }
+
+        let suggestions = suggest_duplicates(&conn).unwrap();
+        let pairs: Vec<(i64, i64)> = suggestions.iter().map(|s| (s.merge.id, s.keep.id)).collect();
+        assert!(pairs.contains(&(bench, bench_press)));
+        assert!(pairs.contains(&(bb_bench, bench_press)));
+        assert!(suggestions.iter().all(|s| s.keep.name != "Squat" && s.merge.name != "Squat"));
+        let bench_pair = suggestions.iter().find(|s| s.merge.id == bench && s.keep.id == bench_press).unwrap();
+        assert_eq!((bench_pair.keep_sets, bench_pair.merge_sets), (3, 0));
+    }
+}
@@ -0,0 +1,103 @@
+use super::DbResult;
+use crate::types::MeasurementKind;
+use rusqlite::{Connection, Row};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub id: i64,
+    pub measured_on: String,
+    pub kind: MeasurementKind,
+    // Inches, or percent for body fat.
+    pub value: f64,
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<Measurement> {
+    let kind: String = row.get(2)?;
+    Ok(Measurement {
+        id: row.get(0)?,
+        measured_on: row.get(1)?,
+        kind: kind.parse().map_err(|e: String| {
+            rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, e.into())
+        })?,
+        value: row.get(3)?,
+    })
+}
+
+// Writes every (kind, value) pair for one date in a single transaction.
+pub fn insert_all(conn: &mut Connection, measured_on: &str, values: &[(MeasurementKind, f64)]) -> DbResult<()> {
+    let tx = conn.transaction()?;
+    for (kind, value) in values {
+        tx.execute(
+            "INSERT INTO body_measurements (measured_on, kind, value) VALUES (?1, ?2, ?3)",
+            rusqlite::params![measured_on, kind.to_string(), value],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+// Oldest first.
+pub fn list(conn: &Connection, kind: MeasurementKind) -> DbResult<Vec<Measurement>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, measured_on, kind, value FROM body_measurements
+         WHERE kind = ?1
+         ORDER BY measured_on ASC, id ASC",
+    )?;
+    let rows = stmt.query_map([kind.to_string()], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// Newest first, at most `limit` rows across every kind.
+pub fn recent(conn: &Connection, limit: i64) -> DbResult<Vec<Measurement>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, measured_on, kind, value FROM body_measurements
+         ORDER BY measured_on DESC, id DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map([limit], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// The most recent value of each kind that has ever been measured.
+pub fn latest_each(conn: &Connection) -> DbResult<Vec<Measurement>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, measured_on, kind, value FROM body_measurements bm
+         WHERE id = (
+             SELECT id FROM body_measurements latest
+             WHERE latest.kind = bm.kind
+             ORDER BY measured_on DESC, id DESC LIMIT 1
+         )",
+    )?;
+    let rows = stmt.query_map([], from_row)?;
+    let mut latest: Vec<Measurement> = rows.collect::<rusqlite::Result<_>>()?;
+    latest.sort_by_key(|m| m.kind);
+    Ok(latest)
+}
+
+pub fn delete(conn: &Connection, id: i64) -> DbResult<()> {
+    conn.execute("DELETE FROM body_measurements WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    #[test]
+    fn latest_each_picks_newest_date_per_kind() {
+        let mut conn = test_conn();
+        insert_all(&mut conn, "2025-03-01", &[(MeasurementKind::Waist, 34.0), (MeasurementKind::Neck, 15.0)]).unwrap();
+        insert_all(&mut conn, "2025-03-08", &[(MeasurementKind::Waist, 33.5)]).unwrap();
+        // Entered late for an earlier date; doesn't become the latest.
+        insert_all(&mut conn, "2025-02-20", &[(MeasurementKind::Waist, 35.0)]).unwrap();
+
+        let latest = latest_each(&conn).unwrap();
+        let pairs: Vec<_> = latest.iter().map(|m| (m.kind, m.value)).collect();
+        assert_eq!(pairs, vec![(MeasurementKind::Waist, 33.5), (MeasurementKind::Neck, 15.0)]);
+
+        let waist = list(&conn, MeasurementKind::Waist).unwrap();
+        assert_eq!(waist.iter().map(|m| m.value).collect::<Vec<_>>(), vec![35.0, 34.0, 33.5]);
+        delete(&conn, waist[0].id).unwrap();
+        assert_eq!(recent(&conn, 10).unwrap().len(), 3);
+    }
+}
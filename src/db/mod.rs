@@ -0,0 +1,92 @@
+// Data-access layer. Everything that reads or writes SQLite for the UI lives in
+// the submodules here; render functions only see the typed structs and
+// `DbError`.
+pub mod diet_cycles;
+pub mod exercises;
+pub mod measurements;
+pub mod nutrition;
+pub mod records;
+pub mod settings;
+pub mod templates;
+pub mod weight_logs;
+pub mod workouts;
+
+pub use diet_cycles::{DietCycle, DietCycleSummary};
+pub use weight_logs::WeightLog;
+pub use workouts::ExerciseSet;
+
+use rusqlite::Connection;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug)]
+pub enum DbError {
+    Sqlite(rusqlite::Error),
+    // Another thread panicked while holding the connection.
+    LockPoisoned,
+    // Input that can't be written as given; the message is user-facing.
+    Invalid(String),
+    // A diet cycle's dates would intersect another cycle.
+    Overlap { id: i64, phase: String, start_date: String },
+}
+
+pub type DbResult<T> = Result<T, DbError>;
+
+impl fmt::Display for DbError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbError::Sqlite(e) => write!(f, "{}", e),
+            DbError::LockPoisoned => write!(f, "failed to acquire database lock"),
+            DbError::Invalid(msg) => write!(f, "{}", msg),
+            DbError::Overlap { id, phase, start_date } => write!(
+                f,
+                "dates overlap the {} cycle starting {} (id {}). end or edit that cycle first.",
+                phase.to_lowercase(),
+                start_date,
+                id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<rusqlite::Error> for DbError {
+    fn from(e: rusqlite::Error) -> Self {
+        DbError::Sqlite(e)
+    }
+}
+
+// Shared handle to the single SQLite connection. Cloning is cheap.
+#[derive(Clone)]
+pub struct Database {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl Database {
+    pub fn new(conn: Connection) -> Self {
+        Database { conn: Arc::new(Mutex::new(conn)) }
+    }
+
+    // Runs `f` with the connection locked for its duration.
+    pub fn with_conn<T>(&self, f: impl FnOnce(&mut Connection) -> DbResult<T>) -> DbResult<T> {
+        let mut conn = self.conn.lock().map_err(|_| DbError::LockPoisoned)?;
+        f(&mut conn)
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_conn() -> Connection {
+    let mut conn = Connection::open_in_memory().unwrap();
+    crate::migrations::run(&mut conn).unwrap();
+    conn
+}
+
+// An empty directory under the system temp dir, unique to this test run.
+#[cfg(test)]
+pub(crate) fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("liftmetrics-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -0,0 +1,76 @@
+use super::DbResult;
+use rusqlite::{Connection, OptionalExtension, Row};
+
+// One day's intake. Macros are grams and optional.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NutritionLog {
+    pub id: i64,
+    pub log_date: String,
+    pub calories: i64,
+    pub protein_g: Option<f64>,
+    pub carbs_g: Option<f64>,
+    pub fat_g: Option<f64>,
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<NutritionLog> {
+    Ok(NutritionLog {
+        id: row.get(0)?,
+        log_date: row.get(1)?,
+        calories: row.get(2)?,
+        protein_g: row.get(3)?,
+        carbs_g: row.get(4)?,
+        fat_g: row.get(5)?,
+    })
+}
+
+// Inserts the day's intake, replacing whatever was logged for that date.
+pub fn upsert(conn: &Connection, entry: &NutritionLog) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO nutrition_logs (log_date, calories, protein_g, carbs_g, fat_g) VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(log_date) DO UPDATE SET
+             calories = excluded.calories, protein_g = excluded.protein_g,
+             carbs_g = excluded.carbs_g, fat_g = excluded.fat_g",
+        rusqlite::params![entry.log_date, entry.calories, entry.protein_g, entry.carbs_g, entry.fat_g],
+    )?;
+    Ok(())
+}
+
+pub fn find_on_date(conn: &Connection, log_date: &str) -> DbResult<Option<NutritionLog>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, log_date, calories, protein_g, carbs_g, fat_g FROM nutrition_logs WHERE log_date = ?1",
+            [log_date],
+            from_row,
+        )
+        .optional()?)
+}
+
+// Oldest first.
+pub fn list(conn: &Connection) -> DbResult<Vec<NutritionLog>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, log_date, calories, protein_g, carbs_g, fat_g FROM nutrition_logs ORDER BY log_date ASC",
+    )?;
+    let rows = stmt.query_map([], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    #[test]
+    fn upsert_replaces_the_days_entry() {
+        let conn = test_conn();
+        let entry = NutritionLog { log_date: "2025-03-02".into(), calories: 2400, protein_g: Some(180.0), ..Default::default() };
+        upsert(&conn, &entry).unwrap();
+        upsert(&conn, &NutritionLog { log_date: "2025-03-01".into(), calories: 2000, ..Default::default() }).unwrap();
+        upsert(&conn, &NutritionLog { calories: 2600, protein_g: None, fat_g: Some(70.0), ..entry }).unwrap();
+
+        let logs = list(&conn).unwrap();
+        assert_eq!(logs.iter().map(|l| l.calories).collect::<Vec<_>>(), vec![2000, 2600]);
+        let day = find_on_date(&conn, "2025-03-02").unwrap().unwrap();
+        assert_eq!((day.protein_g, day.fat_g), (None, Some(70.0)));
+        assert!(find_on_date(&conn, "2025-03-03").unwrap().is_none());
+    }
+}
@@ -0,0 +1,491 @@
+use super::DbResult;
+use crate::e1rm::E1rmFormula;
+use crate::units::WeightUnit;
+use rusqlite::{Connection, OptionalExtension};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Rep maxes are tracked for each rep count from 1 up to this.
+pub const MAX_TRACKED_REP_MAX: i64 = 12;
+
+// Small tolerance so re-logging the exact same weight isn't announced as a PR.
+const IMPROVEMENT_EPSILON: f64 = 1e-6;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum RecordType {
+    // Heaviest weight for exactly `rep_count` reps.
+    RepMax,
+    EstimatedOneRepMax,
+    // Sum of weight x reps for one exercise in one session.
+    SessionVolume,
+    // Most reps in one set logged at 0 lbs.
+    BodyweightReps,
+}
+
+impl Display for RecordType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for RecordType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "RepMax" => Ok(RecordType::RepMax),
+            "EstimatedOneRepMax" => Ok(RecordType::EstimatedOneRepMax),
+            "SessionVolume" => Ok(RecordType::SessionVolume),
+            "BodyweightReps" => Ok(RecordType::BodyweightReps),
+            other => Err(format!("unknown record type: {}", other)),
+        }
+    }
+}
+
+// A row of `personal_records`, joined with the exercise name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PersonalRecord {
+    pub id: i64,
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub record_type: RecordType,
+    pub rep_count: Option<i64>,
+    pub value: f64,
+    pub previous_value: Option<f64>,
+    pub exercise_set_id: Option<i64>,
+    pub achieved_on: String,
+}
+
+impl PersonalRecord {
+    // e.g. "5RM", "e1RM", "Session volume".
+    pub fn label(&self) -> String {
+        record_label(self.record_type, self.rep_count)
+    }
+
+    // The value with its unit, e.g. "225.0 lbs" or "18 reps".
+    pub fn display_value(&self, unit: WeightUnit) -> String {
+        format_value(self.record_type, self.value, unit)
+    }
+}
+
+pub fn record_label(record_type: RecordType, rep_count: Option<i64>) -> String {
+    match record_type {
+        RecordType::RepMax => format!("{}RM", rep_count.unwrap_or(1)),
+        RecordType::EstimatedOneRepMax => "e1RM".to_string(),
+        RecordType::SessionVolume => "Session volume".to_string(),
+        RecordType::BodyweightReps => "Bodyweight reps".to_string(),
+    }
+}
+
+pub fn format_value(record_type: RecordType, value: f64, unit: WeightUnit) -> String {
+    match record_type {
+        RecordType::BodyweightReps => format!("{} reps", value as i64),
+        _ => unit.format(value),
+    }
+}
+
+// A set from the session being evaluated.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidateSet {
+    pub set_id: i64,
+    pub exercise_id: i64,
+    pub reps: i64,
+    pub weight_lbs: f64,
+    pub rpe: Option<f64>,
+}
+
+// The best value of one record kind within a session.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Candidate {
+    pub exercise_id: i64,
+    pub record_type: RecordType,
+    pub rep_count: Option<i64>,
+    pub value: f64,
+    pub exercise_set_id: Option<i64>,
+}
+
+// A record that a just-evaluated session beat (or set for the first time).
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewRecord {
+    pub exercise_name: String,
+    pub record_type: RecordType,
+    pub rep_count: Option<i64>,
+    pub value: f64,
+    pub previous_value: Option<f64>,
+}
+
+impl NewRecord {
+    pub fn announcement(&self, unit: WeightUnit) -> String {
+        let label = record_label(self.record_type, self.rep_count);
+        let value = format_value(self.record_type, self.value, unit);
+        match self.previous_value {
+            Some(prev) => format!(
+                "new {} on {}: {} (was {})",
+                label,
+                self.exercise_name,
+                value,
+                format_value(self.record_type, prev, unit)
+            ),
+            None => format!("first {} on {}: {}", label, self.exercise_name, value),
+        }
+    }
+}
+
+// Reduces one session's sets to the best candidate per record kind per exercise.
+// Earlier sets win ties so the link points at the first set that hit the value.
+pub fn session_candidates(sets: &[CandidateSet], formula: E1rmFormula) -> Vec<Candidate> {
+    let mut by_exercise: BTreeMap<i64, Vec<&CandidateSet>> = BTreeMap::new();
+    for set in sets {
+        by_exercise.entry(set.exercise_id).or_default().push(set);
+    }
+
+    let mut candidates = Vec::new();
+    for (exercise_id, sets) in by_exercise {
+        let mut best = |record_type, rep_count, pairs: Vec<(f64, i64)>| {
+            let top = pairs.into_iter().fold(None, |acc: Option<(f64, i64)>, (value, id)| match acc {
+                Some((best, _)) if best >= value => acc,
+                _ => Some((value, id)),
+            });
+            if let Some((value, set_id)) = top {
+                candidates.push(Candidate { exercise_id, record_type, rep_count, value, exercise_set_id: Some(set_id) });
+            }
+        };
+
+        for reps in 1..=MAX_TRACKED_REP_MAX {
+            let pairs = sets
+                .iter()
+                .filter(|s| s.reps == reps && s.weight_lbs > 0.0)
+                .map(|s| (s.weight_lbs, s.set_id))
+                .collect();
+            best(RecordType::RepMax, Some(reps), pairs);
+        }
+
+        let pairs = sets
+            .iter()
+            .filter(|s| s.weight_lbs > 0.0)
+            .filter_map(|s| formula.estimate(s.weight_lbs, s.reps, s.rpe).map(|e| (e, s.set_id)))
+            .collect();
+        best(RecordType::EstimatedOneRepMax, None, pairs);
+
+        let pairs = sets
+            .iter()
+            .filter(|s| s.weight_lbs == 0.0)
+            .map(|s| (s.reps as f64, s.set_id))
+            .collect();
+        best(RecordType::BodyweightReps, None, pairs);
+
+        let volume: f64 = sets.iter().map(|s| s.weight_lbs * s.reps as f64).sum();
+        if volume > 0.0 {
+            candidates.push(Candidate {
+                exercise_id,
+                record_type: RecordType::SessionVolume,
+                rep_count: None,
+                value: volume,
+                exercise_set_id: None,
+            });
+        }
+    }
+    candidates
+}
+
+// Compares a session's sets against stored records and inserts a row for every
+// record it beats. Runs on whatever connection/transaction it's handed so it can
+// share the workout insert's transaction. A backdated session can change which
+// later records still count, so it replays the whole history instead.
+pub fn detect_for_session(conn: &Connection, session_id: i64, formula: E1rmFormula) -> DbResult<Vec<NewRecord>> {
+    let session_date: String =
+        conn.query_row("SELECT session_date FROM workout_sessions WHERE id = ?1", [session_id], |row| row.get(0))?;
+    let backdated: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM personal_records
+             WHERE achieved_on > ?2
+               AND exercise_id IN (SELECT exercise_id FROM exercise_sets WHERE workout_session_id = ?1))",
+        rusqlite::params![session_id, session_date],
+        |row| row.get(0),
+    )?;
+    if !backdated {
+        return detect_in_order(conn, session_id, &session_date, formula);
+    }
+
+    replay_all(conn, formula)?;
+    let mut stmt = conn.prepare(
+        "SELECT e.name, pr.record_type, pr.rep_count, pr.value, pr.previous_value
+         FROM personal_records pr JOIN exercises e ON e.id = pr.exercise_id
+         WHERE pr.workout_session_id = ?1 ORDER BY pr.id",
+    )?;
+    let records = stmt.query_map([session_id], |row| {
+        let record_type: String = row.get(1)?;
+        Ok(NewRecord {
+            exercise_name: row.get(0)?,
+            record_type: record_type.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(1, rusqlite::types::Type::Text, e.into())
+            })?,
+            rep_count: row.get(2)?,
+            value: row.get(3)?,
+            previous_value: row.get(4)?,
+        })
+    })?;
+    Ok(records.collect::<rusqlite::Result<_>>()?)
+}
+
+// Detection for a session no earlier than any record it could beat; only
+// records standing as of its date count.
+fn detect_in_order(
+    conn: &Connection,
+    session_id: i64,
+    session_date: &str,
+    formula: E1rmFormula,
+) -> DbResult<Vec<NewRecord>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, exercise_id, reps, weight_lbs, rpe FROM exercise_sets
+         WHERE workout_session_id = ?1 ORDER BY exercise_id, set_order, id",
+    )?;
+    let sets = stmt
+        .query_map([session_id], |row| {
+            Ok(CandidateSet {
+                set_id: row.get(0)?,
+                exercise_id: row.get(1)?,
+                reps: row.get(2)?,
+                weight_lbs: row.get(3)?,
+                rpe: row.get(4)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let mut new_records = Vec::new();
+    for candidate in session_candidates(&sets, formula) {
+        let previous: Option<f64> = conn.query_row(
+            "SELECT MAX(value) FROM personal_records
+             WHERE exercise_id = ?1 AND record_type = ?2 AND rep_count IS ?3 AND achieved_on <= ?4",
+            rusqlite::params![candidate.exercise_id, candidate.record_type.to_string(), candidate.rep_count, session_date],
+            |row| row.get(0),
+        )?;
+        if previous.is_some_and(|prev| candidate.value <= prev + IMPROVEMENT_EPSILON) {
+            continue;
+        }
+
+        conn.execute(
+            "INSERT INTO personal_records
+                (exercise_id, record_type, rep_count, value, previous_value, exercise_set_id, workout_session_id, achieved_on)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            rusqlite::params![
+                candidate.exercise_id,
+                candidate.record_type.to_string(),
+                candidate.rep_count,
+                candidate.value,
+                previous,
+                candidate.exercise_set_id,
+                session_id,
+                session_date
+            ],
+        )?;
+
+        let exercise_name: String = conn
+            .query_row("SELECT name FROM exercises WHERE id = ?1", [candidate.exercise_id], |row| row.get(0))
+            .optional()?
+            .unwrap_or_default();
+        new_records.push(NewRecord {
+            exercise_name,
+            record_type: candidate.record_type,
+            rep_count: candidate.rep_count,
+            value: candidate.value,
+            previous_value: previous,
+        });
+    }
+    Ok(new_records)
+}
+
+// Clears `personal_records` and replays every session in date order, e.g. after
+// changing the e1RM formula or for history logged before records existed.
+pub fn rebuild_all(conn: &mut Connection, formula: E1rmFormula) -> DbResult<usize> {
+    let tx = conn.transaction()?;
+    let total = replay_all(&tx, formula)?;
+    tx.commit()?;
+    Ok(total)
+}
+
+fn replay_all(conn: &Connection, formula: E1rmFormula) -> DbResult<usize> {
+    conn.execute("DELETE FROM personal_records", [])?;
+    let sessions = {
+        let mut stmt = conn.prepare("SELECT id, session_date FROM workout_sessions ORDER BY session_date, id")?;
+        stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?
+    };
+    let mut total = 0;
+    for (session_id, session_date) in sessions {
+        total += detect_in_order(conn, session_id, &session_date, formula)?.len();
+    }
+    Ok(total)
+}
+
+const RECORD_COLUMNS: &str = "pr.id, pr.exercise_id, e.name, pr.record_type, pr.rep_count, pr.value,
+     pr.previous_value, pr.exercise_set_id, pr.achieved_on";
+
+fn record_from_row(row: &rusqlite::Row) -> rusqlite::Result<PersonalRecord> {
+    let record_type: String = row.get(3)?;
+    Ok(PersonalRecord {
+        id: row.get(0)?,
+        exercise_id: row.get(1)?,
+        exercise_name: row.get(2)?,
+        record_type: record_type.parse().map_err(|e: String| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, e.into())
+        })?,
+        rep_count: row.get(4)?,
+        value: row.get(5)?,
+        previous_value: row.get(6)?,
+        exercise_set_id: row.get(7)?,
+        achieved_on: row.get(8)?,
+    })
+}
+
+// The standing record for every (exercise, kind, rep count), sorted for display.
+pub fn load_current(conn: &Connection) -> DbResult<Vec<PersonalRecord>> {
+    let sql = format!(
+        "SELECT {} FROM personal_records pr JOIN exercises e ON e.id = pr.exercise_id
+         WHERE NOT EXISTS (
+             SELECT 1 FROM personal_records better
+             WHERE better.exercise_id = pr.exercise_id AND better.record_type = pr.record_type
+               AND better.rep_count IS pr.rep_count AND better.value > pr.value
+         )
+         ORDER BY e.name COLLATE NOCASE",
+        RECORD_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut records = stmt.query_map([], record_from_row)?.collect::<rusqlite::Result<Vec<_>>>()?;
+    records.sort_by(|a, b| {
+        a.exercise_name
+            .to_lowercase()
+            .cmp(&b.exercise_name.to_lowercase())
+            .then(a.record_type.cmp(&b.record_type))
+            .then(a.rep_count.cmp(&b.rep_count))
+    });
+    Ok(records)
+}
+
+// Every record ever set, newest first; optionally limited to one exercise.
+pub fn load_history(conn: &Connection, exercise_id: Option<i64>, limit: i64) -> DbResult<Vec<PersonalRecord>> {
+    let sql = format!(
+        "SELECT {} FROM personal_records pr JOIN exercises e ON e.id = pr.exercise_id
+         WHERE ?1 IS NULL OR pr.exercise_id = ?1
+         ORDER BY pr.achieved_on DESC, pr.id DESC LIMIT ?2",
+        RECORD_COLUMNS
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let records = stmt.query_map(rusqlite::params![exercise_id, limit], record_from_row)?;
+    Ok(records.collect::<rusqlite::Result<_>>()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    const EXERCISES: &str = "INSERT INTO exercises (name) VALUES ('Bench Press'), ('Pull Up');";
+
+    // Inserts a session and returns its id. Sets are (exercise_id, reps, weight).
+    fn log_session(conn: &Connection, date: &str, sets: &[(i64, i64, f64)]) -> i64 {
+        conn.execute("INSERT INTO workout_sessions (session_date) VALUES (?1)", [date]).unwrap();
+        let session_id = conn.last_insert_rowid();
+        for (i, (exercise_id, reps, weight)) in sets.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO exercise_sets (workout_session_id, exercise_id, set_order, reps, weight_lbs) VALUES (?1, ?2, ?3, ?4, ?5)",
+                rusqlite::params![session_id, exercise_id, i as i64 + 1, reps, weight],
+            )
+            .unwrap();
+        }
+        session_id
+    }
+
+    #[test]
+    fn candidates_cover_each_record_kind() {
+        let set = |set_id, exercise_id, reps, weight_lbs| CandidateSet { set_id, exercise_id, reps, weight_lbs, rpe: None };
+        let sets = [set(1, 1, 5, 200.0), set(2, 1, 5, 205.0), set(3, 1, 3, 215.0), set(4, 2, 12, 0.0), set(5, 2, 10, 0.0)];
+        let candidates = session_candidates(&sets, E1rmFormula::Epley);
+
+        let find = |exercise_id, record_type, rep_count| {
+            candidates
+                .iter()
+                .find(|c| c.exercise_id == exercise_id && c.record_type == record_type && c.rep_count == rep_count)
+                .cloned()
+        };
+        assert_eq!(find(1, RecordType::RepMax, Some(5)).map(|c| (c.value, c.exercise_set_id)), Some((205.0, Some(2))));
+        assert_eq!(find(1, RecordType::RepMax, Some(3)).map(|c| c.value), Some(215.0));
+        assert_eq!(find(1, RecordType::RepMax, Some(1)), None);
+        assert_eq!(find(1, RecordType::SessionVolume, None).map(|c| c.value), Some(2670.0));
+        assert_eq!(find(2, RecordType::BodyweightReps, None).map(|c| (c.value, c.exercise_set_id)), Some((12.0, Some(4))));
+        assert_eq!(find(2, RecordType::EstimatedOneRepMax, None), None);
+    }
+
+    #[test]
+    fn only_improvements_are_recorded() {
+        let conn = test_conn();
+        conn.execute_batch(EXERCISES).unwrap();
+        let first = log_session(&conn, "2025-01-01", &[(1, 5, 200.0)]);
+        let announced = detect_for_session(&conn, first, E1rmFormula::Epley).unwrap();
+        assert!(announced.iter().all(|r| r.previous_value.is_none()));
+        assert_eq!(announced.len(), 3); // 5RM, e1RM, volume
+
+        // Same weight again: nothing new. Heavier triple: new 3RM only.
+        let second = log_session(&conn, "2025-01-08", &[(1, 5, 200.0), (1, 3, 205.0)]);
+        let announced = detect_for_session(&conn, second, E1rmFormula::Epley).unwrap();
+        let labels: Vec<String> = announced.iter().map(|r| record_label(r.record_type, r.rep_count)).collect();
+        assert_eq!(labels, vec!["3RM".to_string(), "Session volume".to_string()]);
+
+        let third = log_session(&conn, "2025-01-15", &[(1, 5, 210.0)]);
+        let announced = detect_for_session(&conn, third, E1rmFormula::Epley).unwrap();
+        let five_rm = announced.iter().find(|r| r.rep_count == Some(5)).unwrap();
+        assert_eq!(five_rm.previous_value, Some(200.0));
+        assert!(five_rm.announcement(WeightUnit::Lbs).contains("was 200.0 lbs"));
+
+        let current = load_current(&conn).unwrap();
+        let five_rm = current.iter().find(|r| r.label() == "5RM").unwrap();
+        assert_eq!(five_rm.value, 210.0);
+        assert_eq!(five_rm.achieved_on, "2025-01-15");
+        assert_eq!(load_history(&conn, Some(1), 100).unwrap().len(), 7);
+    }
+
+    #[test]
+    fn rebuild_replays_history_in_date_order() {
+        let mut conn = test_conn();
+        conn.execute_batch(EXERCISES).unwrap();
+        // Logged out of order: the later-dated session is inserted first.
+        log_session(&conn, "2025-02-01", &[(1, 5, 220.0)]);
+        log_session(&conn, "2025-01-01", &[(1, 5, 200.0)]);
+        rebuild_all(&mut conn, E1rmFormula::Epley).unwrap();
+
+        let history: Vec<(String, f64)> = load_history(&conn, Some(1), 100)
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.label() == "5RM")
+            .map(|r| (r.achieved_on, r.value))
+            .collect();
+        assert_eq!(history, vec![("2025-02-01".to_string(), 220.0), ("2025-01-01".to_string(), 200.0)]);
+    }
+
+    #[test]
+    fn backdated_sessions_replay_history() {
+        let conn = test_conn();
+        conn.execute_batch(EXERCISES).unwrap();
+        let later = log_session(&conn, "2025-03-01", &[(1, 5, 200.0)]);
+        detect_for_session(&conn, later, E1rmFormula::Epley).unwrap();
+
+        // Backdated and lighter: still a record as of its own date.
+        let earlier = log_session(&conn, "2025-02-01", &[(1, 5, 180.0)]);
+        let announced = detect_for_session(&conn, earlier, E1rmFormula::Epley).unwrap();
+        assert!(announced.iter().any(|r| r.rep_count == Some(5) && r.value == 180.0 && r.previous_value.is_none()));
+
+        // Backdated and heavier than everything: the later session stops being a record.
+        let heaviest = log_session(&conn, "2025-02-15", &[(1, 5, 220.0)]);
+        let announced = detect_for_session(&conn, heaviest, E1rmFormula::Epley).unwrap();
+        let five_rm = announced.iter().find(|r| r.rep_count == Some(5)).unwrap();
+        assert_eq!(five_rm.previous_value, Some(180.0));
+
+        let history: Vec<(String, f64)> = load_history(&conn, Some(1), 100)
+            .unwrap()
+            .into_iter()
+            .filter(|r| r.label() == "5RM")
+            .map(|r| (r.achieved_on, r.value))
+            .collect();
+        assert_eq!(history, vec![("2025-02-15".to_string(), 220.0), ("2025-02-01".to_string(), 180.0)]);
+    }
+}
@@ -0,0 +1,59 @@
+use super::DbResult;
+use crate::settings::Settings;
+use rusqlite::Connection;
+
+pub fn set(conn: &Connection, key: &str, value: &str) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?1, ?2)
+         ON CONFLICT (key) DO UPDATE SET value = excluded.value, updated_at = CURRENT_TIMESTAMP",
+        [key, value],
+    )?;
+    Ok(())
+}
+
+// Missing keys fall back to their defaults.
+pub fn load(conn: &Connection) -> DbResult<Settings> {
+    let mut stmt = conn.prepare("SELECT key, value FROM settings")?;
+    let pairs = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Settings::from_pairs(pairs.iter().map(|(k, v)| (k.as_str(), v.as_str()))))
+}
+
+pub fn save(conn: &mut Connection, settings: &Settings) -> DbResult<()> {
+    let tx = conn.transaction()?;
+    for (key, value) in settings.to_pairs() {
+        set(&tx, key, &value)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::Theme;
+    use crate::units::WeightUnit;
+    use crate::db::test_conn;
+
+    #[test]
+    fn set_overwrites() {
+        let conn = test_conn();
+        set(&conn, "lift_unit", "lbs").unwrap();
+        set(&conn, "lift_unit", "kg").unwrap();
+        assert_eq!(load(&conn).unwrap().lift_unit, WeightUnit::Kg);
+    }
+
+    #[test]
+    fn load_and_save_round_trip() {
+        let mut conn = test_conn();
+        assert_eq!(load(&conn).unwrap(), Settings::default());
+        // Rows written before the settings struct existed still load.
+        set(&conn, "bodyweight_unit", "st").unwrap();
+        assert_eq!(load(&conn).unwrap().bodyweight_unit, WeightUnit::Stone);
+
+        let settings = Settings { theme: Theme::Dark, console_height: 320.0, ..Settings::default() };
+        save(&mut conn, &settings).unwrap();
+        assert_eq!(load(&conn).unwrap(), settings);
+    }
+}
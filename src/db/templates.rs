@@ -0,0 +1,179 @@
+use super::workouts::NewExercise;
+use super::{exercises, DbError, DbResult};
+use rusqlite::{Connection, OptionalExtension};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TemplateExercise {
+    pub exercise_id: i64,
+    pub exercise_name: String,
+    pub target_sets: i64,
+    pub rep_min: i64,
+    pub rep_max: i64,
+    pub target_rpe: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkoutTemplate {
+    pub id: i64,
+    pub name: String,
+    pub notes: Option<String>,
+    pub exercises: Vec<TemplateExercise>,
+}
+
+// A template exercise waiting to be written; the exercise is looked up by name.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewTemplateExercise {
+    pub name: String,
+    pub target_sets: i64,
+    pub rep_min: i64,
+    pub rep_max: i64,
+    pub target_rpe: Option<f64>,
+}
+
+impl NewTemplateExercise {
+    // Targets taken from what was actually done: the set count, the rep range
+    // across those sets and the hardest RPE logged.
+    pub fn from_logged(exercise: &NewExercise) -> Self {
+        let reps = exercise.sets.iter().map(|s| s.reps);
+        Self {
+            name: exercise.name.clone(),
+            target_sets: exercise.sets.len() as i64,
+            rep_min: reps.clone().min().unwrap_or(1),
+            rep_max: reps.max().unwrap_or(1),
+            target_rpe: exercise.sets.iter().filter_map(|s| s.rpe).reduce(f64::max),
+        }
+    }
+}
+
+// Every template with its exercises, alphabetically.
+pub fn list(conn: &Connection) -> DbResult<Vec<WorkoutTemplate>> {
+    let mut stmt = conn.prepare("SELECT id, name, notes FROM workout_templates ORDER BY name")?;
+    let mut templates = stmt
+        .query_map([], |row| {
+            Ok(WorkoutTemplate { id: row.get(0)?, name: row.get(1)?, notes: row.get(2)?, exercises: Vec::new() })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT te.exercise_id, e.name, te.target_sets, te.rep_min, te.rep_max, te.target_rpe
+         FROM template_exercises te
+         JOIN exercises e ON e.id = te.exercise_id
+         WHERE te.template_id = ?1
+         ORDER BY te.position",
+    )?;
+    for template in &mut templates {
+        template.exercises = stmt
+            .query_map([template.id], |row| {
+                Ok(TemplateExercise {
+                    exercise_id: row.get(0)?,
+                    exercise_name: row.get(1)?,
+                    target_sets: row.get(2)?,
+                    rep_min: row.get(3)?,
+                    rep_max: row.get(4)?,
+                    target_rpe: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+    Ok(templates)
+}
+
+// Saves a template under `name`, replacing the exercises of an existing
+// template with the same name (case-insensitive). Returns the template id.
+pub fn save(conn: &mut Connection, name: &str, notes: Option<&str>, exercises: &[NewTemplateExercise]) -> DbResult<i64> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(DbError::Invalid("give the template a name.".to_string()));
+    }
+    if exercises.is_empty() {
+        return Err(DbError::Invalid("a template needs at least one exercise.".to_string()));
+    }
+    if let Some(bad) = exercises.iter().find(|e| e.target_sets < 1 || e.rep_min < 1 || e.rep_min > e.rep_max) {
+        return Err(DbError::Invalid(format!("{}: targets need at least one set and a valid rep range.", bad.name)));
+    }
+
+    let tx = conn.transaction()?;
+    let existing: Option<i64> =
+        tx.query_row("SELECT id FROM workout_templates WHERE name = ?1", [name], |row| row.get(0)).optional()?;
+    let template_id = match existing {
+        Some(id) => {
+            tx.execute("UPDATE workout_templates SET notes = ?1 WHERE id = ?2", rusqlite::params![notes, id])?;
+            tx.execute("DELETE FROM template_exercises WHERE template_id = ?1", [id])?;
+            id
+        }
+        None => {
+            tx.execute("INSERT INTO workout_templates (name, notes) VALUES (?1, ?2)", rusqlite::params![name, notes])?;
+            tx.last_insert_rowid()
+        }
+    };
+    for (position, exercise) in exercises.iter().enumerate() {
+        let exercise_id = exercises::find_or_create(&tx, &exercise.name)?;
+        tx.execute(
+            "INSERT INTO template_exercises (template_id, exercise_id, position, target_sets, rep_min, rep_max, target_rpe)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                template_id,
+                exercise_id,
+                position as i64 + 1,
+                exercise.target_sets,
+                exercise.rep_min,
+                exercise.rep_max,
+                exercise.target_rpe
+            ],
+        )?;
+    }
+    tx.commit()?;
+    Ok(template_id)
+}
+
+pub fn delete(conn: &mut Connection, template_id: i64) -> DbResult<()> {
+    let tx = conn.transaction()?;
+    tx.execute("DELETE FROM template_exercises WHERE template_id = ?1", [template_id])?;
+    tx.execute("DELETE FROM workout_templates WHERE id = ?1", [template_id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+    use crate::db::workouts::NewSet;
+
+    fn logged(name: &str, sets: &[(i64, Option<f64>)]) -> NewExercise {
+        NewExercise {
+            name: name.into(),
+            sets: sets.iter().map(|&(reps, rpe)| NewSet { reps, weight_lbs: 100.0, rpe, notes: None }).collect(),
+        }
+    }
+
+    #[test]
+    fn saves_from_logged_workout_and_replaces_by_name() {
+        let mut conn = test_conn();
+        let workout = [logged("Squat", &[(5, Some(7.0)), (5, Some(8.0)), (3, None)]), logged("Row", &[(10, None)])];
+        let targets: Vec<_> = workout.iter().map(NewTemplateExercise::from_logged).collect();
+        assert_eq!(
+            targets[0],
+            NewTemplateExercise { name: "Squat".into(), target_sets: 3, rep_min: 3, rep_max: 5, target_rpe: Some(8.0) }
+        );
+
+        let id = save(&mut conn, "Leg Day", None, &targets).unwrap();
+        let templates = list(&conn).unwrap();
+        assert_eq!(templates.len(), 1);
+        assert_eq!(templates[0].exercises.iter().map(|e| e.exercise_name.as_str()).collect::<Vec<_>>(), ["Squat", "Row"]);
+
+        // Same name, different case: replaced rather than duplicated.
+        assert_eq!(save(&mut conn, "leg day", Some("heavy"), &targets[..1]).unwrap(), id);
+        let templates = list(&conn).unwrap();
+        assert_eq!(templates[0].exercises.len(), 1);
+        assert_eq!(templates[0].notes.as_deref(), Some("heavy"));
+
+        assert!(save(&mut conn, " ", None, &targets).is_err());
+        assert!(save(&mut conn, "Empty", None, &[]).is_err());
+
+        delete(&mut conn, id).unwrap();
+        assert!(list(&conn).unwrap().is_empty());
+        let orphans: i64 = conn.query_row("SELECT COUNT(*) FROM template_exercises", [], |r| r.get(0)).unwrap();
+        assert_eq!(orphans, 0);
+    }
+}
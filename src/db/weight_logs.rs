@@ -0,0 +1,209 @@
+use super::{diet_cycles, DbResult};
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Row};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightLog {
+    pub id: i64,
+    pub diet_cycle_id: Option<i64>,
+    pub log_date: String,
+    pub weight_lbs: f64,
+}
+
+fn from_row(row: &Row) -> rusqlite::Result<WeightLog> {
+    Ok(WeightLog {
+        id: row.get(0)?,
+        diet_cycle_id: row.get(1)?,
+        log_date: row.get(2)?,
+        weight_lbs: row.get(3)?,
+    })
+}
+
+pub fn insert(conn: &Connection, diet_cycle_id: Option<i64>, log_date: &str, weight_lbs: f64) -> DbResult<i64> {
+    conn.execute(
+        "INSERT INTO weight_logs (diet_cycle_id, log_date, weight_lbs) VALUES (?1, ?2, ?3)",
+        rusqlite::params![diet_cycle_id, log_date, weight_lbs],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// The earliest weigh-in on `log_date` across all cycles, if any.
+pub fn find_on_date(conn: &Connection, log_date: &str) -> DbResult<Option<WeightLog>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, diet_cycle_id, log_date, weight_lbs FROM weight_logs WHERE log_date = ?1 ORDER BY id LIMIT 1",
+            [log_date],
+            from_row,
+        )
+        .optional()?)
+}
+
+// Whether a weigh-in on `log_date` is within `tolerance_lbs` of `weight_lbs`.
+pub fn has_close_match(conn: &Connection, log_date: &str, weight_lbs: f64, tolerance_lbs: f64) -> DbResult<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM weight_logs WHERE log_date = ?1 AND ABS(weight_lbs - ?2) < ?3)",
+        rusqlite::params![log_date, weight_lbs, tolerance_lbs],
+        |row| row.get(0),
+    )?)
+}
+
+// Newest first, at most `limit` rows.
+pub fn recent_for_cycle(conn: &Connection, diet_cycle_id: i64, limit: i64) -> DbResult<Vec<WeightLog>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, diet_cycle_id, log_date, weight_lbs FROM weight_logs
+         WHERE diet_cycle_id = ?1
+         ORDER BY log_date DESC, id DESC LIMIT ?2",
+    )?;
+    let rows = stmt.query_map(rusqlite::params![diet_cycle_id, limit], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// The most recent weigh-in in any cycle.
+pub fn latest(conn: &Connection) -> DbResult<Option<WeightLog>> {
+    Ok(conn
+        .query_row(
+            "SELECT id, diet_cycle_id, log_date, weight_lbs FROM weight_logs ORDER BY log_date DESC, id DESC LIMIT 1",
+            [],
+            from_row,
+        )
+        .optional()?)
+}
+
+// Oldest first; `None` returns every weigh-in regardless of cycle.
+pub fn list(conn: &Connection, diet_cycle_id: Option<i64>) -> DbResult<Vec<WeightLog>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, diet_cycle_id, log_date, weight_lbs FROM weight_logs
+         WHERE ?1 IS NULL OR diet_cycle_id = ?1
+         ORDER BY log_date ASC, id ASC",
+    )?;
+    let rows = stmt.query_map([diet_cycle_id], from_row)?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+pub fn update(conn: &Connection, entry: &WeightLog) -> DbResult<()> {
+    conn.execute(
+        "UPDATE weight_logs SET diet_cycle_id = ?1, log_date = ?2, weight_lbs = ?3 WHERE id = ?4",
+        rusqlite::params![entry.diet_cycle_id, entry.log_date, entry.weight_lbs, entry.id],
+    )?;
+    Ok(())
+}
+
+// What `edit` did with a changed weigh-in.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EditOutcome {
+    Saved(WeightLog),
+    // Nothing written; this weigh-in already has the new date.
+    DateTaken(WeightLog),
+}
+
+// Saves a changed weigh-in. Moving it to another date refuses to create a
+// second weigh-in for that day and re-attaches it to the cycle covering the
+// new date.
+pub fn edit(conn: &Connection, original: &WeightLog, date: NaiveDate, weight_lbs: f64, today: NaiveDate) -> DbResult<EditOutcome> {
+    let log_date = date.format("%Y-%m-%d").to_string();
+    let mut updated = WeightLog { log_date, weight_lbs, ..original.clone() };
+    if updated.log_date != original.log_date {
+        if let Some(existing) = find_on_date(conn, &updated.log_date)?
+            && existing.id != original.id
+        {
+            return Ok(EditOutcome::DateTaken(existing));
+        }
+        updated.diet_cycle_id = diet_cycles::covering(conn, date, today)?;
+    }
+    update(conn, &updated)?;
+    Ok(EditOutcome::Saved(updated))
+}
+
+pub fn delete(conn: &Connection, id: i64) -> DbResult<()> {
+    conn.execute("DELETE FROM weight_logs WHERE id = ?1", [id])?;
+    Ok(())
+}
+
+// Re-inserts a deleted row under its original id.
+pub fn restore(conn: &Connection, entry: &WeightLog) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO weight_logs (id, diet_cycle_id, log_date, weight_lbs) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![entry.id, entry.diet_cycle_id, entry.log_date, entry.weight_lbs],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    #[test]
+    fn insert_edit_delete_and_restore() {
+        let conn = test_conn();
+        let first = insert(&conn, None, "2025-01-01", 200.0).unwrap();
+        insert(&conn, None, "2025-01-02", 199.0).unwrap();
+
+        let mut entry = find_on_date(&conn, "2025-01-01").unwrap().unwrap();
+        assert_eq!(entry.id, first);
+        entry.weight_lbs = 201.5;
+        update(&conn, &entry).unwrap();
+
+        delete(&conn, first).unwrap();
+        assert!(find_on_date(&conn, "2025-01-01").unwrap().is_none());
+        restore(&conn, &entry).unwrap();
+
+        let all = list(&conn, None).unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], entry);
+    }
+
+    #[test]
+    fn edit_moves_between_cycles_and_refuses_taken_dates() {
+        let conn = test_conn();
+        conn.execute_batch(
+            "INSERT INTO diet_cycles (phase, start_date, planned_end_date, actual_end_date) VALUES ('Cut', '2025-01-01', '2025-02-01', '2025-02-01');
+             INSERT INTO diet_cycles (phase, start_date, planned_end_date) VALUES ('Bulk', '2025-02-01', '2025-05-01');",
+        )
+        .unwrap();
+        let day = |m, d| NaiveDate::from_ymd_opt(2025, m, d).unwrap();
+        let today = day(3, 1);
+        insert(&conn, Some(1), "2025-01-10", 200.0).unwrap();
+        let second = insert(&conn, Some(1), "2025-01-11", 199.0).unwrap();
+        let original = find_on_date(&conn, "2025-01-11").unwrap().unwrap();
+
+        // Onto a day that already has a weigh-in: nothing changes.
+        let outcome = edit(&conn, &original, day(1, 10), 198.0, today).unwrap();
+        assert!(matches!(outcome, EditOutcome::DateTaken(ref existing) if existing.weight_lbs == 200.0));
+        assert_eq!(find_on_date(&conn, "2025-01-11").unwrap().unwrap(), original);
+        assert_eq!(list(&conn, None).unwrap().len(), 2);
+
+        // Into the next cycle's range: the cycle follows the date.
+        let EditOutcome::Saved(moved) = edit(&conn, &original, day(2, 3), 198.0, today).unwrap() else {
+            panic!("edit was refused");
+        };
+        assert_eq!((moved.id, moved.diet_cycle_id, moved.weight_lbs), (second, Some(2), 198.0));
+        assert_eq!(find_on_date(&conn, "2025-02-03").unwrap().unwrap(), moved);
+
+        // Same date, new weight: the cycle is left alone.
+        let EditOutcome::Saved(reweighed) = edit(&conn, &moved, day(2, 3), 197.0, today).unwrap() else {
+            panic!("edit was refused");
+        };
+        assert_eq!(reweighed.diet_cycle_id, Some(2));
+    }
+
+    #[test]
+    fn recent_is_newest_first_and_limited() {
+        let conn = test_conn();
+        conn.execute(
+            "INSERT INTO diet_cycles (phase, start_date, planned_end_date) VALUES ('Cut', '2025-01-01', '2025-03-01')",
+            [],
+        )
+        .unwrap();
+        let cycle_id = conn.last_insert_rowid();
+        for day in 1..=5 {
+            insert(&conn, Some(cycle_id), &format!("2025-01-0{}", day), 200.0 - day as f64).unwrap();
+        }
+        insert(&conn, None, "2025-01-09", 190.0).unwrap();
+
+        let recent = recent_for_cycle(&conn, cycle_id, 3).unwrap();
+        let dates: Vec<_> = recent.iter().map(|e| e.log_date.as_str()).collect();
+        assert_eq!(dates, vec!["2025-01-05", "2025-01-04", "2025-01-03"]);
+        assert_eq!(list(&conn, Some(cycle_id)).unwrap().len(), 5);
+    }
+}
@@ -0,0 +1,391 @@
+use super::records::{self, NewRecord};
+use super::{exercises, DbResult};
+use crate::e1rm::E1rmFormula;
+use chrono::NaiveDate;
+use rusqlite::{Connection, OptionalExtension, Row};
+use log::error;
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkoutSession {
+    pub id: i64,
+    pub session_date: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExerciseSet {
+    pub id: i64,
+    pub workout_session_id: i64,
+    pub exercise_id: i64,
+    pub set_order: i64,
+    pub reps: i64,
+    pub weight_lbs: f64,
+    pub rpe: Option<f64>,
+    pub notes: Option<String>,
+}
+
+// A validated set waiting to be written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewSet {
+    pub reps: i64,
+    pub weight_lbs: f64,
+    pub rpe: Option<f64>,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewExercise {
+    pub name: String,
+    pub sets: Vec<NewSet>,
+}
+
+// One exercise of a logged workout: how many sets and the heaviest.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExerciseSummary {
+    pub name: String,
+    pub sets: i64,
+    pub top_weight_lbs: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkoutSummary {
+    pub date: String,
+    pub notes: Option<String>,
+    pub exercises: Vec<ExerciseSummary>,
+    pub volume_lbs: f64,
+}
+
+const SET_COLUMNS: &str = "es.id, es.workout_session_id, es.exercise_id, es.set_order, es.reps, es.weight_lbs, es.rpe, es.notes";
+
+fn set_from_row(row: &Row) -> rusqlite::Result<ExerciseSet> {
+    Ok(ExerciseSet {
+        id: row.get(0)?,
+        workout_session_id: row.get(1)?,
+        exercise_id: row.get(2)?,
+        set_order: row.get(3)?,
+        reps: row.get(4)?,
+        weight_lbs: row.get(5)?,
+        rpe: row.get(6)?,
+        notes: row.get(7)?,
+    })
+}
+
+// Writes one `workout_sessions` row and all of its `exercise_sets` in a single
+// transaction, creating any exercises that don't exist yet. `set_order` starts
+// at 1 for each exercise and keeps counting if the same exercise comes up
+// again later in the workout. Personal records are detected in the same transaction.
+// Returns the new session and any records it set.
+pub fn insert(
+    conn: &mut Connection,
+    session_date: NaiveDate,
+    notes: Option<&str>,
+    exercises: &[NewExercise],
+    formula: E1rmFormula,
+) -> DbResult<(WorkoutSession, Vec<NewRecord>)> {
+    let tx = conn.transaction()?;
+    let session_date = session_date.format("%Y-%m-%d").to_string();
+    let session_id = create_session(&tx, &session_date, notes)?;
+    let session = WorkoutSession { id: session_id, session_date, notes: notes.map(str::to_string) };
+
+    let mut last_orders: HashMap<i64, i64> = HashMap::new();
+    for exercise in exercises {
+        let exercise_id = exercises::find_or_create(&tx, &exercise.name)?;
+        let last_order = last_orders.entry(exercise_id).or_insert(0);
+        for set in &exercise.sets {
+            *last_order += 1;
+            insert_set(&tx, session.id, exercise_id, *last_order, set)?;
+        }
+    }
+
+    let new_records = records::detect_for_session(&tx, session.id, formula)?;
+    tx.commit()?;
+    Ok((session, new_records))
+}
+
+// Adds sets for one exercise to the first session on `session_date`, creating
+// the session if there isn't one, numbered after any sets it already has for
+// that exercise. Records are detected as for a new workout. Returns the
+// session id and any records the sets beat.
+pub fn append(
+    conn: &mut Connection,
+    session_date: NaiveDate,
+    exercise: &NewExercise,
+    formula: E1rmFormula,
+) -> DbResult<(i64, Vec<NewRecord>)> {
+    let tx = conn.transaction()?;
+    let session_date = session_date.format("%Y-%m-%d").to_string();
+    let session_id = match session_on(&tx, &session_date)? {
+        Some(id) => id,
+        None => create_session(&tx, &session_date, None)?,
+    };
+    let exercise_id = exercises::find_or_create(&tx, &exercise.name)?;
+    let last_order: i64 = tx.query_row(
+        "SELECT COALESCE(MAX(set_order), 0) FROM exercise_sets WHERE workout_session_id = ?1 AND exercise_id = ?2",
+        [session_id, exercise_id],
+        |row| row.get(0),
+    )?;
+    for (set_idx, set) in exercise.sets.iter().enumerate() {
+        insert_set(&tx, session_id, exercise_id, last_order + set_idx as i64 + 1, set)?;
+    }
+
+    let new_records = records::detect_for_session(&tx, session_id, formula)?;
+    tx.commit()?;
+    Ok((session_id, new_records))
+}
+
+pub fn create_session(conn: &Connection, session_date: &str, notes: Option<&str>) -> DbResult<i64> {
+    conn.execute(
+        "INSERT INTO workout_sessions (session_date, notes) VALUES (?1, ?2)",
+        rusqlite::params![session_date, notes],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+pub fn insert_set(conn: &Connection, session_id: i64, exercise_id: i64, set_order: i64, set: &NewSet) -> DbResult<i64> {
+    conn.execute(
+        "INSERT INTO exercise_sets (workout_session_id, exercise_id, set_order, reps, weight_lbs, rpe, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        rusqlite::params![session_id, exercise_id, set_order, set.reps, set.weight_lbs, set.rpe, set.notes],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+// The first session logged on `session_date`, if any.
+pub fn session_on(conn: &Connection, session_date: &str) -> DbResult<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM workout_sessions WHERE session_date = ?1 ORDER BY id LIMIT 1",
+            [session_date],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+// Every session logged on `session_date`, oldest first.
+pub fn sessions_on(conn: &Connection, session_date: &str) -> DbResult<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT id FROM workout_sessions WHERE session_date = ?1 ORDER BY id")?;
+    let rows = stmt.query_map([session_date], |row| row.get(0))?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// The first session on `session_date` with exactly these notes, if any.
+pub fn find_session(conn: &Connection, session_date: &str, notes: Option<&str>) -> DbResult<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM workout_sessions WHERE session_date = ?1 AND notes IS ?2 ORDER BY id LIMIT 1",
+            rusqlite::params![session_date, notes],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+// Whether the session already has this set, with the weight matched to within `tolerance_lbs`.
+pub fn set_exists(
+    conn: &Connection,
+    session_id: i64,
+    exercise_id: i64,
+    set_order: i64,
+    set: &NewSet,
+    tolerance_lbs: f64,
+) -> DbResult<bool> {
+    Ok(conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM exercise_sets
+             WHERE workout_session_id = ?1 AND exercise_id = ?2 AND set_order = ?3 AND reps = ?4
+               AND ABS(weight_lbs - ?5) < ?6)",
+        rusqlite::params![session_id, exercise_id, set_order, set.reps, set.weight_lbs, tolerance_lbs],
+        |row| row.get(0),
+    )?)
+}
+
+// Oldest first.
+pub fn list_sessions(conn: &Connection) -> DbResult<Vec<WorkoutSession>> {
+    let mut stmt = conn.prepare("SELECT id, session_date, notes FROM workout_sessions ORDER BY session_date, id")?;
+    let rows = stmt.query_map([], |row| {
+        Ok(WorkoutSession { id: row.get(0)?, session_date: row.get(1)?, notes: row.get(2)? })
+    })?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// The most recent session with its exercises in the order they were logged.
+pub fn latest_session_summary(conn: &Connection) -> DbResult<Option<WorkoutSummary>> {
+    let Some((id, date, notes)) = conn
+        .query_row(
+            "SELECT id, session_date, notes FROM workout_sessions ORDER BY session_date DESC, id DESC LIMIT 1",
+            [],
+            |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?)),
+        )
+        .optional()?
+    else {
+        return Ok(None);
+    };
+    let mut stmt = conn.prepare(
+        "SELECT e.name, COUNT(*), MAX(es.weight_lbs), SUM(es.reps * es.weight_lbs)
+         FROM exercise_sets es JOIN exercises e ON e.id = es.exercise_id
+         WHERE es.workout_session_id = ?1
+         GROUP BY es.exercise_id ORDER BY MIN(es.id)",
+    )?;
+    let rows = stmt.query_map([id], |row| {
+        Ok((ExerciseSummary { name: row.get(0)?, sets: row.get(1)?, top_weight_lbs: row.get(2)? }, row.get::<_, f64>(3)?))
+    })?;
+    let mut exercises = Vec::new();
+    let mut volume_lbs = 0.0;
+    for row in rows {
+        let (exercise, volume) = row?;
+        exercises.push(exercise);
+        volume_lbs += volume;
+    }
+    Ok(Some(WorkoutSummary { date, notes, exercises, volume_lbs }))
+}
+
+// Each day from `from` to `to` (inclusive) with at least one session.
+pub fn session_dates_between(conn: &Connection, from: NaiveDate, to: NaiveDate) -> DbResult<Vec<NaiveDate>> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT session_date FROM workout_sessions WHERE session_date BETWEEN ?1 AND ?2 ORDER BY session_date",
+    )?;
+    let rows = stmt.query_map([from.format("%Y-%m-%d").to_string(), to.format("%Y-%m-%d").to_string()], |row| {
+        row.get::<_, String>(0)
+    })?;
+    let mut dates = Vec::new();
+    for row in rows {
+        let date_str = row?;
+        match NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+            Ok(date) => dates.push(date),
+            Err(e) => error!("skipping session with bad date {}: {}", date_str, e),
+        }
+    }
+    Ok(dates)
+}
+
+// Every set with its session date and exercise name, in logging order.
+pub fn list_sets(conn: &Connection) -> DbResult<Vec<(String, String, ExerciseSet)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, ws.session_date, e.name
+         FROM exercise_sets es
+         JOIN workout_sessions ws ON ws.id = es.workout_session_id
+         JOIN exercises e ON e.id = es.exercise_id
+         ORDER BY ws.session_date, ws.id, es.id",
+        SET_COLUMNS
+    ))?;
+    let rows = stmt.query_map([], |row| Ok((row.get(8)?, row.get(9)?, set_from_row(row)?)))?;
+    Ok(rows.collect::<rusqlite::Result<_>>()?)
+}
+
+// Every set of one exercise with its session date, oldest session first.
+pub fn exercise_history(conn: &Connection, exercise_id: i64) -> DbResult<Vec<(NaiveDate, ExerciseSet)>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, ws.session_date
+         FROM exercise_sets es
+         JOIN workout_sessions ws ON ws.id = es.workout_session_id
+         WHERE es.exercise_id = ?1
+         ORDER BY ws.session_date, es.set_order",
+        SET_COLUMNS
+    ))?;
+    let rows = stmt.query_map([exercise_id], |row| Ok((row.get::<_, String>(8)?, set_from_row(row)?)))?;
+
+    let mut history = Vec::new();
+    for row in rows {
+        let (date_str, set) = row?;
+        match NaiveDate::parse_from_str(&date_str, "%Y-%m-%d") {
+            Ok(date) => history.push((date, set)),
+            Err(e) => error!("skipping set with bad session date {}: {}", date_str, e),
+        }
+    }
+    Ok(history)
+}
+
+// The sets from the most recent session that included the exercise, in order.
+// Empty if it has never been logged.
+pub fn last_session_sets(conn: &Connection, exercise_id: i64) -> DbResult<Vec<ExerciseSet>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}
+         FROM exercise_sets es
+         WHERE es.workout_session_id = (
+             SELECT ws.id FROM workout_sessions ws
+             JOIN exercise_sets last ON last.workout_session_id = ws.id
+             WHERE last.exercise_id = ?1
+             ORDER BY ws.session_date DESC, ws.id DESC
+             LIMIT 1
+         ) AND es.exercise_id = ?1
+         ORDER BY es.set_order",
+        SET_COLUMNS
+    ))?;
+    let sets = stmt.query_map([exercise_id], set_from_row)?.collect::<Result<Vec<_>, _>>()?;
+    Ok(sets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_conn;
+
+    fn new_set(reps: i64, weight_lbs: f64) -> NewSet {
+        NewSet { reps, weight_lbs, rpe: None, notes: None }
+    }
+
+    #[test]
+    fn insert_assigns_set_order_and_creates_exercises() {
+        let mut conn = test_conn();
+        conn.execute("INSERT INTO exercises (name) VALUES ('Bench Press')", []).unwrap();
+        let exercises = vec![
+            NewExercise { name: "bench press".into(), sets: vec![new_set(5, 185.0), new_set(5, 185.0)] },
+            NewExercise { name: "Row".into(), sets: vec![new_set(8, 135.0)] },
+            // Back to bench later in the same workout.
+            NewExercise { name: "Bench Press".into(), sets: vec![new_set(8, 135.0)] },
+        ];
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let (session, new_records) = insert(&mut conn, date, Some("push"), &exercises, E1rmFormula::Epley).unwrap();
+        assert_eq!(session.session_date, "2025-03-01");
+        assert!(!new_records.is_empty());
+
+        assert_eq!(exercises::list(&conn).unwrap().len(), 2);
+        let bench = exercise_history(&conn, 1).unwrap();
+        let row = exercise_history(&conn, 2).unwrap();
+        let orders: Vec<_> = bench.iter().chain(&row).map(|(_, s)| (s.exercise_id, s.set_order)).collect();
+        assert_eq!(orders, vec![(1, 1), (1, 2), (1, 3), (2, 1)]);
+        assert!(bench.iter().all(|(d, s)| *d == date && s.workout_session_id == session.id));
+    }
+
+    #[test]
+    fn append_continues_the_days_session() {
+        let mut conn = test_conn();
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        let bench = |sets| NewExercise { name: "Bench Press".into(), sets };
+        let (first, records) = append(&mut conn, date, &bench(vec![new_set(5, 185.0)]), E1rmFormula::Epley).unwrap();
+        assert!(!records.is_empty());
+        let (second, records) = append(&mut conn, date, &bench(vec![new_set(5, 175.0), new_set(3, 205.0)]), E1rmFormula::Epley).unwrap();
+        assert_eq!(first, second);
+        assert!(records.iter().any(|r| r.rep_count == Some(3)));
+
+        let sets = last_session_sets(&conn, 1).unwrap();
+        assert_eq!(sets.iter().map(|s| (s.set_order, s.reps)).collect::<Vec<_>>(), vec![(1, 5), (2, 5), (3, 3)]);
+        assert_eq!(list_sessions(&conn).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn last_session_sets_picks_latest_date() {
+        let mut conn = test_conn();
+        let day = |d| NaiveDate::from_ymd_opt(2025, 3, d).unwrap();
+        let squat = |weight| [NewExercise { name: "Squat".into(), sets: vec![new_set(5, weight), new_set(3, weight + 10.0)] }];
+        insert(&mut conn, day(10), None, &squat(225.0), E1rmFormula::Epley).unwrap();
+        // Logged later but dated earlier; the session date wins.
+        insert(&mut conn, day(3), None, &squat(185.0), E1rmFormula::Epley).unwrap();
+
+        let last = last_session_sets(&conn, 1).unwrap();
+        assert_eq!(last.iter().map(|s| s.weight_lbs).collect::<Vec<_>>(), vec![225.0, 235.0]);
+        assert!(last_session_sets(&conn, 99).unwrap().is_empty());
+    }
+
+    #[test]
+    fn insert_rolls_back_on_failure() {
+        let mut conn = test_conn();
+        conn.execute_batch("DROP TABLE exercise_sets;").unwrap();
+        let exercises = [NewExercise { name: "Deadlift".into(), sets: vec![new_set(3, 315.0)] }];
+        let date = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap();
+        assert!(insert(&mut conn, date, None, &exercises, E1rmFormula::Epley).is_err());
+
+        let sessions: i64 = conn.query_row("SELECT COUNT(*) FROM workout_sessions", [], |r| r.get(0)).unwrap();
+        assert_eq!(sessions, 0);
+        assert!(exercises::list(&conn).unwrap().is_empty());
+    }
+}
@@ -1,57 +1,165 @@
-use rusqlite::{Connection, Result};
-use std::path::Path;
-
-pub fn init(db_path: &str) -> Result<()> {
-    let conn = Connection::open(Path::new(db_path))?;
-
-    conn.execute_batch(
-        "
-        CREATE TABLE IF NOT EXISTS diet_cycles (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            phase TEXT NOT NULL,
-            start_date TEXT NOT NULL,
-            planned_end_date TEXT NOT NULL,
-            actual_end_date TEXT,
-            notes TEXT,
-            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS weight_logs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            diet_cycle_id INTEGER,
-            log_date TEXT NOT NULL,
-            weight_lbs REAL NOT NULL,
-            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS exercises (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            name TEXT NOT NULL UNIQUE COLLATE NOCASE,
-            description TEXT,
-            default_metric_to_track TEXT,
-            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS workout_sessions (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            session_date TEXT NOT NULL,
-            notes TEXT,
-            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-
-        CREATE TABLE IF NOT EXISTS exercise_sets (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            workout_session_id INTEGER NOT NULL,
-            exercise_id INTEGER NOT NULL,
-            set_order INTEGER NOT NULL,
-            reps INTEGER NOT NULL,
-            weight_lbs REAL NOT NULL,
-            rpe REAL,
-            notes TEXT,
-            created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
-        );
-    ",
-    )?;
-
-    Ok(())
+use rusqlite::Connection;
+use directories::ProjectDirs;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use log::{error, info};
+
+use crate::backup::{self, BackupReason};
+use crate::migrations::{self, MigrationError};
+
+pub const DB_FILE_NAME: &str = "liftmetrics.db";
+// Overrides the default location; `--db` still wins over it.
+pub const DB_PATH_ENV: &str = "LIFTMETRICS_DB";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DbPathSource {
+    Flag,
+    Env,
+    Default,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DbLocation {
+    pub path: PathBuf,
+    pub source: DbPathSource,
+}
+
+// The platform data directory, e.g. ~/.local/share/liftmetrics on Linux.
+pub fn default_data_dir() -> Option<PathBuf> {
+    ProjectDirs::from("", "", "LiftMetrics").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+// Picks the database file: `--db`, then $LIFTMETRICS_DB, then the data dir.
+pub fn resolve_db_path(flag: Option<PathBuf>, env: Option<OsString>, data_dir: Option<PathBuf>) -> Result<DbLocation, String> {
+    if let Some(path) = flag {
+        return Ok(DbLocation { path, source: DbPathSource::Flag });
+    }
+    if let Some(path) = env.filter(|p| !p.is_empty()) {
+        return Ok(DbLocation { path: PathBuf::from(path), source: DbPathSource::Env });
+    }
+    match data_dir {
+        Some(dir) => Ok(DbLocation { path: dir.join(DB_FILE_NAME), source: DbPathSource::Default }),
+        None => Err(format!(
+            "could not determine a data directory; pass --db <path> or set {}",
+            DB_PATH_ENV
+        )),
+    }
+}
+
+// Databases from before the data directory was used live next to wherever
+// the app was launched. Only offered for migration when the default location
+// is in use and doesn't have a database yet.
+pub fn legacy_db_to_migrate(location: &DbLocation, working_dir: &Path) -> Option<PathBuf> {
+    if location.source != DbPathSource::Default || location.path.exists() {
+        return None;
+    }
+    let legacy = working_dir.join(DB_FILE_NAME);
+    (legacy.is_file() && legacy != location.path).then_some(legacy)
+}
+
+// Copies the legacy file into place, then renames the original so the prompt
+// doesn't come back. The copy is left alone if the rename fails.
+pub fn migrate_legacy_db(legacy: &Path, target: &Path) -> io::Result<PathBuf> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(legacy, target)?;
+    let mut retired = legacy.as_os_str().to_owned();
+    retired.push(".migrated");
+    let retired = PathBuf::from(retired);
+    fs::rename(legacy, &retired)?;
+    info!("moved database from {} to {}", legacy.display(), target.display());
+    Ok(retired)
+}
+
+// Opens (creating if needed) the database and applies any pending migrations.
+// An existing database is snapshotted before it's migrated. Safe to call on
+// every startup.
+pub fn open(db_path: &Path) -> Result<Connection, MigrationError> {
+    if let Some(parent) = db_path.parent().filter(|p| !p.as_os_str().is_empty())
+        && let Err(e) = fs::create_dir_all(parent)
+    {
+        error!("failed to create {}: {}", parent.display(), e);
+    }
+    let mut conn = Connection::open(db_path)?;
+    if migrations::current_version(&conn)? < migrations::latest_version() && has_tables(&conn)? {
+        let now = chrono::Local::now().naive_local();
+        // Migrating anyway beats refusing to start; the failure is in the console.
+        if let Err(e) = backup::snapshot(&conn, &backup::dir_for(db_path), BackupReason::PreMigration, now) {
+            error!("failed to back up the database before migrating: {}", e);
+        }
+    }
+    let applied = migrations::run(&mut conn)?;
+    if applied > 0 {
+        info!("database schema upgraded to version {}", migrations::latest_version());
+    }
+    Ok(conn)
+}
+
+fn has_tables(conn: &Connection) -> rusqlite::Result<bool> {
+    conn.query_row("SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table')", [], |row| row.get(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::test_dir;
+
+    #[test]
+    fn flag_beats_env_beats_default() {
+        let data_dir = Some(PathBuf::from("/data"));
+        let flag = resolve_db_path(Some("a.db".into()), Some("b.db".into()), data_dir.clone()).unwrap();
+        assert_eq!(flag, DbLocation { path: "a.db".into(), source: DbPathSource::Flag });
+        let env = resolve_db_path(None, Some("b.db".into()), data_dir.clone()).unwrap();
+        assert_eq!(env.source, DbPathSource::Env);
+        let default = resolve_db_path(None, Some("".into()), data_dir).unwrap();
+        assert_eq!(default.path, PathBuf::from("/data").join(DB_FILE_NAME));
+        assert!(resolve_db_path(None, None, None).is_err());
+    }
+
+    #[test]
+    fn migrates_legacy_database_once() {
+        let dir = test_dir("migrate");
+        let working_dir = dir.join("cwd");
+        fs::create_dir_all(&working_dir).unwrap();
+        let legacy = working_dir.join(DB_FILE_NAME);
+        open(&legacy).unwrap().execute("INSERT INTO exercises (name) VALUES ('Squat')", []).unwrap();
+
+        let location = resolve_db_path(None, None, Some(dir.join("data"))).unwrap();
+        assert_eq!(legacy_db_to_migrate(&location, &working_dir), Some(legacy.clone()));
+        // An explicit path never triggers the prompt.
+        let explicit = DbLocation { source: DbPathSource::Flag, ..location.clone() };
+        assert_eq!(legacy_db_to_migrate(&explicit, &working_dir), None);
+
+        migrate_legacy_db(&legacy, &location.path).unwrap();
+        let conn = open(&location.path).unwrap();
+        // Already current, so reopening didn't snapshot it.
+        assert!(backup::list(&backup::dir_for(&location.path)).unwrap().is_empty());
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM exercises", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+        assert!(!legacy.exists());
+        assert_eq!(legacy_db_to_migrate(&location, &working_dir), None);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn snapshots_existing_database_before_migrating() {
+        let dir = test_dir("pre-migration");
+        let path = dir.join(DB_FILE_NAME);
+        Connection::open(&path).unwrap().execute_batch(include_str!("../tests/fixtures/legacy_v0.sql")).unwrap();
+
+        open(&path).unwrap();
+        let snapshots = backup::list(&backup::dir_for(&path)).unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(snapshots[0].reason, BackupReason::PreMigration);
+        let old = Connection::open(&snapshots[0].path).unwrap();
+        assert_eq!(migrations::current_version(&old).unwrap(), 0);
+
+        // A brand new database has nothing worth keeping.
+        open(&dir.join("fresh.db")).unwrap();
+        assert_eq!(backup::list(&backup::dir_for(&path)).unwrap().len(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,290 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Estimated one-rep max from a single set. Implementations return `None` when
+// the set is outside what the formula can sensibly estimate (e.g. the RPE chart
+// without an RPE, or Brzycki past 36 reps).
+pub trait OneRepMaxFormula {
+    fn name(&self) -> &'static str;
+    fn estimate(&self, weight_lbs: f64, reps: i64, rpe: Option<f64>) -> Option<f64>;
+}
+
+// Shared handling for the purely rep-based formulas: no reps means no estimate,
+// and a single is its own 1RM regardless of the curve's intercept.
+fn rep_based(weight_lbs: f64, reps: i64, multiplier: impl Fn(f64) -> f64) -> Option<f64> {
+    match reps {
+        r if r < 1 => None,
+        1 => Some(weight_lbs),
+        r => Some(weight_lbs * multiplier(r as f64)),
+    }
+}
+
+pub struct Epley;
+pub struct Brzycki;
+pub struct Lombardi;
+pub struct Mayhew;
+pub struct OConner;
+pub struct Wathan;
+// Reynolds/Tuchscherer (RTS) RPE chart: %1RM by reps and RPE.
+pub struct RpeChart;
+
+impl OneRepMaxFormula for Epley {
+    fn name(&self) -> &'static str {
+        "Epley"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        rep_based(weight_lbs, reps, |r| 1.0 + r / 30.0)
+    }
+}
+
+impl OneRepMaxFormula for Brzycki {
+    fn name(&self) -> &'static str {
+        "Brzycki"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        if reps >= 37 {
+            return None;
+        }
+        rep_based(weight_lbs, reps, |r| 36.0 / (37.0 - r))
+    }
+}
+
+impl OneRepMaxFormula for Lombardi {
+    fn name(&self) -> &'static str {
+        "Lombardi"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        rep_based(weight_lbs, reps, |r| r.powf(0.10))
+    }
+}
+
+impl OneRepMaxFormula for Mayhew {
+    fn name(&self) -> &'static str {
+        "Mayhew"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        rep_based(weight_lbs, reps, |r| 100.0 / (52.2 + 41.9 * (-0.055 * r).exp()))
+    }
+}
+
+impl OneRepMaxFormula for OConner {
+    fn name(&self) -> &'static str {
+        "O'Conner"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        rep_based(weight_lbs, reps, |r| 1.0 + 0.025 * r)
+    }
+}
+
+impl OneRepMaxFormula for Wathan {
+    fn name(&self) -> &'static str {
+        "Wathan"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, _rpe: Option<f64>) -> Option<f64> {
+        rep_based(weight_lbs, reps, |r| 100.0 / (48.8 + 53.8 * (-0.075 * r).exp()))
+    }
+}
+
+// The RTS chart is a single curve indexed by half-reps-in-reserve: each rep
+// adds two steps, each half RPE below 10 adds one. 12 reps @ 6.5 is the last cell.
+const RTS_PERCENTAGES: [f64; 30] = [
+    100.0, 97.8, 95.5, 93.9, 92.2, 90.7, 89.2, 87.8, 86.3, 85.0, 83.7, 82.4, 81.1, 79.9, 78.6,
+    77.4, 76.2, 75.1, 73.9, 72.3, 70.7, 69.4, 68.0, 66.7, 65.3, 64.0, 62.6, 61.3, 59.9, 58.6,
+];
+
+impl RpeChart {
+    pub const MIN_RPE: f64 = 6.5;
+    pub const MAX_REPS: i64 = 12;
+
+    // Fraction of 1RM for `reps` at `rpe`, interpolating between half-RPE steps.
+    pub fn percentage(reps: i64, rpe: f64) -> Option<f64> {
+        if !(1..=Self::MAX_REPS).contains(&reps) || !(Self::MIN_RPE..=10.0).contains(&rpe) {
+            return None;
+        }
+        let index = 2.0 * (reps - 1) as f64 + 2.0 * (10.0 - rpe);
+        let lower = index.floor() as usize;
+        let upper = (lower + 1).min(RTS_PERCENTAGES.len() - 1);
+        let t = index - lower as f64;
+        Some((RTS_PERCENTAGES[lower] * (1.0 - t) + RTS_PERCENTAGES[upper] * t) / 100.0)
+    }
+}
+
+impl OneRepMaxFormula for RpeChart {
+    fn name(&self) -> &'static str {
+        "RPE chart (RTS)"
+    }
+
+    fn estimate(&self, weight_lbs: f64, reps: i64, rpe: Option<f64>) -> Option<f64> {
+        Self::percentage(reps, rpe?).map(|pct| weight_lbs / pct)
+    }
+}
+
+// User-selectable formula. `Average` is the mean of every formula that can
+// estimate the set, so it includes the RPE chart only when an RPE was logged.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum E1rmFormula {
+    #[default]
+    Epley,
+    Brzycki,
+    Lombardi,
+    Mayhew,
+    OConner,
+    Wathan,
+    RpeChart,
+    Average,
+}
+
+const INDIVIDUAL_FORMULAS: [&dyn OneRepMaxFormula; 7] =
+    [&Epley, &Brzycki, &Lombardi, &Mayhew, &OConner, &Wathan, &RpeChart];
+
+impl E1rmFormula {
+    pub const ALL: [E1rmFormula; 8] = [
+        E1rmFormula::Epley,
+        E1rmFormula::Brzycki,
+        E1rmFormula::Lombardi,
+        E1rmFormula::Mayhew,
+        E1rmFormula::OConner,
+        E1rmFormula::Wathan,
+        E1rmFormula::RpeChart,
+        E1rmFormula::Average,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            E1rmFormula::Average => "Average of formulas",
+            other => other.formula().map_or("", |f| f.name()),
+        }
+    }
+
+    fn formula(&self) -> Option<&'static dyn OneRepMaxFormula> {
+        match self {
+            E1rmFormula::Epley => Some(&Epley),
+            E1rmFormula::Brzycki => Some(&Brzycki),
+            E1rmFormula::Lombardi => Some(&Lombardi),
+            E1rmFormula::Mayhew => Some(&Mayhew),
+            E1rmFormula::OConner => Some(&OConner),
+            E1rmFormula::Wathan => Some(&Wathan),
+            E1rmFormula::RpeChart => Some(&RpeChart),
+            E1rmFormula::Average => None,
+        }
+    }
+
+    pub fn estimate(&self, weight_lbs: f64, reps: i64, rpe: Option<f64>) -> Option<f64> {
+        match self.formula() {
+            Some(formula) => formula.estimate(weight_lbs, reps, rpe),
+            None => {
+                let estimates: Vec<f64> = INDIVIDUAL_FORMULAS
+                    .iter()
+                    .filter_map(|f| f.estimate(weight_lbs, reps, rpe))
+                    .collect();
+                if estimates.is_empty() {
+                    None
+                } else {
+                    Some(estimates.iter().sum::<f64>() / estimates.len() as f64)
+                }
+            }
+        }
+    }
+}
+
+impl Display for E1rmFormula {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for E1rmFormula {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        E1rmFormula::ALL
+            .into_iter()
+            .find(|formula| formula.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown e1rm formula: {}", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("expected an estimate");
+        assert!((actual - expected).abs() < 0.05, "expected {}, got {}", expected, actual);
+    }
+
+    // 225 lb x 5 and x 10 worked through each author's published equation.
+    #[test]
+    fn rep_based_formulas_match_reference_values() {
+        assert_close(Epley.estimate(225.0, 5, None), 262.5);
+        assert_close(Epley.estimate(225.0, 10, None), 300.0);
+        assert_close(Brzycki.estimate(225.0, 5, None), 253.1);
+        assert_close(Brzycki.estimate(225.0, 10, None), 300.0);
+        assert_close(Lombardi.estimate(225.0, 5, None), 264.3);
+        assert_close(Lombardi.estimate(225.0, 10, None), 283.3);
+        assert_close(Mayhew.estimate(225.0, 5, None), 267.8);
+        assert_close(Mayhew.estimate(225.0, 10, None), 294.6);
+        assert_close(OConner.estimate(225.0, 5, None), 253.1);
+        assert_close(OConner.estimate(225.0, 10, None), 281.25);
+        assert_close(Wathan.estimate(225.0, 5, None), 262.3);
+        assert_close(Wathan.estimate(225.0, 10, None), 303.2);
+    }
+
+    // Brzycki's %1RM table: 2 reps = 97.2%, 6 = 86.1%, 10 = 75.0%.
+    #[test]
+    fn brzycki_matches_percentage_table() {
+        for (reps, pct) in [(2, 97.2), (6, 86.1), (10, 75.0)] {
+            assert_close(Brzycki.estimate(pct, reps, None), 100.0);
+        }
+        assert_eq!(Brzycki.estimate(100.0, 37, None), None);
+    }
+
+    #[test]
+    fn singles_and_zero_reps() {
+        for formula in INDIVIDUAL_FORMULAS.iter().take(6) {
+            assert_eq!(formula.estimate(315.0, 1, None), Some(315.0), "{}", formula.name());
+            assert_eq!(formula.estimate(315.0, 0, None), None, "{}", formula.name());
+        }
+    }
+
+    // Cells from the RTS RPE chart.
+    #[test]
+    fn rpe_chart_matches_rts_table() {
+        assert_close(RpeChart::percentage(1, 10.0).map(|p| p * 100.0), 100.0);
+        assert_close(RpeChart::percentage(5, 8.0).map(|p| p * 100.0), 81.1);
+        assert_close(RpeChart::percentage(3, 9.0).map(|p| p * 100.0), 89.2);
+        assert_close(RpeChart::percentage(8, 7.5).map(|p| p * 100.0), 72.3);
+        assert_close(RpeChart::percentage(12, 6.5).map(|p| p * 100.0), 58.6);
+        // Between 8 and 8.5 at 5 reps (81.1% and 82.4%).
+        assert_close(RpeChart::percentage(5, 8.25).map(|p| p * 100.0), 81.75);
+
+        assert_close(RpeChart.estimate(250.0, 3, Some(9.0)), 280.3);
+        assert_eq!(RpeChart.estimate(250.0, 3, None), None);
+        assert_eq!(RpeChart.estimate(250.0, 13, Some(9.0)), None);
+        assert_eq!(RpeChart.estimate(250.0, 3, Some(6.0)), None);
+    }
+
+    #[test]
+    fn average_uses_available_formulas() {
+        let without_rpe = E1rmFormula::Average.estimate(225.0, 5, None).unwrap();
+        let expected = (262.5 + 253.125 + 264.289 + 267.774 + 253.125 + 262.311) / 6.0;
+        assert!((without_rpe - expected).abs() < 0.01);
+
+        let with_rpe = E1rmFormula::Average.estimate(225.0, 5, Some(8.0)).unwrap();
+        let expected = (expected * 6.0 + 225.0 / 0.811) / 7.0;
+        assert!((with_rpe - expected).abs() < 0.01);
+    }
+
+    #[test]
+    fn formula_round_trips_through_string() {
+        for formula in E1rmFormula::ALL {
+            assert_eq!(formula.to_string().parse::<E1rmFormula>(), Ok(formula));
+        }
+    }
+}
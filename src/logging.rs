@@ -43,7 +43,7 @@ pub fn init_logger(sender: Sender<String>) {
 
     let mut builder = Builder::new();
     builder
-        .filter(None, LevelFilter::Info) // Set the default log level
+        .filter(None, LevelFilter::Trace) // Let everything through; the Settings tab lowers it with log::set_max_level
         .format(|buf, record| {
             // Customize the log message format
             writeln!(
@@ -1,40 +1,30 @@
 use eframe::NativeOptions;
 use eframe::egui;
 use egui::{FontDefinitions, FontFamily, FontData};
-use rusqlite::Connection;
-use std::sync::{Arc, Mutex};
 use std::sync::mpsc;
 use log::error;
 
 
+mod cli;
+mod csv_io;
+mod db;
+mod backup;
+mod catalog;
 mod db_init;
+mod migrations;
 mod types;
 mod app_state;
 mod ui;
 mod logging;
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
-enum Tab {
-    #[default]
-    LogWeight,
-    LogExercise,
-    WeightProgress,
-    ExerciseProgress,
-}
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
-enum DietPhase {
-    #[default]
-    Bulk,
-    Cut,
-    Maintain,
-}
-
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
-enum ExerciseMetric {
-    #[default]
-    Weight,
-}
+mod smoothing;
+mod tdee;
+mod timer;
+mod e1rm;
+mod body_composition;
+mod dashboard;
+mod progression;
+mod units;
+mod settings;
 
 fn main() {
     let (sender, receiver) = mpsc::channel();
@@ -42,85 +32,58 @@ fn main() {
     // Set up env_logger to use a custom writer that sends messages to the 'sender'
     logging::init_logger(sender);
 
-    let options = NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size(egui::Vec2::new(600.0, 800.0)),
-        ..Default::default()
-    };
-    let database_path = "liftmetrics.db";
-    if !std::path::Path::new(database_path).exists() {
-        if let Err(e) = db_init::init(database_path) {
-            error!("failed to initialize database: {}", e);
-            return;
+    let args = match cli::parse(std::env::args_os().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, cli::USAGE);
+            std::process::exit(2);
         }
+    };
+    if args.show_help {
+        println!("{}", cli::USAGE);
+        return;
     }
-
-    let db_conn = match Connection::open(database_path) {
-        Ok(conn) => Arc::new(Mutex::new(conn)),
+    let location = match db_init::resolve_db_path(
+        args.db_path,
+        std::env::var_os(db_init::DB_PATH_ENV),
+        db_init::default_data_dir(),
+    ) {
+        Ok(location) => location,
         Err(e) => {
-            error!("failed to open database connection: {}", e);
+            error!("{}", e);
+            eprintln!("{}", e);
             return;
         }
     };
 
+    if let Some(command) = &args.command {
+        std::process::exit(run_headless(command, &location, &receiver));
+    }
+
     let mut app = app_state::MyApp {
-        db_conn: Some(db_conn.clone()),
-        recent_weight_logs: Vec::new(),
         // Pass the receiver to the app state
-        // TODO: Add receiver field to MyApp struct
         log_receiver: receiver,
         ..Default::default()
     };
 
-    if let Ok(conn) = db_conn.lock() {
-        match conn.query_row(
-            "SELECT id FROM diet_cycles WHERE actual_end_date IS NULL ORDER BY start_date DESC LIMIT 1",
-            [],
-            |row| row.get(0),
-        ) {
-            Ok(active_id) => {
-                app.active_diet_cycle_id = Some(active_id);
-            }
-            Err(rusqlite::Error::QueryReturnedNoRows) => {
-                // No active cycle, which is fine
-            }
-            Err(e) => error!("failed to load active diet cycle: {}", e),
-        }
-
-        let mut stmt = match conn.prepare("SELECT id, name FROM exercises ORDER BY name COLLATE NOCASE") {
-            Ok(s) => s,
-            Err(e) => {
-                error!("failed to prepare statement for loading exercises: {}", e);
-                return;
-            }
-        };
-        
-        let exercise_iter_map = stmt.query_map([], |row| {
-            Ok((row.get(0)?, row.get::<usize, String>(1)?))
-        });
-
-        match exercise_iter_map {
-            Ok(exercise_iter) => {
-                for exercise_result in exercise_iter {
-                    match exercise_result {
-                        Ok((id, name_str)) => {
-                            app.all_exercises_for_dropdown.push((id, name_str.clone()));
-                        }
-                        Err(e) => error!("failed to process exercise row: {}", e),
-                    }
-                }
-            }
-            Err(e) => {
-                 error!("failed to query exercises: {}", e);
-            }
-        }
-    } else {
-        error!("failed to lock db connection for initial load.");
-    }
-    if app.active_diet_cycle_id.is_some() {
-        app.fetch_recent_weight_logs();
+    let legacy = std::env::current_dir().ok().and_then(|cwd| db_init::legacy_db_to_migrate(&location, &cwd));
+    if let Some(legacy) = legacy {
+        // Opening waits until the user decides what to do with the old file.
+        app.pending_legacy_db = Some(types::LegacyDbMigration { legacy, target: location.path });
+    } else if let Err(e) = app.open_database(&location.path) {
+        error!("failed to open database {}: {}", location.path.display(), e);
+        eprintln!("failed to open database {}: {}", location.path.display(), e);
+        return;
     }
 
+    // Saved settings are only available once the database is open.
+    let options = NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_inner_size(egui::Vec2::new(app.settings.window_width, app.settings.window_height)),
+        ..Default::default()
+    };
+    let theme = app.settings.theme.preference();
+
     let mut fonts = FontDefinitions::default();
 
     fonts.font_data.insert(
@@ -135,6 +98,35 @@ fn main() {
 
     let _ = eframe::run_native("LiftMetrics", options, Box::new(|cc| {
         cc.egui_ctx.set_fonts(fonts);
+        cc.egui_ctx.set_theme(theme);
         Ok(Box::new(app))
     }));
 }
+
+// Runs a command-line subcommand without opening a window. Returns the exit code.
+fn run_headless(command: &cli::Command, location: &db_init::DbLocation, log: &mpsc::Receiver<String>) -> i32 {
+    // Creating a fresh database here would hide the old one from the GUI's move prompt.
+    let legacy = std::env::current_dir().ok().and_then(|cwd| db_init::legacy_db_to_migrate(location, &cwd));
+    if let Some(legacy) = legacy {
+        eprintln!("found a database from an older version at {}; open the app once to move it, or pass --db.", legacy.display());
+        return 1;
+    }
+    let result = db_init::open(&location.path).map_err(|e| e.to_string()).and_then(|mut conn| {
+        if let Err(e) = catalog::seed_if_new(&mut conn) {
+            error!("failed to seed the exercise catalog: {}", e);
+        }
+        let today = chrono::Local::now().date_naive();
+        cli::run(command, &mut conn, &location.path, today, &mut std::io::stdout())
+    });
+    // There's no console to show warnings in, e.g. a failed pre-migration backup.
+    for message in log.try_iter().filter(|m| m.contains("[WARN]") || m.contains("[ERROR]")) {
+        eprint!("{}", message);
+    }
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,14 @@
+CREATE TABLE personal_records (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    exercise_id INTEGER NOT NULL,
+    record_type TEXT NOT NULL,
+    rep_count INTEGER,
+    value REAL NOT NULL,
+    previous_value REAL,
+    exercise_set_id INTEGER,
+    workout_session_id INTEGER NOT NULL,
+    achieved_on TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_personal_records_lookup ON personal_records (exercise_id, record_type, rep_count);
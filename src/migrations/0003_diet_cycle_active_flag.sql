@@ -0,0 +1,7 @@
+ALTER TABLE diet_cycles ADD COLUMN is_active INTEGER NOT NULL DEFAULT 0;
+
+-- Previously the active cycle was the most recent one without an end date.
+UPDATE diet_cycles SET is_active = 1
+WHERE id = (SELECT id FROM diet_cycles WHERE actual_end_date IS NULL ORDER BY start_date DESC LIMIT 1);
+
+CREATE UNIQUE INDEX idx_diet_cycles_single_active ON diet_cycles (is_active) WHERE is_active = 1;
@@ -0,0 +1,6 @@
+-- User preferences as key/value text. Unknown keys are ignored on load.
+CREATE TABLE settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,11 @@
+-- One row per measurement so each can be taken on its own schedule. Lengths
+-- are in inches; `BodyFat` rows are a percentage.
+CREATE TABLE body_measurements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    measured_on TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    value REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_body_measurements_kind ON body_measurements (kind, measured_on);
@@ -0,0 +1,12 @@
+-- Daily intake, one row per day. Macros are grams and optional.
+CREATE TABLE nutrition_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    log_date TEXT NOT NULL UNIQUE,
+    calories INTEGER NOT NULL,
+    protein_g REAL,
+    carbs_g REAL,
+    fat_g REAL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+ALTER TABLE diet_cycles ADD COLUMN calorie_target INTEGER;
@@ -0,0 +1,15 @@
+-- Exercise library attributes. Archived exercises keep their history but are
+-- left out of pickers.
+ALTER TABLE exercises ADD COLUMN category TEXT;
+ALTER TABLE exercises ADD COLUMN equipment TEXT;
+ALTER TABLE exercises ADD COLUMN movement_pattern TEXT;
+ALTER TABLE exercises ADD COLUMN is_archived INTEGER NOT NULL DEFAULT 0;
+
+-- Muscles an exercise works; `role` is 'Primary' or 'Secondary'. Foreign keys
+-- aren't enforced, so code that removes an exercise deletes its rows here too.
+CREATE TABLE exercise_muscles (
+    exercise_id INTEGER NOT NULL,
+    muscle TEXT NOT NULL,
+    role TEXT NOT NULL CHECK (role IN ('Primary', 'Secondary')),
+    PRIMARY KEY (exercise_id, muscle)
+);
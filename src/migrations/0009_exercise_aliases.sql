@@ -0,0 +1,11 @@
+-- Alternative spellings that resolve to one exercise wherever a name is
+-- looked up, e.g. "BB Bench" for "Bench Press". As with `exercise_muscles`,
+-- removing an exercise means moving or deleting its aliases by hand.
+CREATE TABLE exercise_aliases (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    exercise_id INTEGER NOT NULL,
+    alias TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_exercise_aliases_exercise ON exercise_aliases (exercise_id);
@@ -0,0 +1,19 @@
+-- Exercises seeded from (or matched to) the bundled catalog carry its stable id.
+ALTER TABLE exercises ADD COLUMN catalog_id TEXT;
+
+CREATE UNIQUE INDEX idx_exercises_catalog_id ON exercises (catalog_id) WHERE catalog_id IS NOT NULL;
+
+-- The catalog values last written for each entry. A field whose current value
+-- still matches is unedited and follows catalog updates; anything else is the
+-- user's and is kept. Muscles are stored as sorted, semicolon-separated lists.
+CREATE TABLE exercise_catalog_seeds (
+    catalog_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    category TEXT,
+    equipment TEXT,
+    movement_pattern TEXT,
+    default_metric_to_track TEXT,
+    primary_muscles TEXT NOT NULL DEFAULT '',
+    secondary_muscles TEXT NOT NULL DEFAULT '',
+    seeded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
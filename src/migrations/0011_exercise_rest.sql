@@ -0,0 +1,2 @@
+-- Rest between sets for the exercise; NULL falls back to the app default.
+ALTER TABLE exercises ADD COLUMN rest_seconds INTEGER;
@@ -0,0 +1,232 @@
+use rusqlite::Connection;
+use std::fmt;
+use log::info;
+
+// A single schema step. `version` is written to PRAGMA user_version once the
+// step's SQL has been applied, so versions must be strictly increasing.
+pub struct Migration {
+    pub version: i64,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+// Ordered list of every schema change. Append new entries at the end and never
+// edit one that has already shipped.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "initial schema",
+        sql: include_str!("0001_initial_schema.sql"),
+    },
+    Migration {
+        version: 2,
+        description: "personal records",
+        sql: include_str!("0002_personal_records.sql"),
+    },
+    Migration {
+        version: 3,
+        description: "explicit active diet cycle",
+        sql: include_str!("0003_diet_cycle_active_flag.sql"),
+    },
+    Migration {
+        version: 4,
+        description: "settings",
+        sql: include_str!("0004_settings.sql"),
+    },
+    Migration {
+        version: 5,
+        description: "workout templates",
+        sql: include_str!("0005_workout_templates.sql"),
+    },
+    Migration {
+        version: 6,
+        description: "body measurements",
+        sql: include_str!("0006_body_measurements.sql"),
+    },
+    Migration {
+        version: 7,
+        description: "nutrition logs and calorie targets",
+        sql: include_str!("0007_nutrition.sql"),
+    },
+    Migration {
+        version: 8,
+        description: "exercise library attributes",
+        sql: include_str!("0008_exercise_library.sql"),
+    },
+    Migration {
+        version: 9,
+        description: "exercise aliases",
+        sql: include_str!("0009_exercise_aliases.sql"),
+    },
+    Migration {
+        version: 10,
+        description: "exercise catalog",
+        sql: include_str!("0010_exercise_catalog.sql"),
+    },
+    Migration {
+        version: 11,
+        description: "exercise rest times",
+        sql: include_str!("0011_exercise_rest.sql"),
+    },
+];
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Sqlite(rusqlite::Error),
+    // The database was written by a newer build that knows about more migrations.
+    DatabaseTooNew { found: i64, supported: i64 },
+    Failed { version: i64, description: &'static str, source: rusqlite::Error },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Sqlite(e) => write!(f, "sqlite error: {}", e),
+            MigrationError::DatabaseTooNew { found, supported } => write!(
+                f,
+                "database schema version {} is newer than this build supports ({}); please update LiftMetrics",
+                found, supported
+            ),
+            MigrationError::Failed { version, description, source } => {
+                write!(f, "migration {} ({}) failed: {}", version, description, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<rusqlite::Error> for MigrationError {
+    fn from(e: rusqlite::Error) -> Self {
+        MigrationError::Sqlite(e)
+    }
+}
+
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map_or(0, |m| m.version)
+}
+
+pub fn current_version(conn: &Connection) -> rusqlite::Result<i64> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+// Brings the database up to `latest_version()`, applying each pending migration
+// in its own transaction. Returns the number of migrations applied.
+pub fn run(conn: &mut Connection) -> Result<usize, MigrationError> {
+    run_to(conn, MIGRATIONS)
+}
+
+fn run_to(conn: &mut Connection, migrations: &[Migration]) -> Result<usize, MigrationError> {
+    let supported = migrations.last().map_or(0, |m| m.version);
+    let found = current_version(conn)?;
+    if found > supported {
+        return Err(MigrationError::DatabaseTooNew { found, supported });
+    }
+
+    let mut applied = 0;
+    for migration in migrations.iter().filter(|m| m.version > found) {
+        let tx = conn.transaction()?;
+        let result = tx
+            .execute_batch(migration.sql)
+            .and_then(|_| tx.pragma_update(None, "user_version", migration.version));
+        if let Err(source) = result {
+            // Dropping the transaction rolls back the partial migration.
+            return Err(MigrationError::Failed {
+                version: migration.version,
+                description: migration.description,
+                source,
+            });
+        }
+        tx.commit()?;
+        info!("applied migration {} ({})", migration.version, migration.description);
+        applied += 1;
+    }
+    Ok(applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_names(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect()
+    }
+
+    #[test]
+    fn versions_are_strictly_increasing() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version);
+        }
+        assert_eq!(MIGRATIONS[0].version, 1);
+    }
+
+    #[test]
+    fn migrates_empty_database_to_latest() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        let applied = run(&mut conn).unwrap();
+        assert_eq!(applied, MIGRATIONS.len());
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        let tables = table_names(&conn);
+        for expected in ["body_measurements", "diet_cycles", "exercise_aliases", "exercise_catalog_seeds", "exercise_muscles", "exercise_sets", "exercises", "nutrition_logs", "personal_records", "settings", "template_exercises", "weight_logs", "workout_sessions", "workout_templates"] {
+            assert!(tables.iter().any(|t| t == expected), "missing table {}", expected);
+        }
+    }
+
+    #[test]
+    fn migrates_legacy_fixture_and_keeps_rows() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../../tests/fixtures/legacy_v0.sql")).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), 0);
+
+        run(&mut conn).unwrap();
+        assert_eq!(current_version(&conn).unwrap(), latest_version());
+
+        let weight_rows: i64 = conn.query_row("SELECT COUNT(*) FROM weight_logs", [], |r| r.get(0)).unwrap();
+        let set_rows: i64 = conn.query_row("SELECT COUNT(*) FROM exercise_sets", [], |r| r.get(0)).unwrap();
+        assert_eq!(weight_rows, 3);
+        assert_eq!(set_rows, 4);
+
+        // The fixture's only cycle is open, so it becomes the active one.
+        let active: i64 = conn.query_row("SELECT id FROM diet_cycles WHERE is_active = 1", [], |r| r.get(0)).unwrap();
+        assert_eq!(active, 1);
+    }
+
+    #[test]
+    fn rerunning_is_a_no_op() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        run(&mut conn).unwrap();
+        assert_eq!(run(&mut conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn refuses_newer_database() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", latest_version() + 1).unwrap();
+        match run(&mut conn) {
+            Err(MigrationError::DatabaseTooNew { found, supported }) => {
+                assert_eq!(found, latest_version() + 1);
+                assert_eq!(supported, latest_version());
+            }
+            other => panic!("expected DatabaseTooNew, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn failed_migration_rolls_back() {
+        let broken = [
+            Migration { version: 1, description: "ok", sql: "CREATE TABLE a (id INTEGER);" },
+            Migration { version: 2, description: "broken", sql: "CREATE TABLE b (id INTEGER); SELECT * FROM missing;" },
+        ];
+        let mut conn = Connection::open_in_memory().unwrap();
+        let err = run_to(&mut conn, &broken).unwrap_err();
+        assert!(matches!(err, MigrationError::Failed { version: 2, .. }));
+        assert_eq!(current_version(&conn).unwrap(), 1);
+        assert_eq!(table_names(&conn), vec!["a".to_string()]);
+    }
+}
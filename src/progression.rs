@@ -0,0 +1,332 @@
+use crate::db::ExerciseSet;
+use crate::e1rm::{E1rmFormula, RpeChart};
+use crate::types::SetTarget;
+use crate::units::WeightUnit;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Sessions in a row that missed the rep target before a deload is suggested.
+pub const DELOAD_AFTER_FAILURES: usize = 3;
+pub const DELOAD_FACTOR: f64 = 0.9;
+// Used when the RPE scheme has no target RPE from a template.
+pub const DEFAULT_TARGET_RPE: f64 = 8.0;
+// Reps and fraction of e1RM for each session of the percentage wave.
+const WAVE: [(i64, f64); 3] = [(5, 0.75), (3, 0.85), (1, 0.90)];
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ProgressionScheme {
+    // Add the increment every time all working sets hit the target reps.
+    #[default]
+    Linear,
+    // Add reps up to the top of the range, then add weight and drop back.
+    DoubleProgression,
+    // Pick the weight that should land on the target RPE from last time's e1RM.
+    RpeAutoregulation,
+    // Cycle through rising percentages of e1RM.
+    E1rmWave,
+}
+
+impl ProgressionScheme {
+    pub const ALL: [ProgressionScheme; 4] = [
+        ProgressionScheme::Linear,
+        ProgressionScheme::DoubleProgression,
+        ProgressionScheme::RpeAutoregulation,
+        ProgressionScheme::E1rmWave,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProgressionScheme::Linear => "Linear",
+            ProgressionScheme::DoubleProgression => "Double progression",
+            ProgressionScheme::RpeAutoregulation => "RPE autoregulation",
+            ProgressionScheme::E1rmWave => "e1RM percentage wave",
+        }
+    }
+
+    // Rep range used when the exercise didn't come from a template.
+    fn default_target(&self) -> SetTarget {
+        match self {
+            ProgressionScheme::DoubleProgression => SetTarget { sets: 3, rep_min: 8, rep_max: 12, rpe: None },
+            _ => SetTarget { sets: 3, rep_min: 5, rep_max: 5, rpe: None },
+        }
+    }
+}
+
+// Stored in the settings table.
+impl Display for ProgressionScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for ProgressionScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ProgressionScheme::ALL
+            .into_iter()
+            .find(|scheme| scheme.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown progression scheme: {}", s))
+    }
+}
+
+// What to lift today and why.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub weight_lbs: f64,
+    pub reps: i64,
+    pub deload: bool,
+    pub explanation: String,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ProgressionConfig {
+    pub scheme: ProgressionScheme,
+    pub increment_lbs: f64,
+    pub formula: E1rmFormula,
+    // Only used to word the explanation.
+    pub unit: WeightUnit,
+}
+
+// One past session boiled down to its heaviest sets.
+struct SessionSummary<'a> {
+    top_weight_lbs: f64,
+    working_sets: Vec<&'a ExerciseSet>,
+}
+
+impl SessionSummary<'_> {
+    fn min_reps(&self) -> i64 {
+        self.working_sets.iter().map(|s| s.reps).min().unwrap_or(0)
+    }
+}
+
+fn summarize(history: &[(NaiveDate, ExerciseSet)]) -> Vec<SessionSummary<'_>> {
+    let mut by_session: BTreeMap<(NaiveDate, i64), Vec<&ExerciseSet>> = BTreeMap::new();
+    for (date, set) in history {
+        by_session.entry((*date, set.workout_session_id)).or_default().push(set);
+    }
+    by_session
+        .into_values()
+        .map(|sets| {
+            let top = sets.iter().map(|s| s.weight_lbs).fold(0.0, f64::max);
+            // Working sets are the ones at the session's top weight.
+            let working_sets = sets.into_iter().filter(|s| s.weight_lbs >= top - 0.01).collect();
+            SessionSummary { top_weight_lbs: top, working_sets }
+        })
+        .collect()
+}
+
+fn round_to(weight_lbs: f64, increment_lbs: f64) -> f64 {
+    if increment_lbs > 0.0 { (weight_lbs / increment_lbs).round() * increment_lbs } else { weight_lbs }
+}
+
+// Proposes today's working weight and reps from an exercise's history (oldest
+// first, as returned by `workouts::exercise_history`). `None` when there's no
+// history to go on. Repeated failures trigger a deload whatever the scheme.
+pub fn suggest(history: &[(NaiveDate, ExerciseSet)], target: Option<SetTarget>, config: ProgressionConfig) -> Option<Suggestion> {
+    let sessions = summarize(history);
+    let last = sessions.last()?;
+    let target = target.unwrap_or_else(|| config.scheme.default_target());
+    let fmt = |lbs: f64| config.unit.format(lbs);
+
+    // Consecutive recent sessions that missed the bottom of the rep range
+    // without the weight having been dropped in between.
+    let failures = sessions
+        .iter()
+        .rev()
+        .take_while(|s| s.min_reps() < target.rep_min && s.top_weight_lbs >= last.top_weight_lbs - 0.01)
+        .count();
+    if failures >= DELOAD_AFTER_FAILURES {
+        let weight = round_to(last.top_weight_lbs * DELOAD_FACTOR, config.increment_lbs);
+        return Some(Suggestion {
+            weight_lbs: weight,
+            reps: target.rep_min,
+            deload: true,
+            explanation: format!(
+                "deload: missed {} reps at {} for {} sessions in a row, so drop {:.0}% and build back up.",
+                target.reps_label(),
+                fmt(last.top_weight_lbs),
+                failures,
+                (1.0 - DELOAD_FACTOR) * 100.0
+            ),
+        });
+    }
+
+    let linear = |prefix: &str| {
+        let hit = last.min_reps() >= target.rep_min;
+        let (weight, why) = if hit {
+            let next = last.top_weight_lbs + config.increment_lbs;
+            (next, format!("hit {} reps at {} last time, so add {}.", target.rep_min, fmt(last.top_weight_lbs), fmt(config.increment_lbs)))
+        } else {
+            (
+                last.top_weight_lbs,
+                format!("got {} of {} reps at {} last time, so repeat it.", last.min_reps(), target.rep_min, fmt(last.top_weight_lbs)),
+            )
+        };
+        Suggestion { weight_lbs: weight, reps: target.rep_min, deload: false, explanation: format!("{}{}", prefix, why) }
+    };
+
+    let suggestion = match config.scheme {
+        ProgressionScheme::Linear => linear(""),
+        ProgressionScheme::DoubleProgression => {
+            let reps = last.min_reps();
+            if reps >= target.rep_max {
+                Suggestion {
+                    weight_lbs: last.top_weight_lbs + config.increment_lbs,
+                    reps: target.rep_min,
+                    deload: false,
+                    explanation: format!(
+                        "every set reached {} reps at {}, so add {} and start again at {}.",
+                        target.rep_max,
+                        fmt(last.top_weight_lbs),
+                        fmt(config.increment_lbs),
+                        target.rep_min
+                    ),
+                }
+            } else {
+                let next = (reps + 1).clamp(target.rep_min, target.rep_max);
+                Suggestion {
+                    weight_lbs: last.top_weight_lbs,
+                    reps: next,
+                    deload: false,
+                    explanation: format!(
+                        "stay at {} and aim for {} reps; add weight once every set reaches {}.",
+                        fmt(last.top_weight_lbs),
+                        next,
+                        target.rep_max
+                    ),
+                }
+            }
+        }
+        ProgressionScheme::RpeAutoregulation => {
+            let rated = last.working_sets.iter().find_map(|s| {
+                let rpe = s.rpe?;
+                Some((s, s.weight_lbs / RpeChart::percentage(s.reps, rpe)?))
+            });
+            let target_rpe = target.rpe.unwrap_or(DEFAULT_TARGET_RPE);
+            match (rated, RpeChart::percentage(target.rep_min, target_rpe)) {
+                (Some((set, e1rm)), Some(pct)) => Suggestion {
+                    weight_lbs: round_to(e1rm * pct, config.increment_lbs),
+                    reps: target.rep_min,
+                    deload: false,
+                    explanation: format!(
+                        "{} x {} @ RPE {} last time puts your e1RM near {}; {} reps @ RPE {} is about {:.0}% of that.",
+                        fmt(set.weight_lbs),
+                        set.reps,
+                        set.rpe.unwrap_or_default(),
+                        fmt(e1rm),
+                        target.rep_min,
+                        target_rpe,
+                        pct * 100.0
+                    ),
+                },
+                _ => linear("no usable RPE logged last time; "),
+            }
+        }
+        ProgressionScheme::E1rmWave => {
+            let e1rm = last
+                .working_sets
+                .iter()
+                .filter_map(|s| config.formula.estimate(s.weight_lbs, s.reps, s.rpe))
+                .reduce(f64::max)?;
+            let step = sessions.len() % WAVE.len();
+            let (reps, pct) = WAVE[step];
+            Suggestion {
+                weight_lbs: round_to(e1rm * pct, config.increment_lbs),
+                reps,
+                deload: false,
+                explanation: format!(
+                    "wave session {} of {}: {} reps at {:.0}% of your {} e1RM ({}).",
+                    step + 1,
+                    WAVE.len(),
+                    reps,
+                    pct * 100.0,
+                    fmt(e1rm),
+                    config.formula.label()
+                ),
+            }
+        }
+    };
+    Some(suggestion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One session per entry of `reps`, each set at `weight`.
+    fn history(sessions: &[(f64, &[i64])]) -> Vec<(NaiveDate, ExerciseSet)> {
+        let mut rows = Vec::new();
+        for (idx, (weight, reps)) in sessions.iter().enumerate() {
+            let date = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap() + chrono::Duration::days(idx as i64 * 2);
+            for (order, r) in reps.iter().enumerate() {
+                rows.push((
+                    date,
+                    ExerciseSet {
+                        id: 0,
+                        workout_session_id: idx as i64,
+                        exercise_id: 1,
+                        set_order: order as i64 + 1,
+                        reps: *r,
+                        weight_lbs: *weight,
+                        rpe: None,
+                        notes: None,
+                    },
+                ));
+            }
+        }
+        rows
+    }
+
+    fn config(scheme: ProgressionScheme) -> ProgressionConfig {
+        ProgressionConfig { scheme, increment_lbs: 5.0, formula: E1rmFormula::Epley, unit: WeightUnit::Lbs }
+    }
+
+    #[test]
+    fn linear_adds_weight_or_repeats() {
+        assert!(suggest(&[], None, config(ProgressionScheme::Linear)).is_none());
+        let hit = suggest(&history(&[(225.0, &[5, 5, 5])]), None, config(ProgressionScheme::Linear)).unwrap();
+        assert_eq!((hit.weight_lbs, hit.reps, hit.deload), (230.0, 5, false));
+        let missed = suggest(&history(&[(225.0, &[5, 5, 4])]), None, config(ProgressionScheme::Linear)).unwrap();
+        assert_eq!(missed.weight_lbs, 225.0);
+        assert!(missed.explanation.contains("repeat"));
+    }
+
+    #[test]
+    fn double_progression_climbs_reps_then_weight() {
+        let target = Some(SetTarget { sets: 3, rep_min: 8, rep_max: 10, rpe: None });
+        let climbing = suggest(&history(&[(100.0, &[9, 8, 8])]), target, config(ProgressionScheme::DoubleProgression)).unwrap();
+        assert_eq!((climbing.weight_lbs, climbing.reps), (100.0, 9));
+        let topped = suggest(&history(&[(100.0, &[10, 10, 10])]), target, config(ProgressionScheme::DoubleProgression)).unwrap();
+        assert_eq!((topped.weight_lbs, topped.reps), (105.0, 8));
+    }
+
+    #[test]
+    fn repeated_failures_trigger_deload() {
+        let stuck = history(&[(200.0, &[5, 5, 5]), (225.0, &[5, 4, 3]), (225.0, &[4, 4, 3]), (225.0, &[5, 3, 3])]);
+        let deload = suggest(&stuck, None, config(ProgressionScheme::Linear)).unwrap();
+        assert!(deload.deload);
+        assert_eq!(deload.weight_lbs, 205.0);
+        // Two misses aren't enough.
+        assert!(!suggest(&stuck[..9], None, config(ProgressionScheme::Linear)).unwrap().deload);
+    }
+
+    #[test]
+    fn rpe_and_wave_use_e1rm() {
+        let mut rated = history(&[(300.0, &[1])]);
+        rated[0].1.rpe = Some(10.0);
+        let target = Some(SetTarget { sets: 1, rep_min: 1, rep_max: 1, rpe: Some(8.0) });
+        let rpe = suggest(&rated, target, config(ProgressionScheme::RpeAutoregulation)).unwrap();
+        // 1 @ 8 is 92.2% on the chart.
+        assert_eq!(rpe.weight_lbs, 275.0);
+
+        let unrated = suggest(&history(&[(300.0, &[5])]), None, config(ProgressionScheme::RpeAutoregulation)).unwrap();
+        assert!(unrated.explanation.starts_with("no usable RPE"));
+
+        // Epley: 300 x 1 is a 300 e1RM; one session done, so the wave is on its second step.
+        let wave = suggest(&history(&[(300.0, &[1])]), None, config(ProgressionScheme::E1rmWave)).unwrap();
+        assert_eq!((wave.weight_lbs, wave.reps), (255.0, 3));
+    }
+}
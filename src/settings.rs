@@ -0,0 +1,334 @@
+use crate::body_composition::Sex;
+use crate::dashboard::DashboardLayout;
+use crate::e1rm::E1rmFormula;
+use crate::progression::ProgressionScheme;
+use crate::smoothing::{self, SmoothingMethod};
+use crate::timer;
+use crate::units::{LengthUnit, WeightUnit};
+use chrono::NaiveDate;
+use eframe::egui;
+use log::{error, LevelFilter};
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum Theme {
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Dark, Theme::Light];
+
+    pub fn preference(&self) -> egui::ThemePreference {
+        match self {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Dark => egui::ThemePreference::Dark,
+            Theme::Light => egui::ThemePreference::Light,
+        }
+    }
+}
+
+impl Display for Theme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown theme: {}", s))
+    }
+}
+
+// How dates are shown. Text fields and storage always use yyyy-mm-dd.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum DateFormat {
+    #[default]
+    Iso,
+    DayMonthYear,
+    MonthDayYear,
+    Long,
+}
+
+impl DateFormat {
+    pub const ALL: [DateFormat; 4] =
+        [DateFormat::Iso, DateFormat::DayMonthYear, DateFormat::MonthDayYear, DateFormat::Long];
+
+    fn pattern(&self) -> &'static str {
+        match self {
+            DateFormat::Iso => "%Y-%m-%d",
+            DateFormat::DayMonthYear => "%d/%m/%Y",
+            DateFormat::MonthDayYear => "%m/%d/%Y",
+            DateFormat::Long => "%-d %b %Y",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DateFormat::Iso => "2025-03-31",
+            DateFormat::DayMonthYear => "31/03/2025",
+            DateFormat::MonthDayYear => "03/31/2025",
+            DateFormat::Long => "31 Mar 2025",
+        }
+    }
+
+    pub fn format(&self, date: NaiveDate) -> String {
+        date.format(self.pattern()).to_string()
+    }
+
+    // Reformats a stored yyyy-mm-dd string, leaving anything unparseable as is.
+    pub fn format_stored(&self, stored: &str) -> String {
+        match NaiveDate::parse_from_str(stored, "%Y-%m-%d") {
+            Ok(date) => self.format(date),
+            Err(_) => stored.to_string(),
+        }
+    }
+}
+
+impl Display for DateFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DateFormat::ALL
+            .into_iter()
+            .find(|format| format.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown date format: {}", s))
+    }
+}
+
+pub const MIN_CONSOLE_HEIGHT: f32 = 60.0;
+pub const MAX_CONSOLE_HEIGHT: f32 = 600.0;
+pub const MAX_BACKUP_KEEP: usize = 100;
+
+// Everything the user can configure. Stored one key per field in the
+// `settings` table; see `db::settings`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Settings {
+    pub lift_unit: WeightUnit,
+    pub bodyweight_unit: WeightUnit,
+    pub length_unit: LengthUnit,
+    // Profile for the Navy body fat estimate; a height of zero means unset.
+    pub sex: Sex,
+    pub height_in: f64,
+    pub theme: Theme,
+    pub date_format: DateFormat,
+    pub smoothing: SmoothingMethod,
+    pub smoothing_window: usize,
+    pub e1rm_formula: E1rmFormula,
+    pub progression_scheme: ProgressionScheme,
+    // Smallest weight jump the suggestions make, and what they round to.
+    pub progression_increment_lbs: f64,
+    pub log_level: LevelFilter,
+    pub console_height: f32,
+    // Snapshots of each kind kept in the backups folder.
+    pub backup_keep: usize,
+    // Rest for exercises without their own; see `exercises.rest_seconds`.
+    pub default_rest_secs: u32,
+    // Start the rest timer whenever a set is added to the workout being logged.
+    pub auto_start_rest: bool,
+    // Ring the terminal bell when a timer phase ends.
+    pub timer_bell: bool,
+    pub dashboard_layout: DashboardLayout,
+    pub window_width: f32,
+    pub window_height: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            lift_unit: WeightUnit::default(),
+            bodyweight_unit: WeightUnit::default(),
+            length_unit: LengthUnit::default(),
+            sex: Sex::default(),
+            height_in: 0.0,
+            theme: Theme::default(),
+            date_format: DateFormat::default(),
+            smoothing: SmoothingMethod::default(),
+            smoothing_window: smoothing::DEFAULT_WINDOW_DAYS,
+            e1rm_formula: E1rmFormula::default(),
+            progression_scheme: ProgressionScheme::default(),
+            progression_increment_lbs: 5.0,
+            log_level: LevelFilter::Info,
+            console_height: 200.0,
+            backup_keep: 10,
+            default_rest_secs: timer::DEFAULT_REST_SECS,
+            auto_start_rest: true,
+            timer_bell: false,
+            dashboard_layout: DashboardLayout::default(),
+            window_width: 600.0,
+            window_height: 800.0,
+        }
+    }
+}
+
+fn parse_into<T: FromStr>(key: &str, value: &str, target: &mut T)
+where
+    T::Err: Display,
+{
+    match value.parse() {
+        Ok(parsed) => *target = parsed,
+        Err(e) => error!("ignoring setting {} = {:?}: {}", key, value, e),
+    }
+}
+
+impl Settings {
+    pub fn to_pairs(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("lift_unit", self.lift_unit.to_string()),
+            ("bodyweight_unit", self.bodyweight_unit.to_string()),
+            ("length_unit", self.length_unit.to_string()),
+            ("sex", self.sex.to_string()),
+            ("height_in", self.height_in.to_string()),
+            ("theme", self.theme.to_string()),
+            ("date_format", self.date_format.to_string()),
+            ("smoothing", self.smoothing.to_string()),
+            ("smoothing_window", self.smoothing_window.to_string()),
+            ("e1rm_formula", self.e1rm_formula.to_string()),
+            ("progression_scheme", self.progression_scheme.to_string()),
+            ("progression_increment_lbs", self.progression_increment_lbs.to_string()),
+            ("log_level", self.log_level.to_string()),
+            ("console_height", self.console_height.to_string()),
+            ("backup_keep", self.backup_keep.to_string()),
+            ("default_rest_secs", self.default_rest_secs.to_string()),
+            ("auto_start_rest", self.auto_start_rest.to_string()),
+            ("timer_bell", self.timer_bell.to_string()),
+            ("dashboard_layout", self.dashboard_layout.to_string()),
+            ("window_width", self.window_width.to_string()),
+            ("window_height", self.window_height.to_string()),
+        ]
+    }
+
+    // Starts from the defaults and applies every recognised pair. Bad values
+    // are logged and skipped so one broken row can't lose the rest.
+    pub fn from_pairs<'a>(pairs: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut settings = Settings::default();
+        for (key, value) in pairs {
+            match key {
+                "lift_unit" => parse_into(key, value, &mut settings.lift_unit),
+                "bodyweight_unit" => parse_into(key, value, &mut settings.bodyweight_unit),
+                "length_unit" => parse_into(key, value, &mut settings.length_unit),
+                "sex" => parse_into(key, value, &mut settings.sex),
+                "height_in" => parse_into(key, value, &mut settings.height_in),
+                "theme" => parse_into(key, value, &mut settings.theme),
+                "date_format" => parse_into(key, value, &mut settings.date_format),
+                "smoothing" => parse_into(key, value, &mut settings.smoothing),
+                "smoothing_window" => parse_into(key, value, &mut settings.smoothing_window),
+                "e1rm_formula" => parse_into(key, value, &mut settings.e1rm_formula),
+                "progression_scheme" => parse_into(key, value, &mut settings.progression_scheme),
+                "progression_increment_lbs" => parse_into(key, value, &mut settings.progression_increment_lbs),
+                "log_level" => parse_into(key, value, &mut settings.log_level),
+                "console_height" => parse_into(key, value, &mut settings.console_height),
+                "backup_keep" => parse_into(key, value, &mut settings.backup_keep),
+                "default_rest_secs" => parse_into(key, value, &mut settings.default_rest_secs),
+                "auto_start_rest" => parse_into(key, value, &mut settings.auto_start_rest),
+                "timer_bell" => parse_into(key, value, &mut settings.timer_bell),
+                "dashboard_layout" => parse_into(key, value, &mut settings.dashboard_layout),
+                "window_width" => parse_into(key, value, &mut settings.window_width),
+                "window_height" => parse_into(key, value, &mut settings.window_height),
+                _ => {}
+            }
+        }
+        settings.sanitize();
+        settings
+    }
+
+    // Clamps values a hand-edited database could have pushed out of range.
+    pub fn sanitize(&mut self) {
+        // Stone is bodyweight only, so never let it leak into lifts.
+        if self.lift_unit == WeightUnit::Stone {
+            self.lift_unit = WeightUnit::default();
+        }
+        // Zero (or junk) leaves height unset; anything else is kept plausible.
+        self.height_in = if self.height_in > 0.0 { self.height_in.clamp(36.0, 96.0) } else { 0.0 };
+        // clamp passes NaN through, so junk floats go back to their defaults first.
+        let defaults = Settings::default();
+        if !self.progression_increment_lbs.is_finite() {
+            self.progression_increment_lbs = defaults.progression_increment_lbs;
+        }
+        for (value, default) in [
+            (&mut self.console_height, defaults.console_height),
+            (&mut self.window_width, defaults.window_width),
+            (&mut self.window_height, defaults.window_height),
+        ] {
+            if !value.is_finite() {
+                *value = default;
+            }
+        }
+        self.smoothing_window = self.smoothing_window.clamp(1, 120);
+        self.progression_increment_lbs = self.progression_increment_lbs.clamp(0.5, 50.0);
+        self.console_height = self.console_height.clamp(MIN_CONSOLE_HEIGHT, MAX_CONSOLE_HEIGHT);
+        self.backup_keep = self.backup_keep.clamp(1, MAX_BACKUP_KEEP);
+        self.default_rest_secs = self.default_rest_secs.clamp(5, timer::MAX_REST_SECS);
+        self.window_width = self.window_width.clamp(400.0, 4000.0);
+        self.window_height = self.window_height.clamp(400.0, 4000.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_and_skips_bad_values() {
+        let settings = Settings {
+            lift_unit: WeightUnit::Kg,
+            bodyweight_unit: WeightUnit::Stone,
+            length_unit: LengthUnit::Centimeters,
+            sex: Sex::Female,
+            height_in: 65.5,
+            theme: Theme::Light,
+            date_format: DateFormat::Long,
+            smoothing: SmoothingMethod::Sma,
+            smoothing_window: 14,
+            e1rm_formula: E1rmFormula::Brzycki,
+            progression_scheme: ProgressionScheme::DoubleProgression,
+            progression_increment_lbs: 2.5,
+            log_level: LevelFilter::Debug,
+            console_height: 150.0,
+            backup_keep: 3,
+            default_rest_secs: 180,
+            auto_start_rest: false,
+            timer_bell: true,
+            dashboard_layout: "RecentRecords,-Sparkline".parse().unwrap(),
+            window_width: 1024.0,
+            window_height: 768.0,
+        };
+        let pairs = settings.to_pairs();
+        let restored = Settings::from_pairs(pairs.iter().map(|(k, v)| (*k, v.as_str())));
+        assert_eq!(restored, settings);
+
+        let broken = Settings::from_pairs([("theme", "neon"), ("lift_unit", "st"), ("console_height", "5"), ("mystery", "1")]);
+        assert_eq!(broken.theme, Theme::System);
+        assert_eq!(broken.lift_unit, WeightUnit::Lbs);
+        assert_eq!(broken.console_height, MIN_CONSOLE_HEIGHT);
+
+        let junk = Settings::from_pairs([("console_height", "NaN"), ("window_width", "inf"), ("height_in", "NaN")]);
+        assert_eq!(junk.console_height, Settings::default().console_height);
+        assert_eq!(junk.window_width, Settings::default().window_width);
+        assert_eq!(junk.height_in, 0.0);
+    }
+
+    #[test]
+    fn formats_dates() {
+        let date = NaiveDate::from_ymd_opt(2025, 3, 5).unwrap();
+        assert_eq!(DateFormat::Iso.format(date), "2025-03-05");
+        assert_eq!(DateFormat::DayMonthYear.format(date), "05/03/2025");
+        assert_eq!(DateFormat::MonthDayYear.format(date), "03/05/2025");
+        assert_eq!(DateFormat::Long.format(date), "5 Mar 2025");
+        assert_eq!(DateFormat::Long.format_stored("not a date"), "not a date");
+    }
+}
@@ -0,0 +1,119 @@
+use chrono::NaiveDate;
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Trend smoothing for (x, y) series where x is measured in days. Both methods
+// look only backwards, so the latest smoothed value never depends on the future,
+// and both account for gaps between samples instead of assuming one per day.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum SmoothingMethod {
+    #[default]
+    Ema,
+    Sma,
+}
+
+impl Display for SmoothingMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmoothingMethod::Ema => write!(f, "EMA"),
+            SmoothingMethod::Sma => write!(f, "SMA"),
+        }
+    }
+}
+
+impl FromStr for SmoothingMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_uppercase().as_str() {
+            "EMA" => Ok(SmoothingMethod::Ema),
+            "SMA" => Ok(SmoothingMethod::Sma),
+            other => Err(format!("unknown smoothing method: {}", other)),
+        }
+    }
+}
+
+pub const DEFAULT_WINDOW_DAYS: usize = 7;
+
+// Series use "days since 1970-01-01" for x so that spacing is linear in time.
+pub fn date_to_x(date: NaiveDate) -> f64 {
+    (date - NaiveDate::default()).num_days() as f64
+}
+
+pub fn x_to_date(x: f64) -> Option<NaiveDate> {
+    NaiveDate::default().checked_add_signed(chrono::Duration::days(x.round() as i64))
+}
+
+// `points` must be sorted by x.
+pub fn smooth(points: &[(f64, f64)], method: SmoothingMethod, window_days: usize) -> Vec<(f64, f64)> {
+    match method {
+        SmoothingMethod::Ema => exponential_moving_average(points, window_days),
+        SmoothingMethod::Sma => simple_moving_average(points, window_days),
+    }
+}
+
+// Mean of every sample within the trailing `window_days` (inclusive of the current one).
+pub fn simple_moving_average(points: &[(f64, f64)], window_days: usize) -> Vec<(f64, f64)> {
+    let window = window_days.max(1) as f64;
+    let mut out = Vec::with_capacity(points.len());
+    let mut start = 0;
+    let mut sum = 0.0;
+    for (i, &(x, y)) in points.iter().enumerate() {
+        sum += y;
+        while points[start].0 <= x - window {
+            sum -= points[start].1;
+            start += 1;
+        }
+        out.push((x, sum / (i - start + 1) as f64));
+    }
+    out
+}
+
+// EMA with alpha = 2 / (window + 1) per day. A gap of `dt` days applies the
+// daily decay `dt` times, so sparse weigh-ins pull the trend proportionally harder.
+pub fn exponential_moving_average(points: &[(f64, f64)], window_days: usize) -> Vec<(f64, f64)> {
+    let alpha = 2.0 / (window_days.max(1) as f64 + 1.0);
+    let mut out = Vec::with_capacity(points.len());
+    let mut prev: Option<(f64, f64)> = None;
+    for &(x, y) in points {
+        let value = match prev {
+            None => y,
+            Some((prev_x, prev_value)) => {
+                let dt = (x - prev_x).max(0.0);
+                let weight = 1.0 - (1.0 - alpha).powf(dt);
+                prev_value + weight * (y - prev_value)
+            }
+        };
+        out.push((x, value));
+        prev = Some((x, value));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sma_uses_trailing_window() {
+        let points = [(0.0, 10.0), (1.0, 20.0), (2.0, 30.0), (3.0, 40.0)];
+        let smoothed = simple_moving_average(&points, 2);
+        assert_eq!(smoothed, vec![(0.0, 10.0), (1.0, 15.0), (2.0, 25.0), (3.0, 35.0)]);
+    }
+
+    #[test]
+    fn ema_on_constant_series_is_constant() {
+        let points = [(0.0, 180.0), (1.0, 180.0), (5.0, 180.0)];
+        for (_, y) in exponential_moving_average(&points, 7) {
+            assert!((y - 180.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn ema_weights_gaps() {
+        let daily = exponential_moving_average(&[(0.0, 100.0), (1.0, 110.0)], 7);
+        let gapped = exponential_moving_average(&[(0.0, 100.0), (4.0, 110.0)], 7);
+        assert!((daily[1].1 - 102.5).abs() < 1e-9);
+        assert!(gapped[1].1 > daily[1].1 && gapped[1].1 < 110.0);
+    }
+}
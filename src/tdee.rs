@@ -0,0 +1,76 @@
+// Adaptive TDEE: what intake would have held the weight trend flat. Over a
+// trailing window, maintenance = mean intake - trend change * kcal per lb / days.
+
+pub const WINDOW_DAYS: f64 = 14.0;
+// Days of the window that need logged intake before an estimate is made.
+pub const MIN_LOGGED_DAYS: usize = 7;
+pub const KCAL_PER_LB: f64 = 3500.0;
+
+// Linear interpolation of the trend at `x`; `None` outside the trend's range.
+fn trend_at(trend: &[(f64, f64)], x: f64) -> Option<f64> {
+    let first = trend.first()?;
+    let last = trend.last()?;
+    if x < first.0 || x > last.0 {
+        return None;
+    }
+    let idx = trend.partition_point(|&(tx, _)| tx < x);
+    let (x1, y1) = trend[idx];
+    if idx == 0 || x1 == x {
+        return Some(y1);
+    }
+    let (x0, y0) = trend[idx - 1];
+    Some(y0 + (y1 - y0) * (x - x0) / (x1 - x0))
+}
+
+// One estimate per intake day that has a full window behind it. `trend` is the
+// smoothed weight in lbs and `intake` the logged calories, both as (day x, value)
+// oldest first.
+pub fn estimate_series(trend: &[(f64, f64)], intake: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut series = Vec::new();
+    for (idx, &(x, _)) in intake.iter().enumerate() {
+        let window: Vec<f64> = intake[..=idx]
+            .iter()
+            .rev()
+            .take_while(|&&(day, _)| day > x - WINDOW_DAYS)
+            .map(|&(_, kcal)| kcal)
+            .collect();
+        if window.len() < MIN_LOGGED_DAYS {
+            continue;
+        }
+        let (Some(start), Some(end)) = (trend_at(trend, x - WINDOW_DAYS), trend_at(trend, x)) else {
+            continue;
+        };
+        let mean_intake = window.iter().sum::<f64>() / window.len() as f64;
+        series.push((x, mean_intake - (end - start) * KCAL_PER_LB / WINDOW_DAYS));
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_trend_between_points() {
+        let trend = [(0.0, 200.0), (10.0, 190.0)];
+        assert_eq!(trend_at(&trend, 4.0), Some(196.0));
+        assert_eq!(trend_at(&trend, 10.0), Some(190.0));
+        assert_eq!(trend_at(&trend, 11.0), None);
+    }
+
+    #[test]
+    fn losing_weight_means_tdee_above_intake() {
+        // Half a pound a week down on 2000 kcal a day: 250 kcal/day deficit.
+        let trend: Vec<(f64, f64)> = (0..=42).map(|d| (d as f64, 200.0 - 0.5 * d as f64 / 7.0)).collect();
+        let intake: Vec<(f64, f64)> = (1..=42).map(|d| (d as f64, 2000.0)).collect();
+        let series = estimate_series(&trend, &intake);
+        assert_eq!(series.first().unwrap().0, 14.0);
+        for (_, tdee) in &series {
+            assert!((tdee - 2250.0).abs() < 1e-6, "{}", tdee);
+        }
+
+        // Too few logged days in any window.
+        let sparse: Vec<(f64, f64)> = (1..=42).step_by(3).map(|d| (d as f64, 2000.0)).collect();
+        assert!(estimate_series(&trend, &sparse).is_empty());
+    }
+}
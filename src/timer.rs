@@ -0,0 +1,293 @@
+use std::fmt::Display;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_REST_SECS: u32 = 90;
+pub const MAX_REST_SECS: u32 = 900;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum TimerMode {
+    // One countdown between sets.
+    #[default]
+    Rest,
+    // A new round at the top of every minute.
+    Emom,
+    // 8 rounds of 20 seconds on, 10 off.
+    Tabata,
+    Custom,
+}
+
+impl TimerMode {
+    pub const ALL: [TimerMode; 4] = [TimerMode::Rest, TimerMode::Emom, TimerMode::Tabata, TimerMode::Custom];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TimerMode::Rest => "Rest",
+            TimerMode::Emom => "EMOM",
+            TimerMode::Tabata => "Tabata",
+            TimerMode::Custom => "Custom intervals",
+        }
+    }
+}
+
+impl Display for TimerMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for TimerMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        TimerMode::ALL
+            .into_iter()
+            .find(|mode| mode.to_string().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown timer mode: {}", s))
+    }
+}
+
+// Rounds of work followed by rest. A zero rest means rounds run back to back.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct IntervalPlan {
+    pub work_secs: u32,
+    pub rest_secs: u32,
+    pub rounds: u32,
+}
+
+impl IntervalPlan {
+    pub fn emom(minutes: u32) -> IntervalPlan {
+        IntervalPlan { work_secs: 60, rest_secs: 0, rounds: minutes.max(1) }
+    }
+
+    pub fn tabata() -> IntervalPlan {
+        IntervalPlan { work_secs: 20, rest_secs: 10, rounds: 8 }
+    }
+}
+
+impl Default for IntervalPlan {
+    fn default() -> Self {
+        IntervalPlan { work_secs: 40, rest_secs: 20, rounds: 5 }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Phase {
+    Work,
+    Rest,
+}
+
+impl Phase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Phase::Work => "work",
+            Phase::Rest => "rest",
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+struct Segment {
+    phase: Phase,
+    round: u32,
+    length: Duration,
+}
+
+// Where a timer is at a given moment.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TimerStatus {
+    pub phase: Phase,
+    // 1-based.
+    pub round: u32,
+    pub rounds: u32,
+    pub remaining: Duration,
+    // Share of the current segment already elapsed, 0.0 to 1.0.
+    pub progress: f32,
+    pub finished: bool,
+}
+
+// Something worth alerting about since the last poll.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum TimerEvent {
+    PhaseStarted(Phase, u32),
+    Finished,
+}
+
+// A countdown through one or more segments. Every method takes `now` so the
+// caller owns the clock.
+#[derive(Clone, Debug)]
+pub struct Timer {
+    pub mode: TimerMode,
+    // The heading while it runs, e.g. "Squat rest".
+    pub label: String,
+    segments: Vec<Segment>,
+    started_at: Instant,
+    paused_at: Option<Instant>,
+    paused_for: Duration,
+    // Segments already reported through `poll`.
+    reported: usize,
+}
+
+impl Timer {
+    pub fn rest(label: impl Into<String>, secs: u32, now: Instant) -> Timer {
+        let segment = Segment { phase: Phase::Rest, round: 1, length: Duration::from_secs(secs.max(1) as u64) };
+        Timer::new(TimerMode::Rest, label.into(), vec![segment], now)
+    }
+
+    pub fn intervals(mode: TimerMode, plan: IntervalPlan, now: Instant) -> Timer {
+        let mut segments = Vec::new();
+        for round in 1..=plan.rounds.max(1) {
+            segments.push(Segment { phase: Phase::Work, round, length: Duration::from_secs(plan.work_secs.max(1) as u64) });
+            // No rest after the last round; the timer is simply done.
+            if plan.rest_secs > 0 && round < plan.rounds {
+                segments.push(Segment { phase: Phase::Rest, round, length: Duration::from_secs(plan.rest_secs as u64) });
+            }
+        }
+        Timer::new(mode, mode.label().to_string(), segments, now)
+    }
+
+    fn new(mode: TimerMode, label: String, segments: Vec<Segment>, now: Instant) -> Timer {
+        Timer { mode, label, segments, started_at: now, paused_at: None, paused_for: Duration::ZERO, reported: 0 }
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        let until = self.paused_at.unwrap_or(now);
+        until.saturating_duration_since(self.started_at).saturating_sub(self.paused_for)
+    }
+
+    fn total(&self) -> Duration {
+        self.segments.iter().map(|s| s.length).sum()
+    }
+
+    // Index of the segment running at `elapsed`, or the segment count once done.
+    fn segment_at(&self, elapsed: Duration) -> (usize, Duration) {
+        let mut start = Duration::ZERO;
+        for (idx, segment) in self.segments.iter().enumerate() {
+            if elapsed < start + segment.length {
+                return (idx, elapsed - start);
+            }
+            start += segment.length;
+        }
+        (self.segments.len(), Duration::ZERO)
+    }
+
+    pub fn status(&self, now: Instant) -> TimerStatus {
+        let rounds = self.segments.last().map_or(1, |s| s.round);
+        let (idx, into) = self.segment_at(self.elapsed(now));
+        match self.segments.get(idx) {
+            Some(segment) => TimerStatus {
+                phase: segment.phase,
+                round: segment.round,
+                rounds,
+                remaining: segment.length - into,
+                progress: into.as_secs_f32() / segment.length.as_secs_f32(),
+                finished: false,
+            },
+            None => TimerStatus {
+                phase: self.segments.last().map_or(Phase::Rest, |s| s.phase),
+                round: rounds,
+                rounds,
+                remaining: Duration::ZERO,
+                progress: 1.0,
+                finished: true,
+            },
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self, now: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(now);
+        }
+    }
+
+    pub fn resume(&mut self, now: Instant) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_for += now.saturating_duration_since(paused_at);
+        }
+    }
+
+    // Lengthens (or, with a negative amount, shortens) the running segment.
+    pub fn extend(&mut self, secs: i64, now: Instant) {
+        let (idx, into) = self.segment_at(self.elapsed(now));
+        let Some(segment) = self.segments.get_mut(idx) else { return };
+        let length = segment.length.as_secs() as i64 + secs;
+        // Never shorter than what has already run, plus a second.
+        segment.length = Duration::from_secs(length.max(0) as u64).max(into + Duration::from_secs(1));
+    }
+
+    // Reports segment changes since the last poll; only the latest one when
+    // several went by unseen (e.g. while the window was hidden).
+    pub fn poll(&mut self, now: Instant) -> Option<TimerEvent> {
+        let (idx, _) = self.segment_at(self.elapsed(now));
+        if idx <= self.reported {
+            return None;
+        }
+        self.reported = idx;
+        match self.segments.get(idx) {
+            Some(segment) => Some(TimerEvent::PhaseStarted(segment.phase, segment.round)),
+            None => Some(TimerEvent::Finished),
+        }
+    }
+
+    pub fn total_remaining(&self, now: Instant) -> Duration {
+        self.total().saturating_sub(self.elapsed(now))
+    }
+}
+
+// "1:05", rounding up so a countdown shows 0:00 only once it's done.
+pub fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    format!("{}:{:02}", secs / 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn rest_counts_down_and_pauses() {
+        let start = Instant::now();
+        let mut timer = Timer::rest("Squat", 90, start);
+        assert_eq!(timer.status(start + secs(30)).remaining, secs(60));
+        assert_eq!(format_remaining(timer.status(start + Duration::from_millis(30_500)).remaining), "1:00");
+
+        timer.pause(start + secs(30));
+        timer.resume(start + secs(50));
+        assert_eq!(timer.status(start + secs(60)).remaining, secs(50));
+        timer.extend(30, start + secs(60));
+        assert_eq!(timer.status(start + secs(60)).remaining, secs(80));
+        timer.extend(-300, start + secs(60));
+        assert_eq!(timer.status(start + secs(60)).remaining, secs(1));
+
+        assert_eq!(timer.poll(start + secs(60)), None);
+        assert!(timer.status(start + secs(61)).finished);
+        assert_eq!(timer.poll(start + secs(61)), Some(TimerEvent::Finished));
+        assert_eq!(timer.poll(start + secs(90)), None);
+    }
+
+    #[test]
+    fn intervals_walk_through_rounds() {
+        let start = Instant::now();
+        let mut tabata = Timer::intervals(TimerMode::Tabata, IntervalPlan::tabata(), start);
+        // 8 x 20s work with 7 x 10s rest in between.
+        assert_eq!(tabata.total_remaining(start), secs(230));
+        let status = tabata.status(start + secs(25));
+        assert_eq!((status.phase, status.round, status.rounds, status.remaining), (Phase::Rest, 1, 8, secs(5)));
+        assert_eq!(tabata.poll(start + secs(25)), Some(TimerEvent::PhaseStarted(Phase::Rest, 1)));
+        assert_eq!(tabata.poll(start + secs(95)), Some(TimerEvent::PhaseStarted(Phase::Work, 4)));
+        assert_eq!(tabata.poll(start + secs(230)), Some(TimerEvent::Finished));
+
+        let emom = Timer::intervals(TimerMode::Emom, IntervalPlan::emom(10), start);
+        let status = emom.status(start + secs(125));
+        assert_eq!((status.phase, status.round, status.remaining), (Phase::Work, 3, secs(55)));
+        assert!(emom.status(start + secs(600)).finished);
+    }
+}
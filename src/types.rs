@@ -1,12 +1,27 @@
 use std::fmt::Display;
+use std::str::FromStr;
+use crate::csv_io::CsvTable;
+use crate::csv_io::apps::{AppExport, AppFormat, NameMatch};
+use crate::csv_io::import::{ColumnMapping, CsvSource, ImportReport, SessionIds};
+use crate::db::WeightLog;
+use crate::progression::Suggestion;
+use crate::units::WeightUnit;
+use std::path::PathBuf;
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
 pub enum Tab {
     #[default]
+    Dashboard,
     LogWeight,
     LogExercise,
     WeightProgress,
     ExerciseProgress,
+    Records,
+    Exercises,
+    DietCycles,
+    Measurements,
+    Data,
+    Settings,
 }
 
 impl Display for Tab {
@@ -29,14 +44,546 @@ impl Display for DietPhase {
     }
 }
 
+// Parses the `diet_cycles.phase` column, which stores the Display form.
+impl FromStr for DietPhase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "bulk" => Ok(DietPhase::Bulk),
+            "cut" => Ok(DietPhase::Cut),
+            "maintain" => Ok(DietPhase::Maintain),
+            other => Err(format!("unknown diet phase: {}", other)),
+        }
+    }
+}
+
+// Which weigh-ins the weight progress chart shows.
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
-pub enum ExerciseMetric {
+pub enum WeightProgressFilter {
+    #[default]
+    ActiveCycle,
+    Cycle(i64),
+    AllTime,
+}
+
+// What the weight progress chart plots: bodyweight, or intake against the TDEE estimate.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum WeightProgressView {
     #[default]
     Weight,
+    Energy,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ExerciseMetric {
+    #[default]
+    TopSetWeight,
+    EstimatedOneRepMax,
+    TotalVolume,
+    TotalReps,
+    // Most reps in a single set at or above a chosen weight.
+    BestRepsAtWeight,
+}
+
+impl ExerciseMetric {
+    pub const ALL: [ExerciseMetric; 5] = [
+        ExerciseMetric::TopSetWeight,
+        ExerciseMetric::EstimatedOneRepMax,
+        ExerciseMetric::TotalVolume,
+        ExerciseMetric::TotalReps,
+        ExerciseMetric::BestRepsAtWeight,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExerciseMetric::TopSetWeight => "Top set weight",
+            ExerciseMetric::EstimatedOneRepMax => "Estimated 1RM",
+            ExerciseMetric::TotalVolume => "Total volume",
+            ExerciseMetric::TotalReps => "Total reps",
+            ExerciseMetric::BestRepsAtWeight => "Best reps at weight",
+        }
+    }
+
+    // Whether values are weights (and so follow the lift unit) rather than rep counts.
+    pub fn is_weight(&self) -> bool {
+        !matches!(self, ExerciseMetric::TotalReps | ExerciseMetric::BestRepsAtWeight)
+    }
+
+    pub fn unit(&self, lift_unit: WeightUnit) -> &'static str {
+        if self.is_weight() { lift_unit.suffix() } else { "reps" }
+    }
 }
 
+// Stored in `exercises.default_metric_to_track`.
 impl Display for ExerciseMetric {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
     }
-} 
\ No newline at end of file
+}
+
+impl FromStr for ExerciseMetric {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            // "Weight" was the only variant before metrics were selectable
+            "Weight" | "TopSetWeight" => Ok(ExerciseMetric::TopSetWeight),
+            "EstimatedOneRepMax" => Ok(ExerciseMetric::EstimatedOneRepMax),
+            "TotalVolume" => Ok(ExerciseMetric::TotalVolume),
+            "TotalReps" => Ok(ExerciseMetric::TotalReps),
+            "BestRepsAtWeight" => Ok(ExerciseMetric::BestRepsAtWeight),
+            other => Err(format!("unknown exercise metric: {}", other)),
+        }
+    }
+}
+
+// One kind of row in `body_measurements`. Lengths are stored in inches, body
+// fat as a percentage.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, PartialOrd, Ord)]
+pub enum MeasurementKind {
+    #[default]
+    Waist,
+    Chest,
+    Hips,
+    Arms,
+    Thighs,
+    Neck,
+    BodyFat,
+}
+
+impl MeasurementKind {
+    pub const ALL: [MeasurementKind; 7] = [
+        MeasurementKind::Waist,
+        MeasurementKind::Chest,
+        MeasurementKind::Hips,
+        MeasurementKind::Arms,
+        MeasurementKind::Thighs,
+        MeasurementKind::Neck,
+        MeasurementKind::BodyFat,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeasurementKind::BodyFat => "Body Fat",
+            MeasurementKind::Waist => "Waist",
+            MeasurementKind::Chest => "Chest",
+            MeasurementKind::Hips => "Hips",
+            MeasurementKind::Arms => "Arms",
+            MeasurementKind::Thighs => "Thighs",
+            MeasurementKind::Neck => "Neck",
+        }
+    }
+
+    pub fn is_length(&self) -> bool {
+        *self != MeasurementKind::BodyFat
+    }
+}
+
+// Stored in `body_measurements.kind`.
+impl Display for MeasurementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for MeasurementKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MeasurementKind::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown measurement kind: {}", s))
+    }
+}
+
+// Exercise library attributes. All are optional on an exercise; the pickers
+// filter by them.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+pub enum ExerciseCategory {
+    Compound,
+    Isolation,
+}
+
+impl ExerciseCategory {
+    pub const ALL: [ExerciseCategory; 2] = [
+        ExerciseCategory::Compound,
+        ExerciseCategory::Isolation,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExerciseCategory::Compound => "Compound",
+            ExerciseCategory::Isolation => "Isolation",
+        }
+    }
+}
+
+// Stored in `exercises.category`.
+impl Display for ExerciseCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for ExerciseCategory {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ExerciseCategory::ALL
+            .into_iter()
+            .find(|value| value.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown exercise category: {}", s))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+pub enum MuscleGroup {
+    Chest,
+    Back,
+    Shoulders,
+    Biceps,
+    Triceps,
+    Forearms,
+    Core,
+    Quads,
+    Hamstrings,
+    Glutes,
+    Calves,
+}
+
+impl MuscleGroup {
+    pub const ALL: [MuscleGroup; 11] = [
+        MuscleGroup::Chest,
+        MuscleGroup::Back,
+        MuscleGroup::Shoulders,
+        MuscleGroup::Biceps,
+        MuscleGroup::Triceps,
+        MuscleGroup::Forearms,
+        MuscleGroup::Core,
+        MuscleGroup::Quads,
+        MuscleGroup::Hamstrings,
+        MuscleGroup::Glutes,
+        MuscleGroup::Calves,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MuscleGroup::Chest => "Chest",
+            MuscleGroup::Back => "Back",
+            MuscleGroup::Shoulders => "Shoulders",
+            MuscleGroup::Biceps => "Biceps",
+            MuscleGroup::Triceps => "Triceps",
+            MuscleGroup::Forearms => "Forearms",
+            MuscleGroup::Core => "Core",
+            MuscleGroup::Quads => "Quads",
+            MuscleGroup::Hamstrings => "Hamstrings",
+            MuscleGroup::Glutes => "Glutes",
+            MuscleGroup::Calves => "Calves",
+        }
+    }
+}
+
+// Stored in `exercise_muscles.muscle`.
+impl Display for MuscleGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for MuscleGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MuscleGroup::ALL
+            .into_iter()
+            .find(|value| value.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown muscle group: {}", s))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+pub enum Equipment {
+    Barbell,
+    Dumbbell,
+    Kettlebell,
+    Machine,
+    Cable,
+    Bodyweight,
+    Band,
+    Other,
+}
+
+impl Equipment {
+    pub const ALL: [Equipment; 8] = [
+        Equipment::Barbell,
+        Equipment::Dumbbell,
+        Equipment::Kettlebell,
+        Equipment::Machine,
+        Equipment::Cable,
+        Equipment::Bodyweight,
+        Equipment::Band,
+        Equipment::Other,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Equipment::Barbell => "Barbell",
+            Equipment::Dumbbell => "Dumbbell",
+            Equipment::Kettlebell => "Kettlebell",
+            Equipment::Machine => "Machine",
+            Equipment::Cable => "Cable",
+            Equipment::Bodyweight => "Bodyweight",
+            Equipment::Band => "Band",
+            Equipment::Other => "Other",
+        }
+    }
+}
+
+// Stored in `exercises.equipment`.
+impl Display for Equipment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for Equipment {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Equipment::ALL
+            .into_iter()
+            .find(|value| value.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown equipment: {}", s))
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord)]
+pub enum MovementPattern {
+    HorizontalPush,
+    VerticalPush,
+    HorizontalPull,
+    VerticalPull,
+    Squat,
+    Hinge,
+    Lunge,
+    Carry,
+    Rotation,
+    Isolation,
+}
+
+impl MovementPattern {
+    pub const ALL: [MovementPattern; 10] = [
+        MovementPattern::HorizontalPush,
+        MovementPattern::VerticalPush,
+        MovementPattern::HorizontalPull,
+        MovementPattern::VerticalPull,
+        MovementPattern::Squat,
+        MovementPattern::Hinge,
+        MovementPattern::Lunge,
+        MovementPattern::Carry,
+        MovementPattern::Rotation,
+        MovementPattern::Isolation,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MovementPattern::HorizontalPush => "Horizontal push",
+            MovementPattern::VerticalPush => "Vertical push",
+            MovementPattern::HorizontalPull => "Horizontal pull",
+            MovementPattern::VerticalPull => "Vertical pull",
+            MovementPattern::Squat => "Squat",
+            MovementPattern::Hinge => "Hinge",
+            MovementPattern::Lunge => "Lunge",
+            MovementPattern::Carry => "Carry",
+            MovementPattern::Rotation => "Rotation",
+            MovementPattern::Isolation => "Isolation",
+        }
+    }
+}
+
+// Stored in `exercises.movement_pattern`.
+impl Display for MovementPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+impl FromStr for MovementPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MovementPattern::ALL
+            .into_iter()
+            .find(|value| value.to_string() == s.trim())
+            .ok_or_else(|| format!("unknown movement pattern: {}", s))
+    }
+}
+
+// Text buffers for one set row in the log exercise tab; parsed on "Log Workout".
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetEntry {
+    pub reps: String,
+    // In the lift unit unless the text carries its own suffix.
+    pub weight: String,
+    pub rpe: String,
+    pub notes: String,
+}
+
+// Targets carried over from a workout template, shown as hints while logging.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SetTarget {
+    pub sets: i64,
+    pub rep_min: i64,
+    pub rep_max: i64,
+    pub rpe: Option<f64>,
+}
+
+impl SetTarget {
+    pub fn reps_label(&self) -> String {
+        if self.rep_min == self.rep_max {
+            self.rep_min.to_string()
+        } else {
+            format!("{}-{}", self.rep_min, self.rep_max)
+        }
+    }
+
+    // e.g. "target: 3 x 8-10 @ RPE 8"
+    pub fn label(&self) -> String {
+        let mut label = format!("target: {} x {}", self.sets, self.reps_label());
+        if let Some(rpe) = self.rpe {
+            label.push_str(&format!(" @ RPE {}", rpe));
+        }
+        label
+    }
+}
+
+// One exercise being logged in the current workout, with its sets in order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExerciseLogEntry {
+    pub exercise_name: String,
+    pub sets: Vec<SetEntry>,
+    // Set when the entry came from a template.
+    pub target: Option<SetTarget>,
+    pub suggestion: Option<Suggestion>,
+    // The name `suggestion` was worked out for; `None` means recompute.
+    pub suggestion_for: Option<String>,
+}
+
+impl Default for ExerciseLogEntry {
+    fn default() -> Self {
+        Self {
+            exercise_name: String::new(),
+            sets: vec![SetEntry::default()],
+            target: None,
+            suggestion: None,
+            suggestion_for: None,
+        }
+    }
+}
+
+// The data tab's import form: a loaded file, how its columns map onto the
+// table, and the last preview or import.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsvImportState {
+    pub table: CsvTable,
+    pub path: String,
+    pub source: Option<CsvSource>,
+    pub mapping: ColumnMapping,
+    // `None` detects them.
+    pub date_format: Option<&'static str>,
+    pub unit: Option<WeightUnit>,
+    pub report: Option<ImportReport>,
+    // Kept across files so sets imported after their sessions join them.
+    pub session_ids: SessionIds,
+}
+
+impl Default for CsvImportState {
+    fn default() -> Self {
+        CsvImportState {
+            table: CsvTable::WeightLogs,
+            path: String::new(),
+            source: None,
+            mapping: Vec::new(),
+            date_format: None,
+            unit: None,
+            report: None,
+            session_ids: SessionIds::new(),
+        }
+    }
+}
+
+// The data tab's import from another app's export: the parsed workouts and
+// the user's review of how their exercise names map onto ours.
+#[derive(Clone, Debug, Default)]
+pub struct AppImportState {
+    // `None` detects it from the header row.
+    pub format: Option<AppFormat>,
+    pub path: String,
+    pub export: Option<AppExport>,
+    pub names: Vec<NameMatch>,
+    pub report: Option<ImportReport>,
+}
+
+// Text buffers for the log weight tab's food entry; macros may stay blank.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NutritionInput {
+    pub calories: String,
+    pub protein: String,
+    pub carbs: String,
+    pub fat: String,
+}
+
+// Text buffers for a weigh-in being edited inline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WeightLogEdit {
+    pub id: i64,
+    pub log_date: String,
+    pub weight: String,
+}
+
+// A second weigh-in for a date that already has one, waiting on the user to
+// replace the existing entry or cancel; a date only ever has one weigh-in.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingDuplicateWeighIn {
+    pub existing: WeightLog,
+    pub new_weight_lbs: f64,
+}
+
+// Enough of a destructive weight log change to reverse it.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WeightLogUndo {
+    Deleted(WeightLog),
+    // Holds the row as it was before the edit or replacement.
+    Edited(WeightLog),
+}
+
+impl WeightLogUndo {
+    pub fn describe(&self) -> String {
+        match self {
+            WeightLogUndo::Deleted(entry) => format!("delete of {}", entry.log_date),
+            WeightLogUndo::Edited(entry) => format!("edit of {}", entry.log_date),
+        }
+    }
+}
+
+// Text buffers for a diet cycle being edited in the manager.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DietCycleEdit {
+    pub id: i64,
+    pub phase: DietPhase,
+    pub start_date: String,
+    pub planned_end_date: String,
+    pub actual_end_date: String,
+    pub notes: String,
+    pub calorie_target: String,
+}
+
+// A database found in the launch directory that predates the data directory,
+// waiting on the user to move it or start fresh at `target`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LegacyDbMigration {
+    pub legacy: PathBuf,
+    pub target: PathBuf,
+}
@@ -2,9 +2,9 @@ use eframe::egui;
 use crate::app_state::MyApp;
 
 pub fn render(app: &mut MyApp, ctx: &egui::Context) {
-    let panel_frame = egui::Frame::none().fill(egui::Color32::BLACK); // Set background to black
+    let panel_frame = egui::Frame::NONE.fill(egui::Color32::BLACK); // Set background to black
 
-    egui::TopBottomPanel::bottom("console_panel").exact_height(200.0).frame(panel_frame).show(ctx, |ui| {
+    egui::TopBottomPanel::bottom("console_panel").exact_height(app.settings.console_height).frame(panel_frame).show(ctx, |ui| {
         egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
             ui.vertical(|ui| {
                 for message_str in &app.console_messages { // renamed for clarity
@@ -25,23 +25,21 @@ pub fn render(app: &mut MyApp, ctx: &egui::Context) {
 
                         // part 2: the level "[level]" (light blue) and part 3: text (white)
                         let mut level_processed = false;
-                        if display_message.starts_with('[') {
-                            if let Some(end_bracket_idx) = display_message.find(']') {
-                                // a level is valid if it's "[somelevel]" followed by a space, or just "[somelevel]" at the end.
-                                let is_followed_by_space_or_is_end = display_message.len() == end_bracket_idx + 1 ||
-                                                                     (display_message.len() > end_bracket_idx + 1 && display_message.chars().nth(end_bracket_idx + 1) == Some(' '));
-
-                                if is_followed_by_space_or_is_end {
-                                    let level_text = &display_message[..=end_bracket_idx];
-                                    ui.label(egui::RichText::new(level_text).color(egui::Color32::LIGHT_BLUE).monospace());
-
-                                    let rest_of_message = display_message[end_bracket_idx + 1..].trim_start();
-                                    if !rest_of_message.is_empty() {
-                                        ui.add_space(4.0); // space after level, before text
-                                        ui.label(egui::RichText::new(rest_of_message).color(egui::Color32::WHITE).monospace());
-                                    }
-                                    level_processed = true;
+                        if display_message.starts_with('[') && let Some(end_bracket_idx) = display_message.find(']') {
+                            // a level is valid if it's "[somelevel]" followed by a space, or just "[somelevel]" at the end.
+                            let is_followed_by_space_or_is_end = display_message.len() == end_bracket_idx + 1 ||
+                                                                 (display_message.len() > end_bracket_idx + 1 && display_message.chars().nth(end_bracket_idx + 1) == Some(' '));
+
+                            if is_followed_by_space_or_is_end {
+                                let level_text = &display_message[..=end_bracket_idx];
+                                ui.label(egui::RichText::new(level_text).color(egui::Color32::LIGHT_BLUE).monospace());
+
+                                let rest_of_message = display_message[end_bracket_idx + 1..].trim_start();
+                                if !rest_of_message.is_empty() {
+                                    ui.add_space(4.0); // space after level, before text
+                                    ui.label(egui::RichText::new(rest_of_message).color(egui::Color32::WHITE).monospace());
                                 }
+                                level_processed = true;
                             }
                         }
 
@@ -0,0 +1,83 @@
+use crate::db::exercises::{Exercise, ExerciseFilter};
+use crate::types::{Equipment, ExerciseCategory, MovementPattern, MuscleGroup};
+use eframe::egui;
+use std::hash::Hash;
+
+// Search box and attribute filters, shared by the library and every place an
+// exercise is chosen. Returns whether the filter changed.
+pub fn filter_row(ui: &mut egui::Ui, id: &str, filter: &mut ExerciseFilter) -> bool {
+    let mut changed = false;
+    ui.horizontal_wrapped(|ui| {
+        changed |= ui
+            .add(egui::TextEdit::singleline(&mut filter.search).hint_text("search exercises").desired_width(130.0))
+            .changed();
+        changed |= option_combo(ui, (id, "category"), "any category", &mut filter.category, &ExerciseCategory::ALL, |c| c.label());
+        changed |= option_combo(ui, (id, "muscle"), "any muscle", &mut filter.muscle, &MuscleGroup::ALL, |m| m.label());
+        changed |= option_combo(ui, (id, "equipment"), "any equipment", &mut filter.equipment, &Equipment::ALL, |e| e.label());
+        changed |= option_combo(ui, (id, "pattern"), "any movement", &mut filter.movement_pattern, &MovementPattern::ALL, |p| {
+            p.label()
+        });
+        changed |= ui.checkbox(&mut filter.include_archived, "archived").changed();
+        if filter.is_active() && ui.small_button("Clear").clicked() {
+            *filter = ExerciseFilter { include_archived: filter.include_archived, ..ExerciseFilter::default() };
+            changed = true;
+        }
+    });
+    changed
+}
+
+// A combo for an optional attribute; `unset` labels the `None` entry.
+pub fn option_combo<T: PartialEq + Copy>(
+    ui: &mut egui::Ui,
+    id: impl Hash,
+    unset: &str,
+    value: &mut Option<T>,
+    options: &[T],
+    label: impl Fn(&T) -> &'static str,
+) -> bool {
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id).selected_text(value.as_ref().map_or_else(|| unset.to_string(), |v| label(v).to_string())).show_ui(ui, |ui| {
+        changed |= ui.selectable_value(value, None, unset).changed();
+        for option in options {
+            changed |= ui.selectable_value(value, Some(*option), label(option)).changed();
+        }
+    });
+    changed
+}
+
+pub fn display_name(exercise: &Exercise) -> String {
+    if exercise.is_archived { format!("{} (archived)", exercise.name) } else { exercise.name.clone() }
+}
+
+// The exercises `filter` lets through. With `none_label`, an entry for no
+// exercise comes first. The current selection stays visible even when the
+// filter would hide it. Returns whether the selection changed.
+pub fn combo(
+    ui: &mut egui::Ui,
+    id: impl Hash,
+    library: &[Exercise],
+    filter: &ExerciseFilter,
+    selected: &mut Option<i64>,
+    none_label: Option<&str>,
+) -> bool {
+    let selected_text = selected
+        .and_then(|id| library.iter().find(|e| e.id == id))
+        .map(display_name)
+        .unwrap_or_else(|| none_label.unwrap_or("select...").to_string());
+    let mut changed = false;
+    egui::ComboBox::from_id_salt(id).selected_text(selected_text).height(320.0).show_ui(ui, |ui| {
+        if let Some(label) = none_label {
+            changed |= ui.selectable_value(selected, None, label).changed();
+        }
+        let current = *selected;
+        let mut shown = 0;
+        for exercise in library.iter().filter(|e| filter.matches(e) || Some(e.id) == current) {
+            changed |= ui.selectable_value(selected, Some(exercise.id), display_name(exercise)).changed();
+            shown += 1;
+        }
+        if shown == 0 {
+            ui.label("no exercises match the filter.");
+        }
+    });
+    changed
+}
@@ -1,3 +1,7 @@
 pub mod popups;
 pub mod tabs;
 pub mod console;
+pub mod plot_helpers;
+pub mod exercise_picker;
+pub mod unit_picker;
+pub mod timer_panel;
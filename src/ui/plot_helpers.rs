@@ -0,0 +1,71 @@
+use crate::settings::DateFormat;
+use crate::types::DietPhase;
+use eframe::egui;
+use egui_plot::{GridMark, PlotPoint, PlotUi, Polygon};
+use std::ops::RangeInclusive;
+
+// Plots share the smoothed series' x axis of days since 1970-01-01.
+pub use crate::smoothing::{date_to_x, x_to_date};
+
+pub fn format_date_axis(date_format: DateFormat) -> impl Fn(GridMark, &RangeInclusive<f64>) -> String {
+    move |mark, _range| x_to_date(mark.value).map(|d| date_format.format(d)).unwrap_or_default()
+}
+
+pub fn format_point_label(date_format: DateFormat) -> impl Fn(&str, &PlotPoint) -> String {
+    move |name, point| {
+        let date = x_to_date(point.x).map(|d| date_format.format(d)).unwrap_or_default();
+        if name.is_empty() {
+            format!("{}\n{:.1}", date, point.y)
+        } else {
+            format!("{}\n{}\n{:.1}", name, date, point.y)
+        }
+    }
+}
+
+// A diet cycle's extent on the x axis, used to shade the chart background.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhaseSpan {
+    pub start_x: f64,
+    pub end_x: f64,
+    pub phase: DietPhase,
+}
+
+pub fn phase_color(phase: DietPhase) -> egui::Color32 {
+    match phase {
+        DietPhase::Bulk => egui::Color32::from_rgba_unmultiplied(80, 170, 90, 28),
+        DietPhase::Cut => egui::Color32::from_rgba_unmultiplied(200, 80, 70, 28),
+        DietPhase::Maintain => egui::Color32::from_rgba_unmultiplied(90, 130, 200, 28),
+    }
+}
+
+// Draws one translucent rectangle per span between `y_min` and `y_max`. Spans
+// share a legend entry per phase.
+pub fn add_phase_shading(plot_ui: &mut PlotUi, spans: &[PhaseSpan], y_min: f64, y_max: f64) {
+    for span in spans {
+        let color = phase_color(span.phase);
+        let corners = vec![
+            [span.start_x, y_min],
+            [span.end_x, y_min],
+            [span.end_x, y_max],
+            [span.start_x, y_max],
+        ];
+        plot_ui.polygon(
+            Polygon::new(corners)
+                .fill_color(color)
+                .stroke(egui::Stroke::NONE)
+                .allow_hover(false)
+                .name(span.phase.to_string()),
+        );
+    }
+}
+
+// Min/max of the y values with a little breathing room, for sizing the shading.
+pub fn padded_y_range(series: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let min = series.iter().map(|p| p.1).fold(f64::INFINITY, f64::min);
+    let max = series.iter().map(|p| p.1).fold(f64::NEG_INFINITY, f64::max);
+    if !min.is_finite() || !max.is_finite() {
+        return None;
+    }
+    let pad = ((max - min) * 0.1).max(1.0);
+    Some((min - pad, max + pad))
+}
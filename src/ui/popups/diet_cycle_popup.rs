@@ -1,9 +1,8 @@
 use crate::app_state::MyApp;
 use crate::types::DietPhase;
 use eframe::egui;
-use rusqlite;
+use crate::db::diet_cycles;
 use chrono::NaiveDate;
-use std::time::Instant;
 use log::error;
 
 pub fn render(app: &mut MyApp, ctx: &egui::Context) {
@@ -29,62 +28,46 @@ pub fn render(app: &mut MyApp, ctx: &egui::Context) {
                     ui.label("Planned End Date (YYYY-MM-DD):");
                     ui.add(egui::TextEdit::singleline(&mut app.new_diet_planned_end_date));
 
+                    ui.label("Calorie Target (kcal/day, optional):");
+                    ui.add(egui::TextEdit::singleline(&mut app.new_diet_calorie_target));
+
+                    if app.active_diet_cycle_id.is_some() {
+                        ui.checkbox(&mut app.new_diet_end_previous, "End the current cycle on the start date");
+                    }
+
                     ui.add_space(10.0);
                     ui.horizontal(|ui| {
                         if ui.button("Save").clicked() {
-                            if let (Ok(start_date), Ok(planned_end_date)) = (
+                            let calorie_target = diet_cycles::parse_calorie_target(&app.new_diet_calorie_target);
+                            if let Err(msg) = &calorie_target {
+                                app.push_status(msg);
+                            } else if let (Ok(start_date), Ok(planned_end_date), Ok(calorie_target)) = (
                                 NaiveDate::parse_from_str(&app.new_diet_start_date, "%Y-%m-%d"),
-                                NaiveDate::parse_from_str(&app.new_diet_planned_end_date, "%Y-%m-%d")
+                                NaiveDate::parse_from_str(&app.new_diet_planned_end_date, "%Y-%m-%d"),
+                                calorie_target,
                             ) {
-                                let mut save_successful = false;
-                                let mut new_active_id: Option<i64> = None;
-                                let mut error_message: Option<String> = None;
-
-                                if let Some(conn_mutex) = &app.db_conn {
-                                    match conn_mutex.lock() {
-                                        Ok(conn) => {
-                                            match conn.execute(
-                                                "INSERT INTO diet_cycles (phase, start_date, planned_end_date) VALUES (?1, ?2, ?3)",
-                                                rusqlite::params![
-                                                    app.new_diet_phase.to_string(),
-                                                    start_date.format("%Y-%m-%d").to_string(),
-                                                    planned_end_date.format("%Y-%m-%d").to_string()
-                                                ],
-                                            ) {
-                                                Ok(_) => {
-                                                    new_active_id = Some(conn.last_insert_rowid());
-                                                    save_successful = true;
-                                                }
-                                                Err(e) => {
-                                                    error_message = Some(format!("error saving diet cycle: {}", e));
-                                                    error!("error saving diet cycle: {}", e);
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                             error_message = Some(format!("failed to acquire db lock: {}", e));
-                                             error!("failed to acquire db lock: {}", e);
-                                        }
+                                let phase = app.new_diet_phase;
+                                let end_previous = app.new_diet_end_previous;
+                                let today = chrono::Local::now().date_naive();
+                                match app.with_db(|conn| {
+                                    diet_cycles::create(conn, phase, start_date, planned_end_date, calorie_target, end_previous, today)
+                                }) {
+                                    Ok(new_id) => {
+                                        app.active_diet_cycle_id = Some(new_id);
+                                        app.push_status("new diet cycle saved.");
+                                        app.new_diet_calorie_target.clear();
+                                        app.show_diet_cycle_popup = false;
+                                        app.fetch_recent_weight_logs();
+                                        app.fetch_diet_cycles();
+                                        app.fetch_energy_data();
+                                    }
+                                    Err(e) => {
+                                        error!("error saving diet cycle: {}", e);
+                                        app.push_status(format!("error saving diet cycle: {}", e));
                                     }
-                                } 
-
-                                if save_successful {
-                                    app.active_diet_cycle_id = new_active_id;
-                                    app.console_messages.push(format!("[STATUS] new diet cycle saved.\n"));
-                                    app.last_status_time = Instant::now();
-                                    app.show_diet_cycle_popup = false;
-                                    app.fetch_recent_weight_logs();
-                                } else if let Some(msg) = error_message {
-                                    app.console_messages.push(format!("[STATUS] {}\n", msg));
-                                    app.last_status_time = Instant::now();
-                                } else {
-                                    // This case might need more specific error handling if there are other failure modes
-                                    app.console_messages.push(format!("[STATUS] failed to save diet cycle.\n"));
-                                    app.last_status_time = Instant::now();
                                 }
                             } else {
-                                app.console_messages.push(format!("[STATUS] invalid date format. use yyyy-mm-dd.\n"));
-                                app.last_status_time = Instant::now();
+                                app.push_status("invalid date format. use yyyy-mm-dd.");
                             }
                         }
                         if ui.button("Cancel").clicked() {
@@ -0,0 +1,48 @@
+use crate::app_state::MyApp;
+use crate::db_init;
+use eframe::egui;
+use log::error;
+
+pub fn render(app: &mut MyApp, ctx: &egui::Context) {
+    let Some(pending) = app.pending_legacy_db.clone() else { return };
+
+    egui::Window::new("Move Existing Database?")
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!("found a database at {}.", pending.legacy.display()));
+            ui.label(format!("LiftMetrics now keeps its data in {}.", pending.target.display()));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Move It").clicked() {
+                    match db_init::migrate_legacy_db(&pending.legacy, &pending.target) {
+                        Ok(retired) => {
+                            app.push_status(format!("database moved. the old file was renamed to {}.", retired.display()));
+                            open_target(app, ctx, &pending.target);
+                        }
+                        Err(e) => {
+                            error!("failed to move database: {}", e);
+                            app.push_status(format!("failed to move database: {}", e));
+                        }
+                    }
+                }
+                if ui.button("Start Fresh").on_hover_text("leave the old file where it is").clicked() {
+                    open_target(app, ctx, &pending.target);
+                }
+            });
+        });
+}
+
+fn open_target(app: &mut MyApp, ctx: &egui::Context, target: &std::path::Path) {
+    match app.open_database(target) {
+        Ok(()) => {
+            app.pending_legacy_db = None;
+            app.apply_window_settings(ctx);
+        }
+        Err(e) => {
+            error!("failed to open database {}: {}", target.display(), e);
+            app.push_status(format!("failed to open database: {}", e));
+        }
+    }
+}
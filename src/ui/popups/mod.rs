@@ -1 +1,2 @@
-pub mod diet_cycle_popup; 
\ No newline at end of file
+pub mod diet_cycle_popup;
+pub mod legacy_db_popup;
@@ -0,0 +1,239 @@
+use crate::app_state::MyApp;
+use crate::dashboard::{self, DashboardWidget};
+use crate::db::records;
+use crate::types::Tab;
+use crate::units::WeightUnit;
+use eframe::egui;
+use egui_plot::{Line, Plot, Points};
+
+const WIDGET_WIDTH: f32 = 280.0;
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.horizontal(|ui| {
+        ui.heading("Dashboard");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("Refresh").clicked() {
+                app.fetch_dashboard();
+            }
+            ui.toggle_value(&mut app.customizing_dashboard, "Customize");
+        });
+    });
+    ui.add_space(10.0);
+
+    if app.customizing_dashboard {
+        render_customize(app, ui, ctx);
+        ui.add_space(10.0);
+    }
+
+    let widgets: Vec<DashboardWidget> = app.settings.dashboard_layout.visible().collect();
+    if widgets.is_empty() {
+        ui.label("every widget is hidden. use Customize to show some.");
+        return;
+    }
+    egui::ScrollArea::vertical().max_height((ui.available_height() - 40.0).max(150.0)).show(ui, |ui| {
+        ui.horizontal_wrapped(|ui| {
+            for widget in widgets {
+                ui.group(|ui| {
+                    ui.set_width(WIDGET_WIDTH);
+                    ui.vertical(|ui| {
+                        ui.label(egui::RichText::new(widget.label()).strong());
+                        ui.add_space(4.0);
+                        match widget {
+                            DashboardWidget::Weight => render_weight(app, ui),
+                            DashboardWidget::Sparkline => render_sparkline(app, ui),
+                            DashboardWidget::DietCycle => render_cycle(app, ui),
+                            DashboardWidget::LastWorkout => render_last_workout(app, ui),
+                            DashboardWidget::TrainingFrequency => render_frequency(app, ui),
+                            DashboardWidget::RecentRecords => render_records(app, ui),
+                        }
+                    });
+                });
+            }
+        });
+    });
+    app.display_status_message(ui);
+}
+
+// Show/hide and reorder widgets; saved with the other settings.
+fn render_customize(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let mut layout = app.settings.dashboard_layout.clone();
+    let count = layout.widgets.len();
+    let mut moves = Vec::new();
+    ui.group(|ui| {
+        egui::Grid::new("dashboard_layout_grid").num_columns(2).spacing([12.0, 2.0]).show(ui, |ui| {
+            for (idx, (widget, visible)) in layout.widgets.iter_mut().enumerate() {
+                ui.checkbox(visible, widget.label());
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(idx > 0, egui::Button::new("Up").small()).clicked() {
+                        moves.push((idx, true));
+                    }
+                    if ui.add_enabled(idx + 1 < count, egui::Button::new("Down").small()).clicked() {
+                        moves.push((idx, false));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+        if ui.small_button("Reset Layout").clicked() {
+            layout = dashboard::DashboardLayout::default();
+        }
+    });
+    for (idx, up) in moves {
+        if up { layout.move_up(idx) } else { layout.move_down(idx) }
+    }
+    if layout != app.settings.dashboard_layout {
+        let mut settings = app.settings.clone();
+        settings.dashboard_layout = layout;
+        app.apply_settings(ctx, settings);
+    }
+}
+
+// Stone is too coarse for small changes, so those fall back to pounds.
+fn format_change(change_lbs: f64, unit: WeightUnit) -> String {
+    let unit = if unit == WeightUnit::Stone { WeightUnit::Lbs } else { unit };
+    format!("{:+.1} {}", unit.convert_lbs(change_lbs), unit.suffix())
+}
+
+fn render_weight(app: &mut MyApp, ui: &mut egui::Ui) {
+    let unit = app.settings.bodyweight_unit;
+    let Some(weight) = &app.dashboard.weight else {
+        ui.label("no weigh-ins yet.");
+        if ui.button("Log Weight").clicked() {
+            app.active_tab = Tab::LogWeight;
+        }
+        return;
+    };
+    ui.label(egui::RichText::new(unit.format(weight.trend_lbs)).size(24.0))
+        .on_hover_text(format!("{} trend", app.settings.smoothing));
+    ui.label(format!(
+        "last weigh-in {} on {}",
+        unit.format(weight.latest_lbs),
+        app.settings.date_format.format(weight.logged_on)
+    ));
+    match weight.change_7d_lbs {
+        Some(change) => ui.label(format!("{} over 7 days", format_change(change, unit))),
+        None => ui.label("not enough weigh-ins for a 7 day change."),
+    };
+}
+
+fn render_sparkline(app: &mut MyApp, ui: &mut egui::Ui) {
+    let unit = app.settings.bodyweight_unit;
+    let Some(weight) = app.dashboard.weight.as_ref().filter(|w| w.recent.len() > 1) else {
+        ui.label("weigh in a few times to see a trend.");
+        return;
+    };
+    let to_points = |series: &[(f64, f64)]| series.iter().map(|&(x, y)| [x, unit.convert_lbs(y)]).collect::<Vec<_>>();
+    let (raw, trend) = (to_points(&weight.recent), to_points(&weight.recent_trend));
+    let response = Plot::new("dashboard_sparkline")
+        .height(80.0)
+        .width(WIDGET_WIDTH)
+        .show_axes(false)
+        .show_grid(false)
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .show_x(false)
+        .show(ui, |plot_ui| {
+            plot_ui.points(Points::new(raw).radius(1.5).color(egui::Color32::GRAY));
+            plot_ui.line(Line::new(trend).width(2.0));
+        })
+        .response;
+    response.on_hover_text(format!("last {} days; open Weight Progress for the full chart", dashboard::SPARKLINE_DAYS));
+}
+
+fn render_cycle(app: &mut MyApp, ui: &mut egui::Ui) {
+    let Some(cycle) = &app.dashboard.cycle else {
+        ui.label("no active diet cycle.");
+        if ui.button("Manage Diet Cycles").clicked() {
+            app.active_tab = Tab::DietCycles;
+        }
+        return;
+    };
+    ui.label(egui::RichText::new(cycle.phase.to_string()).size(20.0));
+    ui.label(format!("day {} of {}", cycle.day, cycle.planned_days));
+    if cycle.days_remaining >= 0 {
+        ui.label(format!("{} day(s) remaining", cycle.days_remaining));
+    } else {
+        ui.colored_label(ui.visuals().warn_fg_color, format!("{} day(s) past the planned end", -cycle.days_remaining));
+    }
+    if cycle.planned_days > 0 {
+        let progress = (cycle.day as f32 / cycle.planned_days as f32).clamp(0.0, 1.0);
+        ui.add(egui::ProgressBar::new(progress).desired_width(WIDGET_WIDTH));
+    }
+    if let Some(target) = cycle.calorie_target {
+        ui.label(format!("target {} kcal/day", target));
+    }
+}
+
+fn render_last_workout(app: &mut MyApp, ui: &mut egui::Ui) {
+    let unit = app.settings.lift_unit;
+    let Some(workout) = &app.dashboard.last_workout else {
+        ui.label("no workouts logged yet.");
+        if ui.button("Log Exercise").clicked() {
+            app.active_tab = Tab::LogExercise;
+        }
+        return;
+    };
+    ui.label(app.settings.date_format.format_stored(&workout.date));
+    if let Some(notes) = workout.notes.as_deref().filter(|n| !n.is_empty()) {
+        ui.label(egui::RichText::new(notes).italics());
+    }
+    egui::Grid::new("dashboard_last_workout").num_columns(3).spacing([10.0, 2.0]).show(ui, |ui| {
+        for exercise in &workout.exercises {
+            ui.label(&exercise.name);
+            ui.label(format!("{} set(s)", exercise.sets));
+            ui.label(format!("top {}", unit.format(exercise.top_weight_lbs)));
+            ui.end_row();
+        }
+    });
+    ui.label(format!("volume {}", unit.format(workout.volume_lbs)));
+}
+
+fn render_frequency(app: &mut MyApp, ui: &mut egui::Ui) {
+    let weeks = &app.dashboard.weekly_sessions;
+    let this_week = weeks.last().copied().unwrap_or(0);
+    ui.label(egui::RichText::new(format!("{} workout day(s) in the last 7 days", this_week)).size(16.0));
+    if weeks.is_empty() {
+        return;
+    }
+    let average = weeks.iter().sum::<usize>() as f64 / weeks.len() as f64;
+    ui.label(format!("{:.1} per week over {} weeks", average, weeks.len()));
+    // One bar per week, oldest on the left.
+    let max = weeks.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(WIDGET_WIDTH, 40.0), egui::Sense::hover());
+    let slot = rect.width() / weeks.len() as f32;
+    let color = ui.visuals().selection.bg_fill;
+    for (idx, count) in weeks.iter().enumerate() {
+        let height = rect.height() * (*count as f32 / max);
+        let left = rect.left() + idx as f32 * slot;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + 2.0, rect.bottom() - height),
+            egui::pos2(left + slot - 2.0, rect.bottom()),
+        );
+        ui.painter().rect_filled(bar, 2.0, color);
+    }
+}
+
+fn render_records(app: &mut MyApp, ui: &mut egui::Ui) {
+    let unit = app.settings.lift_unit;
+    if app.dashboard.recent_records.is_empty() {
+        ui.label("no personal records yet.");
+        return;
+    }
+    egui::Grid::new("dashboard_recent_records").num_columns(3).spacing([10.0, 2.0]).show(ui, |ui| {
+        for record in &app.dashboard.recent_records {
+            ui.label(&record.exercise_name);
+            ui.label(format!(
+                "{} {}",
+                records::record_label(record.record_type, record.rep_count),
+                records::format_value(record.record_type, record.value, unit)
+            ));
+            ui.label(app.settings.date_format.format_stored(&record.achieved_on));
+            ui.end_row();
+        }
+    });
+    if ui.small_button("All Records").clicked() {
+        app.active_tab = Tab::Records;
+    }
+}
@@ -0,0 +1,517 @@
+use crate::app_state::MyApp;
+use crate::backup::{self, BackupReason};
+use crate::csv_io::apps::{self, AppFormat};
+use crate::csv_io::import::{self, CsvSource, ImportOptions, ImportReport, RowStatus};
+use crate::csv_io::{self, CsvError, CsvTable, export};
+use crate::settings::DateFormat;
+use crate::units::WeightUnit;
+use eframe::egui;
+use log::error;
+use std::path::PathBuf;
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.heading("Data");
+    ui.add_space(10.0);
+
+    egui::ScrollArea::vertical().id_salt("data_tab_scroll").show(ui, |ui| {
+        render_backups(app, ui, ctx);
+        ui.add_space(10.0);
+        ui.separator();
+        render_folder(app, ui);
+        ui.add_space(10.0);
+        ui.separator();
+        render_file_import(app, ui);
+        ui.add_space(10.0);
+        ui.separator();
+        render_app_import(app, ui);
+    });
+
+    app.display_status_message(ui);
+}
+
+// Snapshots of the whole database: taken on startup, exit and before
+// migrations, or on demand, and restorable after a validity check.
+fn render_backups(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.label(egui::RichText::new("Backups").strong());
+    if let Some(path) = &app.db_path {
+        ui.small(format!(
+            "kept in {}; the newest {} of each kind are kept.",
+            backup::dir_for(path).display(),
+            app.settings.backup_keep
+        ));
+    }
+    ui.horizontal(|ui| {
+        if ui.button("Back Up Now").clicked() {
+            match app.back_up(BackupReason::Manual) {
+                Ok(path) => app.push_status(format!("backed up to {}.", path.display())),
+                Err(e) => {
+                    error!("error backing up: {}", e);
+                    app.push_status(format!("error backing up: {}", e));
+                }
+            }
+        }
+        if ui.button("Check Integrity").on_hover_text("run sqlite's integrity check on the database").clicked()
+            && app.check_integrity()
+        {
+            app.push_status("integrity check passed.");
+        }
+    });
+
+    let date_format = app.settings.date_format;
+    let mut restore = None;
+    if !app.backups.is_empty() {
+        egui::ScrollArea::vertical().id_salt("backups_scroll").max_height(180.0).show(ui, |ui| {
+            egui::Grid::new("backups_grid").num_columns(4).spacing([12.0, 2.0]).striped(true).show(ui, |ui| {
+                for snapshot in &app.backups {
+                    ui.label(format!("{} {}", date_format.format(snapshot.taken_at.date()), snapshot.taken_at.format("%H:%M:%S")));
+                    ui.label(snapshot.reason.label());
+                    ui.label(format!("{:.0} KB", snapshot.size_bytes as f64 / 1024.0));
+                    if ui.small_button("Restore").clicked() {
+                        restore = Some(snapshot.path.clone());
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+    }
+    ui.horizontal(|ui| {
+        ui.label("Restore from file:");
+        ui.add(egui::TextEdit::singleline(&mut app.restore_path).desired_width(300.0).hint_text("path to a .db backup"));
+        if ui.add_enabled(!app.restore_path.trim().is_empty(), egui::Button::new("Restore")).clicked() {
+            restore = Some(PathBuf::from(app.restore_path.trim()));
+        }
+    });
+    if restore.is_some() {
+        app.pending_restore = restore;
+    }
+
+    let Some(source) = app.pending_restore.clone() else { return };
+    ui.colored_label(
+        ui.visuals().warn_fg_color,
+        format!("replace all data with {}? the current data is backed up first.", source.display()),
+    );
+    ui.horizontal(|ui| {
+        if ui.button("Confirm Restore").clicked() {
+            app.pending_restore = None;
+            match app.restore_backup(&source) {
+                Ok(safety) => {
+                    app.apply_window_settings(ctx);
+                    app.push_status(format!("restored {}; previous data saved to {}.", source.display(), safety.display()));
+                }
+                Err(e) => {
+                    error!("error restoring {}: {}", source.display(), e);
+                    app.push_status(format!("error restoring {}: {}", source.display(), e));
+                }
+            }
+        }
+        if ui.button("Cancel").clicked() {
+            app.pending_restore = None;
+        }
+    });
+}
+
+// Export everything (or one table) into the folder, or import a whole dump from it.
+fn render_folder(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Folder").strong());
+    ui.horizontal(|ui| {
+        ui.label("Path:");
+        ui.add(egui::TextEdit::singleline(&mut app.csv_dir).desired_width(360.0));
+    });
+    ui.small(format!(
+        "exports write ISO dates, weigh-ins in {} and sets in {}.",
+        app.settings.bodyweight_unit.suffix(),
+        app.settings.lift_unit.suffix()
+    ));
+    ui.horizontal(|ui| {
+        if ui.button("Export All").clicked() {
+            export_all(app);
+        }
+        for table in CsvTable::ALL {
+            if ui.small_button(table.file_name()).on_hover_text(format!("export {} only", table.label().to_lowercase())).clicked() {
+                export_one(app, table);
+            }
+        }
+    });
+    ui.horizontal(|ui| {
+        if ui.button("Preview Folder Import").on_hover_text("dry run of every exported file in the folder").clicked() {
+            import_folder(app, true);
+        }
+        if ui.button("Import Folder").clicked() {
+            import_folder(app, false);
+        }
+    });
+    for report in &app.csv_dir_reports {
+        ui.label(report.summary());
+    }
+}
+
+fn render_file_import(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Import a CSV file").strong());
+    let mut table_changed = false;
+    let mut load = false;
+    ui.horizontal(|ui| {
+        ui.label("Into:");
+        let state = &mut app.csv_import;
+        egui::ComboBox::from_id_salt("csv_import_table").selected_text(state.table.label()).show_ui(ui, |ui| {
+            for table in CsvTable::ALL {
+                table_changed |= ui.selectable_value(&mut state.table, table, table.label()).changed();
+            }
+        });
+        ui.label("File:");
+        ui.add(egui::TextEdit::singleline(&mut state.path).desired_width(300.0).hint_text("path to a .csv file"));
+        load = ui.button("Load").clicked();
+    });
+    if load {
+        load_file(app);
+    } else if table_changed {
+        let state = &mut app.csv_import;
+        if let Some(source) = &state.source {
+            state.mapping = import::auto_map(state.table, &source.headers);
+        }
+        state.report = None;
+    }
+
+    let Some(source) = app.csv_import.source.clone() else { return };
+    ui.small(format!("{} rows; columns: {}", source.rows.len(), source.headers.join(", ")));
+
+    let table = app.csv_import.table;
+    let detected = import::resolve_date_format(table, &source, &app.csv_import.mapping, month_first(app.settings.date_format));
+    let header_unit = import::column_unit(table, &source, &app.csv_import.mapping).unwrap_or(default_unit(app, table));
+    let mut changed = false;
+    egui::Grid::new("csv_mapping_grid").num_columns(2).spacing([12.0, 4.0]).show(ui, |ui| {
+        for (idx, field) in table.fields().iter().enumerate() {
+            let required = if table.is_required(*field) { " *" } else { "" };
+            ui.label(format!("{}{}:", field.header(), required));
+            let column = &mut app.csv_import.mapping[idx];
+            let selected = column.and_then(|c| source.headers.get(c)).map_or("(not mapped)", String::as_str);
+            egui::ComboBox::from_id_salt(("csv_mapping", idx)).selected_text(selected).show_ui(ui, |ui| {
+                changed |= ui.selectable_value(column, None, "(not mapped)").changed();
+                for (col, header) in source.headers.iter().enumerate() {
+                    changed |= ui.selectable_value(column, Some(col), header).changed();
+                }
+            });
+            ui.end_row();
+        }
+
+        ui.label("Date format:");
+        let state = &mut app.csv_import;
+        let auto_label = match &detected {
+            Ok(format) => format!("detect ({})", csv_io::date_format_label(format)),
+            Err(_) => "detect (not recognised)".to_string(),
+        };
+        let selected = state.date_format.map_or(auto_label.clone(), csv_io::date_format_label);
+        egui::ComboBox::from_id_salt("csv_date_format").selected_text(selected).show_ui(ui, |ui| {
+            changed |= ui.selectable_value(&mut state.date_format, None, auto_label).changed();
+            for format in csv_io::DATE_FORMATS {
+                changed |= ui.selectable_value(&mut state.date_format, Some(format), csv_io::date_format_label(format)).changed();
+            }
+        });
+        ui.end_row();
+
+        if table.fields().contains(&csv_io::Field::Weight) {
+            ui.label("Weight unit:");
+            let auto_label = format!("detect ({})", header_unit.suffix());
+            let selected = state.unit.map_or(auto_label.clone(), |u| u.suffix().to_string());
+            egui::ComboBox::from_id_salt("csv_unit").selected_text(selected).show_ui(ui, |ui| {
+                changed |= ui.selectable_value(&mut state.unit, None, auto_label).changed();
+                for unit in WeightUnit::BODYWEIGHT_UNITS {
+                    changed |= ui.selectable_value(&mut state.unit, Some(unit), unit.suffix()).changed();
+                }
+            });
+            ui.end_row();
+        }
+    });
+    if changed {
+        // A preview no longer describes what Import would do.
+        app.csv_import.report = None;
+    }
+
+    let previewed = app.csv_import.report.as_ref().is_some_and(|r| r.dry_run);
+    ui.horizontal(|ui| {
+        if ui.button("Preview").clicked() {
+            import_file(app, true);
+        }
+        if ui.add_enabled(previewed, egui::Button::new("Import")).on_disabled_hover_text("preview the import first").clicked() {
+            import_file(app, false);
+        }
+    });
+
+    if let Some(report) = &app.csv_import.report {
+        render_report(ui, "csv", report);
+    }
+}
+
+// Workouts exported from Strong, Hevy or FitNotes, with a review of the exercise names.
+fn render_app_import(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Import from another app").strong());
+    let mut load = false;
+    ui.horizontal(|ui| {
+        ui.label("Format:");
+        let state = &mut app.app_import;
+        let selected = state.format.map_or("detect", |f| f.label());
+        egui::ComboBox::from_id_salt("app_import_format").selected_text(selected).show_ui(ui, |ui| {
+            ui.selectable_value(&mut state.format, None, "detect");
+            for format in AppFormat::ALL {
+                ui.selectable_value(&mut state.format, Some(format), format.label());
+            }
+        });
+        ui.label("File:");
+        ui.add(egui::TextEdit::singleline(&mut state.path).desired_width(300.0).hint_text("path to the app's .csv export"));
+        load = ui.button("Load").clicked();
+    });
+    if load {
+        load_app_export(app);
+    }
+
+    let Some(export) = &app.app_import.export else { return };
+    let sets: usize = export.workouts.iter().map(|w| w.set_count()).sum();
+    ui.small(format!(
+        "{} export: {} workouts, {} sets, weights in {}, dates as {}.",
+        export.format.label(),
+        export.workouts.len(),
+        sets,
+        export.unit.suffix(),
+        csv_io::date_format_label(export.date_format)
+    ));
+
+    ui.add_space(5.0);
+    ui.label("Exercise names:");
+    let mut changed = false;
+    let existing = &app.all_exercises_for_dropdown;
+    egui::Grid::new("app_import_names").num_columns(3).spacing([12.0, 4.0]).striped(true).show(ui, |ui| {
+        for (idx, name) in app.app_import.names.iter_mut().enumerate() {
+            ui.label(&name.source);
+            let create = format!("new: {}", name.source);
+            let selected = name.target.clone().unwrap_or_else(|| create.clone());
+            egui::ComboBox::from_id_salt(("app_import_name", idx)).selected_text(selected).width(220.0).show_ui(ui, |ui| {
+                changed |= ui.selectable_value(&mut name.target, None, create).changed();
+                for (_, exercise) in existing {
+                    changed |= ui.selectable_value(&mut name.target, Some(exercise.clone()), exercise).changed();
+                }
+            });
+            match name.score {
+                Some(score) if name.target.is_some() => {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("suggested, {:.0}% match", score * 100.0))
+                }
+                _ if name.target.is_some() => ui.label("exact match"),
+                _ => ui.label(""),
+            };
+            ui.end_row();
+        }
+    });
+    if changed {
+        app.app_import.report = None;
+    }
+
+    let previewed = app.app_import.report.as_ref().is_some_and(|r| r.dry_run);
+    ui.horizontal(|ui| {
+        if ui.button("Preview").clicked() {
+            import_app_export(app, true);
+        }
+        if ui.add_enabled(previewed, egui::Button::new("Import")).on_disabled_hover_text("preview the import first").clicked() {
+            import_app_export(app, false);
+        }
+    });
+
+    if let Some(report) = &app.app_import.report {
+        render_report(ui, "app_import", report);
+    }
+}
+
+fn render_report(ui: &mut egui::Ui, id: &str, report: &ImportReport) {
+    ui.add_space(5.0);
+    ui.label(report.summary());
+    egui::ScrollArea::vertical().id_salt((id, "report_scroll")).max_height(250.0).show(ui, |ui| {
+        egui::Grid::new((id, "report_grid")).num_columns(3).spacing([12.0, 2.0]).striped(true).show(ui, |ui| {
+            for row in &report.rows {
+                ui.label(format!("line {}", row.line));
+                match &row.status {
+                    RowStatus::Added if report.dry_run => ui.label("new"),
+                    RowStatus::Added => ui.label("added"),
+                    RowStatus::Duplicate => ui.label("duplicate"),
+                    RowStatus::Skipped(reason) => ui.colored_label(ui.visuals().warn_fg_color, reason),
+                };
+                ui.label(&row.summary);
+                ui.end_row();
+            }
+        });
+    });
+}
+
+fn month_first(format: DateFormat) -> bool {
+    format == DateFormat::MonthDayYear
+}
+
+// Weigh-ins default to the bodyweight unit, sets to the lift unit.
+fn default_unit(app: &MyApp, table: CsvTable) -> WeightUnit {
+    match table {
+        CsvTable::ExerciseSets => app.settings.lift_unit,
+        _ => app.settings.bodyweight_unit,
+    }
+}
+
+fn options(app: &MyApp, table: CsvTable, dry_run: bool) -> ImportOptions {
+    ImportOptions {
+        date_format: None,
+        unit: None,
+        default_unit: default_unit(app, table),
+        month_first: month_first(app.settings.date_format),
+        formula: app.settings.e1rm_formula,
+        dry_run,
+    }
+}
+
+fn export_all(app: &mut MyApp) {
+    let dir = PathBuf::from(app.csv_dir.trim());
+    let settings = app.settings.clone();
+    match app.with_csv(|conn| export::export_dir(conn, &dir, &settings)) {
+        Ok(written) => {
+            let rows: usize = written.iter().map(|(_, count)| count).sum();
+            app.push_status(format!("exported {} rows in {} files to {}.", rows, written.len(), dir.display()));
+        }
+        Err(e) => {
+            error!("error exporting csv: {}", e);
+            app.push_status(format!("error exporting csv: {}", e));
+        }
+    }
+}
+
+fn export_one(app: &mut MyApp, table: CsvTable) {
+    let path = PathBuf::from(app.csv_dir.trim()).join(table.file_name());
+    let settings = app.settings.clone();
+    let result = app.with_csv(|conn| {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        export::export_table(conn, table, std::fs::File::create(&path)?, &settings)
+    });
+    match result {
+        Ok(count) => app.push_status(format!("exported {} rows to {}.", count, path.display())),
+        Err(e) => {
+            error!("error exporting {}: {}", table.file_name(), e);
+            app.push_status(format!("error exporting {}: {}", table.file_name(), e));
+        }
+    }
+}
+
+fn load_file(app: &mut MyApp) {
+    let path = PathBuf::from(app.csv_import.path.trim());
+    match CsvSource::read(&path) {
+        Ok(source) => {
+            let state = &mut app.csv_import;
+            state.mapping = import::auto_map(state.table, &source.headers);
+            state.source = Some(source);
+            state.date_format = None;
+            state.unit = None;
+            state.report = None;
+        }
+        Err(e) => {
+            error!("error reading {}: {}", path.display(), e);
+            app.push_status(format!("error reading {}: {}", path.display(), e));
+            app.csv_import.source = None;
+        }
+    }
+}
+
+fn import_file(app: &mut MyApp, dry_run: bool) {
+    let state = app.csv_import.clone();
+    let Some(source) = state.source else { return };
+    let options = ImportOptions { date_format: state.date_format, unit: state.unit, ..options(app, state.table, dry_run) };
+    let mut session_ids = state.session_ids;
+    let result =
+        app.with_csv(|conn| import::import(conn, state.table, &source, &state.mapping, &options, &mut session_ids));
+    app.csv_import.session_ids = session_ids;
+    match result {
+        Ok(report) => {
+            app.push_status(format!("{}.", report.summary()));
+            if !dry_run {
+                reload_after_import(app);
+            }
+            app.csv_import.report = Some(report);
+        }
+        Err(e) => {
+            error!("error importing {}: {}", state.path, e);
+            app.push_status(format!("error importing {}: {}", state.path, e));
+        }
+    }
+}
+
+fn load_app_export(app: &mut MyApp) {
+    let path = PathBuf::from(app.app_import.path.trim());
+    let unit = app.settings.lift_unit;
+    let month_first = month_first(app.settings.date_format);
+    let format = app.app_import.format;
+    let result = CsvSource::read(&path).and_then(|source| {
+        let format = format
+            .or_else(|| AppFormat::detect(&source.headers))
+            .ok_or_else(|| CsvError::Invalid("not a Strong, Hevy or FitNotes export.".to_string()))?;
+        apps::parse(format, &source, unit, month_first)
+    });
+    let result = result.map_err(|e| e.to_string()).and_then(|export| {
+        let names = app.with_db(|conn| apps::match_names(conn, &export))?;
+        Ok((export, names))
+    });
+    let state = &mut app.app_import;
+    state.report = None;
+    match result {
+        Ok((export, names)) => {
+            let review = names.iter().filter(|n| n.needs_review()).count();
+            let status = format!("loaded {} workouts; {} exercise names to review.", export.workouts.len(), review);
+            state.export = Some(export);
+            state.names = names;
+            app.push_status(status);
+        }
+        Err(e) => {
+            state.export = None;
+            state.names.clear();
+            error!("error reading {}: {}", path.display(), e);
+            app.push_status(format!("error reading {}: {}", path.display(), e));
+        }
+    }
+}
+
+fn import_app_export(app: &mut MyApp, dry_run: bool) {
+    let state = app.app_import.clone();
+    let Some(export) = state.export else { return };
+    let formula = app.settings.e1rm_formula;
+    match app.with_csv(|conn| apps::import(conn, &export, &state.names, formula, dry_run)) {
+        Ok(report) => {
+            app.push_status(format!("{}.", report.summary()));
+            if !dry_run {
+                reload_after_import(app);
+            }
+            app.app_import.report = Some(report);
+        }
+        Err(e) => {
+            error!("error importing {}: {}", state.path, e);
+            app.push_status(format!("error importing {}: {}", state.path, e));
+        }
+    }
+}
+
+fn import_folder(app: &mut MyApp, dry_run: bool) {
+    let dir = PathBuf::from(app.csv_dir.trim());
+    // Each file's weights carry their own unit column; this only covers files without one.
+    let options = options(app, CsvTable::WeightLogs, dry_run);
+    match app.with_csv(|conn| import::import_dir(conn, &dir, &options)) {
+        Ok(reports) => {
+            app.push_status(format!("{} {} files from {}.", if dry_run { "previewed" } else { "imported" }, reports.len(), dir.display()));
+            app.csv_dir_reports = reports;
+            if !dry_run {
+                reload_after_import(app);
+            }
+        }
+        Err(e) => {
+            error!("error importing from {}: {}", dir.display(), e);
+            app.push_status(format!("error importing from {}: {}", dir.display(), e));
+        }
+    }
+}
+
+fn reload_after_import(app: &mut MyApp) {
+    app.fetch_active_diet_cycle();
+    app.fetch_recent_weight_logs();
+    app.fetch_diet_cycles();
+    app.fetch_exercises_for_dropdown();
+    app.fetch_energy_data();
+}
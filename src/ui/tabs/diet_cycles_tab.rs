@@ -0,0 +1,216 @@
+use crate::app_state::MyApp;
+use crate::db::{diet_cycles, DietCycle};
+use crate::types::{DietCycleEdit, DietPhase};
+use crate::units::WeightUnit;
+use eframe::egui;
+use egui_extras::DatePickerButton;
+use chrono::NaiveDate;
+use log::error;
+
+enum CycleAction {
+    Edit(usize),
+    SetActive(i64),
+    Reopen(i64),
+}
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+    ui.heading("Diet Cycles");
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        if ui.button("New Cycle").clicked() {
+            app.new_diet_start_date = chrono::Local::now().date_naive().format("%Y-%m-%d").to_string();
+            app.new_diet_planned_end_date = (chrono::Local::now().date_naive() + chrono::Duration::days(90)).format("%Y-%m-%d").to_string();
+            app.show_diet_cycle_popup = true;
+        }
+        if let Some(active_id) = app.active_diet_cycle_id {
+            ui.separator();
+            ui.label("End active cycle on:");
+            ui.add(DatePickerButton::new(&mut app.end_diet_cycle_date).id_salt("end_diet_cycle_date"));
+            if ui.button("End Cycle").clicked() {
+                let end_date = app.end_diet_cycle_date;
+                match app.with_db(|conn| diet_cycles::end(conn, active_id, end_date)) {
+                    Ok(()) => {
+                        app.push_status(format!("diet cycle ended on {}.", end_date.format("%Y-%m-%d")));
+                        app.active_diet_cycle_id = None;
+                        app.recent_weight_logs.clear();
+                        app.fetch_diet_cycles();
+                    }
+                    Err(e) => {
+                        error!("error ending diet cycle: {}", e);
+                        app.push_status(format!("error ending diet cycle: {}", e));
+                    }
+                }
+            }
+        }
+    });
+    ui.add_space(10.0);
+
+    if app.diet_cycles.is_empty() {
+        ui.label("no diet cycles yet.");
+        app.display_status_message(ui);
+        return;
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let unit = app.settings.bodyweight_unit;
+    let date_format = app.settings.date_format;
+    let mut actions = Vec::new();
+    egui::ScrollArea::both().max_height((ui.available_height() - 160.0).max(150.0)).show(ui, |ui| {
+        egui::Grid::new("diet_cycles_grid")
+            .num_columns(10)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for header in ["", "Phase", "Start", "Planned End", "Ended", "Days", "Start Wt", "End Wt", "Rate", "Target"] {
+                    ui.label(egui::RichText::new(header).strong());
+                }
+                ui.label("");
+                ui.end_row();
+
+                for (index, summary) in app.diet_cycles.iter().enumerate() {
+                    let cycle = &summary.cycle;
+                    ui.label(if cycle.is_active { "● active" } else { "" });
+                    ui.label(cycle.phase.to_string());
+                    ui.label(date_format.format_stored(&cycle.start_date));
+                    ui.label(date_format.format_stored(&cycle.planned_end_date));
+                    ui.label(cycle.actual_end_date.as_deref().map_or("-".to_string(), |d| date_format.format_stored(d)));
+                    ui.label(summary.duration_days(today).map_or("-".to_string(), |d| d.to_string()));
+                    ui.label(summary.start_weight_lbs.map_or("-".to_string(), |w| unit.format(w)));
+                    ui.label(summary.end_weight_lbs.map_or("-".to_string(), |w| unit.format(w)));
+                    ui.label(summary.weekly_rate_lbs().map_or("-".to_string(), |r| format_rate(r, unit)));
+                    ui.label(cycle.calorie_target.map_or("-".to_string(), |t| format!("{} kcal", t)));
+                    ui.horizontal(|ui| {
+                        if ui.small_button("Edit").clicked() {
+                            actions.push(CycleAction::Edit(index));
+                        }
+                        if !cycle.is_active && cycle.actual_end_date.is_none() && ui.small_button("Set Active").clicked() {
+                            actions.push(CycleAction::SetActive(cycle.id));
+                        }
+                        if cycle.actual_end_date.is_some() && ui.small_button("Reopen").clicked() {
+                            actions.push(CycleAction::Reopen(cycle.id));
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+    });
+
+    for action in actions {
+        match action {
+            CycleAction::Edit(index) => {
+                let cycle = app.diet_cycles[index].cycle.clone();
+                app.editing_diet_cycle = Some(DietCycleEdit {
+                    id: cycle.id,
+                    phase: cycle.phase,
+                    start_date: cycle.start_date,
+                    planned_end_date: cycle.planned_end_date,
+                    actual_end_date: cycle.actual_end_date.unwrap_or_default(),
+                    notes: cycle.notes.unwrap_or_default(),
+                    calorie_target: cycle.calorie_target.map(|t| t.to_string()).unwrap_or_default(),
+                });
+            }
+            CycleAction::SetActive(id) => match app.with_db(|conn| diet_cycles::set_active(conn, id)) {
+                Ok(()) => {
+                    app.push_status(format!("diet cycle {} is now active.", id));
+                    app.active_diet_cycle_id = Some(id);
+                    app.fetch_recent_weight_logs();
+                    app.fetch_diet_cycles();
+                }
+                Err(e) => app.push_status(format!("error switching diet cycle: {}", e)),
+            },
+            CycleAction::Reopen(id) => match app.with_db(|conn| diet_cycles::reopen(conn, id, today)) {
+                Ok(()) => {
+                    app.push_status(format!("diet cycle {} reopened.", id));
+                    app.active_diet_cycle_id = Some(id);
+                    app.fetch_recent_weight_logs();
+                    app.fetch_diet_cycles();
+                }
+                Err(e) => app.push_status(format!("error reopening diet cycle: {}", e)),
+            },
+        }
+    }
+
+    render_edit_form(app, ui, today);
+    app.display_status_message(ui);
+}
+
+fn render_edit_form(app: &mut MyApp, ui: &mut egui::Ui, today: NaiveDate) {
+    let mut save = false;
+    let mut cancel = false;
+    let Some(edit) = &mut app.editing_diet_cycle else { return };
+
+    ui.add_space(10.0);
+    ui.separator();
+    ui.label(egui::RichText::new(format!("Edit cycle {}", edit.id)).strong());
+    egui::Grid::new("diet_cycle_edit_grid").num_columns(2).spacing([10.0, 4.0]).show(ui, |ui| {
+        ui.label("Phase:");
+        egui::ComboBox::from_id_salt("diet_cycle_edit_phase")
+            .selected_text(edit.phase.to_string())
+            .show_ui(ui, |ui| {
+                for phase in [DietPhase::Bulk, DietPhase::Cut, DietPhase::Maintain] {
+                    ui.selectable_value(&mut edit.phase, phase, phase.to_string());
+                }
+            });
+        ui.end_row();
+        ui.label("Start Date (YYYY-MM-DD):");
+        ui.text_edit_singleline(&mut edit.start_date);
+        ui.end_row();
+        ui.label("Planned End Date:");
+        ui.text_edit_singleline(&mut edit.planned_end_date);
+        ui.end_row();
+        ui.label("Actual End Date (blank if ongoing):");
+        ui.text_edit_singleline(&mut edit.actual_end_date);
+        ui.end_row();
+        ui.label("Calorie Target (kcal, blank for none):");
+        ui.text_edit_singleline(&mut edit.calorie_target);
+        ui.end_row();
+        ui.label("Notes:");
+        ui.text_edit_multiline(&mut edit.notes);
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        save = ui.button("Save").clicked();
+        cancel = ui.button("Cancel").clicked();
+    });
+
+    if save {
+        let calorie_target = match diet_cycles::parse_calorie_target(&edit.calorie_target) {
+            Ok(target) => target,
+            Err(msg) => {
+                app.push_status(msg);
+                return;
+            }
+        };
+        let optional = |text: &str| Some(text.trim().to_string()).filter(|t| !t.is_empty());
+        let cycle = DietCycle {
+            id: edit.id,
+            phase: edit.phase,
+            start_date: edit.start_date.trim().to_string(),
+            planned_end_date: edit.planned_end_date.trim().to_string(),
+            actual_end_date: optional(&edit.actual_end_date),
+            notes: optional(&edit.notes),
+            is_active: false,
+            calorie_target,
+        };
+        match app.with_db(|conn| diet_cycles::update(conn, &cycle, today)) {
+            Ok(()) => {
+                app.push_status(format!("diet cycle {} updated.", cycle.id));
+                app.editing_diet_cycle = None;
+                app.fetch_active_diet_cycle();
+                app.fetch_recent_weight_logs();
+                app.fetch_diet_cycles();
+                app.fetch_energy_data();
+            }
+            Err(e) => app.push_status(format!("error updating diet cycle: {}", e)),
+        }
+    } else if cancel {
+        app.editing_diet_cycle = None;
+    }
+}
+
+// Weekly change; stone is too coarse for this, so it falls back to pounds.
+fn format_rate(rate_lbs: f64, unit: WeightUnit) -> String {
+    let unit = if unit == WeightUnit::Stone { WeightUnit::Lbs } else { unit };
+    format!("{:+.2} {}/wk", unit.convert_lbs(rate_lbs), unit.suffix())
+}
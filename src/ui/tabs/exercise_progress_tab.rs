@@ -1,8 +1,211 @@
 use crate::app_state::MyApp;
+use crate::db::ExerciseSet;
+use crate::e1rm::E1rmFormula;
+use crate::settings::Settings;
+use crate::smoothing::SmoothingMethod;
+use crate::types::ExerciseMetric;
+use crate::ui::{exercise_picker, plot_helpers};
 use eframe::egui;
+use egui_plot::{Legend, Line, Plot, Points};
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
 
-pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
     ui.heading("Exercise Progress");
-    // todo
+    ui.add_space(10.0);
+
+    if app.exercise_library.is_empty() {
+        ui.label("no exercises yet. log a workout first.");
+        app.display_status_message(ui);
+        return;
+    }
+
+    let mut exercise_changed = false;
+    let mut changed = false;
+    exercise_picker::filter_row(ui, "exercise_progress_filter", &mut app.exercise_progress_filter);
+    ui.horizontal(|ui| {
+        ui.label("Exercise:");
+        exercise_changed |= exercise_picker::combo(
+            ui,
+            "exercise_progress_exercise_combo",
+            &app.exercise_library,
+            &app.exercise_progress_filter,
+            &mut app.exercise_progress_selected_exercise_id,
+            None,
+        );
+
+        ui.label("Metric:");
+        egui::ComboBox::from_id_salt("exercise_progress_metric_combo")
+            .selected_text(app.selected_exercise_metric.label())
+            .show_ui(ui, |ui| {
+                for metric in ExerciseMetric::ALL {
+                    changed |= ui.selectable_value(&mut app.selected_exercise_metric, metric, metric.label()).changed();
+                }
+            });
+        if app.selected_exercise_metric == ExerciseMetric::EstimatedOneRepMax {
+            // Same setting as the Settings tab, so the choice sticks.
+            let mut selected = app.settings.e1rm_formula;
+            egui::ComboBox::from_id_salt("exercise_progress_e1rm_combo")
+                .selected_text(selected.label())
+                .show_ui(ui, |ui| {
+                    for formula in E1rmFormula::ALL {
+                        ui.selectable_value(&mut selected, formula, formula.label());
+                    }
+                });
+            if selected != app.settings.e1rm_formula {
+                app.apply_settings(ctx, Settings { e1rm_formula: selected, ..app.settings.clone() });
+            }
+        }
+        if app.selected_exercise_metric == ExerciseMetric::BestRepsAtWeight {
+            // Edited in the lift unit but kept in pounds like the stored sets.
+            let unit = app.settings.lift_unit;
+            let mut target = unit.convert_lbs(app.exercise_metric_weight_lbs);
+            if ui
+                .add(
+                    egui::DragValue::new(&mut target)
+                        .range(0.0..=unit.convert_lbs(2000.0))
+                        .speed(unit.convert_lbs(2.5))
+                        .max_decimals(1)
+                        .suffix(format!(" {}", unit.suffix())),
+                )
+                .changed()
+            {
+                app.exercise_metric_weight_lbs = unit.to_lbs(target);
+                changed = true;
+            }
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut app.exercise_progress_show_raw, "Show raw");
+        ui.label("Smoothing:");
+        egui::ComboBox::from_id_salt("exercise_smoothing_combo")
+            .selected_text(app.exercise_smoothing.to_string())
+            .show_ui(ui, |ui| {
+                changed |= ui.selectable_value(&mut app.exercise_smoothing, SmoothingMethod::Ema, "EMA").changed();
+                changed |= ui.selectable_value(&mut app.exercise_smoothing, SmoothingMethod::Sma, "SMA").changed();
+            });
+        changed |= ui
+            .add(egui::DragValue::new(&mut app.exercise_smoothing_window).range(1..=120).suffix(" days"))
+            .changed();
+    });
+
+    if exercise_changed {
+        app.apply_default_metric_for_selected_exercise();
+    }
+    if exercise_changed || changed {
+        app.fetch_exercise_progress_data();
+    }
+
+    ui.small("drag to pan, scroll to zoom, double-click or press R to reset the view.");
+    ui.add_space(5.0);
+
+    if app.exercise_progress_selected_exercise_id.is_none() {
+        ui.label("pick an exercise to see its progress.");
+        app.display_status_message(ui);
+        return;
+    }
+    if app.exercise_progress_data.is_empty() {
+        ui.label("no sessions to show for this exercise and metric.");
+        app.display_status_message(ui);
+        return;
+    }
+
+    let metric = app.selected_exercise_metric;
+    let mut plot = Plot::new("exercise_progress_plot")
+        .legend(Legend::default())
+        .height((ui.available_height() - 30.0).max(200.0))
+        .x_axis_formatter(plot_helpers::format_date_axis(app.settings.date_format))
+        .label_formatter(plot_helpers::format_point_label(app.settings.date_format))
+        .y_axis_label(metric.unit(app.settings.lift_unit));
+    if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::R)) {
+        plot = plot.reset();
+    }
+
+    plot.show(ui, |plot_ui| {
+        if app.exercise_progress_show_raw {
+            plot_ui.points(
+                Points::new(app.exercise_progress_data.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                    .radius(3.0)
+                    .name(metric.label()),
+            );
+        }
+        plot_ui.line(
+            Line::new(app.smoothed_exercise_progress_data.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                .width(2.0)
+                .name(format!("Trend ({} {}d)", app.exercise_smoothing, app.exercise_smoothing_window)),
+        );
+    });
+
     app.display_status_message(ui);
-} 
\ No newline at end of file
+}
+
+// Reduces every set of one exercise to a single value per session date.
+// Dates with no qualifying set (e.g. nothing heavy enough for
+// `BestRepsAtWeight`, or no set the e1RM formula can estimate) are left out.
+// Output is sorted by date.
+pub(crate) fn aggregate_metric(
+    sets: &[(NaiveDate, ExerciseSet)],
+    metric: ExerciseMetric,
+    target_weight_lbs: f64,
+    formula: E1rmFormula,
+) -> Vec<(f64, f64)> {
+    let mut by_date: BTreeMap<NaiveDate, Vec<&ExerciseSet>> = BTreeMap::new();
+    for (date, set) in sets {
+        by_date.entry(*date).or_default().push(set);
+    }
+
+    by_date
+        .into_iter()
+        .filter_map(|(date, sets)| {
+            let value = match metric {
+                ExerciseMetric::TopSetWeight => sets.iter().map(|s| s.weight_lbs).reduce(f64::max),
+                ExerciseMetric::EstimatedOneRepMax => sets
+                    .iter()
+                    .filter_map(|s| formula.estimate(s.weight_lbs, s.reps, s.rpe))
+                    .reduce(f64::max),
+                ExerciseMetric::TotalVolume => Some(sets.iter().map(|s| s.weight_lbs * s.reps as f64).sum()),
+                ExerciseMetric::TotalReps => Some(sets.iter().map(|s| s.reps as f64).sum()),
+                ExerciseMetric::BestRepsAtWeight => sets
+                    .iter()
+                    .filter(|s| s.weight_lbs >= target_weight_lbs)
+                    .map(|s| s.reps as f64)
+                    .reduce(f64::max),
+            };
+            value.map(|v| (plot_helpers::date_to_x(date), v))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(day: u32, reps: i64, weight_lbs: f64) -> (NaiveDate, ExerciseSet) {
+        let set = ExerciseSet {
+            id: 0,
+            workout_session_id: day as i64,
+            exercise_id: 1,
+            set_order: 1,
+            reps,
+            weight_lbs,
+            rpe: None,
+            notes: None,
+        };
+        (NaiveDate::from_ymd_opt(2025, 1, day).unwrap(), set)
+    }
+
+    #[test]
+    fn aggregates_per_session_date() {
+        let sets = [set(1, 5, 200.0), set(1, 3, 220.0), set(3, 8, 185.0)];
+        let values = |metric| aggregate_metric(&sets, metric, 200.0, E1rmFormula::Epley).into_iter().map(|p| p.1).collect::<Vec<_>>();
+
+        assert_eq!(values(ExerciseMetric::TopSetWeight), vec![220.0, 185.0]);
+        assert_eq!(values(ExerciseMetric::TotalVolume), vec![1660.0, 1480.0]);
+        assert_eq!(values(ExerciseMetric::TotalReps), vec![8.0, 8.0]);
+        // Session on the 3rd has nothing at 200+ lbs, so it is skipped.
+        assert_eq!(values(ExerciseMetric::BestRepsAtWeight), vec![5.0]);
+        let e1rm = values(ExerciseMetric::EstimatedOneRepMax);
+        assert!((e1rm[0] - 242.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,449 @@
+use crate::app_state::MyApp;
+use crate::catalog;
+use crate::timer;
+use crate::db::exercises::{self, Exercise, ExerciseFilter};
+use crate::types::{Equipment, ExerciseCategory, ExerciseMetric, MovementPattern, MuscleGroup};
+use crate::ui::exercise_picker;
+use eframe::egui;
+use log::{error, info};
+
+enum LibraryAction {
+    Edit(usize),
+    Archive(i64, bool),
+}
+
+enum AliasAction {
+    Add(String),
+    Remove(String),
+}
+
+// How a muscle figures in the exercise being edited.
+#[derive(PartialEq, Clone, Copy)]
+enum MuscleRole {
+    None,
+    Primary,
+    Secondary,
+}
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+    ui.heading("Exercises");
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        if ui.button("New Exercise").clicked() {
+            start_editing(app, Exercise { default_metric: Some(ExerciseMetric::default()), ..Exercise::default() });
+        }
+        if ui.button("Find Duplicates").on_hover_text("list exercises whose names look alike").clicked() {
+            find_duplicates(app);
+        }
+        let import = ui
+            .button("Import Catalog")
+            .on_hover_text("add the built-in exercises and apply catalog updates; fields you've edited are kept");
+        if import.clicked() {
+            import_catalog(app);
+        }
+    });
+    if app.editing_exercise.is_some() {
+        render_edit_form(app, ui);
+    }
+    render_pending_merge(app, ui);
+    render_duplicates(app, ui);
+    ui.add_space(5.0);
+    exercise_picker::filter_row(ui, "library_filter", &mut app.library_filter);
+    ui.add_space(5.0);
+
+    let shown: Vec<usize> =
+        (0..app.exercise_library.len()).filter(|idx| app.library_filter.matches(&app.exercise_library[*idx])).collect();
+    if shown.is_empty() {
+        ui.label(if app.exercise_library.is_empty() { "no exercises yet." } else { "no exercises match the filter." });
+        app.display_status_message(ui);
+        return;
+    }
+
+    let mut actions = Vec::new();
+    egui::ScrollArea::both().max_height((ui.available_height() - 40.0).max(150.0)).show(ui, |ui| {
+        egui::Grid::new("exercise_library_grid").num_columns(6).spacing([16.0, 4.0]).striped(true).show(ui, |ui| {
+            for header in ["Name", "Category", "Muscles", "Equipment", "Movement", ""] {
+                ui.label(egui::RichText::new(header).strong());
+            }
+            ui.end_row();
+
+            for idx in shown {
+                let exercise = &app.exercise_library[idx];
+                let name = ui.label(exercise_picker::display_name(exercise));
+                let hover = match (&exercise.description, exercise.aliases.is_empty()) {
+                    (Some(description), false) => Some(format!("{}\nalso known as {}", description, exercise.aliases.join(", "))),
+                    (None, false) => Some(format!("also known as {}", exercise.aliases.join(", "))),
+                    (description, true) => description.clone(),
+                };
+                if let Some(hover) = hover {
+                    name.on_hover_text(hover);
+                }
+                ui.label(exercise.category.map_or("-", |c| c.label()));
+                ui.label(muscles_label(exercise));
+                ui.label(exercise.equipment.map_or("-", |e| e.label()));
+                ui.label(exercise.movement_pattern.map_or("-", |p| p.label()));
+                ui.horizontal(|ui| {
+                    if ui.small_button("Edit").clicked() {
+                        actions.push(LibraryAction::Edit(idx));
+                    }
+                    let (label, hint) = if exercise.is_archived {
+                        ("Restore", "show it in exercise pickers again")
+                    } else {
+                        ("Archive", "hide it from exercise pickers; its history is kept")
+                    };
+                    if ui.small_button(label).on_hover_text(hint).clicked() {
+                        actions.push(LibraryAction::Archive(exercise.id, !exercise.is_archived));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    });
+
+    for action in actions {
+        match action {
+            LibraryAction::Edit(idx) => start_editing(app, app.exercise_library[idx].clone()),
+            LibraryAction::Archive(id, archived) => match app.with_db(|conn| exercises::set_archived(conn, id, archived)) {
+                Ok(()) => {
+                    app.push_status(if archived { "exercise archived." } else { "exercise restored." });
+                    app.fetch_exercises_for_dropdown();
+                }
+                Err(e) => {
+                    error!("error archiving exercise: {}", e);
+                    app.push_status(format!("error archiving exercise: {}", e));
+                }
+            },
+        }
+    }
+
+    app.display_status_message(ui);
+}
+
+fn start_editing(app: &mut MyApp, exercise: Exercise) {
+    app.editing_exercise = Some(exercise);
+    app.new_alias.clear();
+    app.merge_into = None;
+}
+
+// "Chest; also Shoulders, Triceps".
+fn muscles_label(exercise: &Exercise) -> String {
+    let join = |muscles: &[MuscleGroup]| muscles.iter().map(|m| m.label()).collect::<Vec<_>>().join(", ");
+    match (exercise.primary_muscles.is_empty(), exercise.secondary_muscles.is_empty()) {
+        (true, true) => "-".to_string(),
+        (false, true) => join(&exercise.primary_muscles),
+        (true, false) => format!("also {}", join(&exercise.secondary_muscles)),
+        (false, false) => format!("{}; also {}", join(&exercise.primary_muscles), join(&exercise.secondary_muscles)),
+    }
+}
+
+fn render_edit_form(app: &mut MyApp, ui: &mut egui::Ui) {
+    let mut save = false;
+    let mut cancel = false;
+    let mut alias_action = None;
+    let Some(draft) = app.editing_exercise.as_mut() else { return };
+    ui.add_space(5.0);
+    ui.group(|ui| {
+        ui.label(egui::RichText::new(if draft.id == 0 { "New exercise" } else { "Edit exercise" }).strong());
+        egui::Grid::new("exercise_edit_grid").num_columns(2).spacing([12.0, 4.0]).show(ui, |ui| {
+            ui.label("Name:");
+            ui.add(egui::TextEdit::singleline(&mut draft.name).desired_width(240.0));
+            ui.end_row();
+
+            ui.label("Description:");
+            ui.add(
+                egui::TextEdit::multiline(draft.description.get_or_insert_with(String::new))
+                    .desired_rows(2)
+                    .desired_width(240.0),
+            );
+            ui.end_row();
+
+            ui.label("Chart by default:");
+            exercise_picker::option_combo(ui, "exercise_edit_metric", "-", &mut draft.default_metric, &ExerciseMetric::ALL, |m| {
+                m.label()
+            });
+            ui.end_row();
+
+            ui.label("Category:");
+            exercise_picker::option_combo(ui, "exercise_edit_category", "-", &mut draft.category, &ExerciseCategory::ALL, |c| {
+                c.label()
+            });
+            ui.end_row();
+
+            ui.label("Equipment:");
+            exercise_picker::option_combo(ui, "exercise_edit_equipment", "-", &mut draft.equipment, &Equipment::ALL, |e| e.label());
+            ui.end_row();
+
+            ui.label("Movement:");
+            exercise_picker::option_combo(
+                ui,
+                "exercise_edit_pattern",
+                "-",
+                &mut draft.movement_pattern,
+                &MovementPattern::ALL,
+                |p| p.label(),
+            );
+            ui.end_row();
+
+            ui.label("Rest between sets:");
+            let mut rest = draft.rest_secs.unwrap_or(0);
+            ui.add(egui::DragValue::new(&mut rest).range(0..=timer::MAX_REST_SECS).suffix(" s"))
+                .on_hover_text("0 uses the default from settings");
+            draft.rest_secs = (rest > 0).then_some(rest);
+            ui.end_row();
+        });
+
+        ui.label("Muscles:");
+        egui::Grid::new("exercise_edit_muscles").num_columns(4).spacing([12.0, 2.0]).show(ui, |ui| {
+            for muscle in MuscleGroup::ALL {
+                let before = if draft.primary_muscles.contains(&muscle) {
+                    MuscleRole::Primary
+                } else if draft.secondary_muscles.contains(&muscle) {
+                    MuscleRole::Secondary
+                } else {
+                    MuscleRole::None
+                };
+                let mut role = before;
+                ui.label(muscle.label());
+                ui.radio_value(&mut role, MuscleRole::None, "-");
+                ui.radio_value(&mut role, MuscleRole::Primary, "primary");
+                ui.radio_value(&mut role, MuscleRole::Secondary, "secondary");
+                ui.end_row();
+                if role != before {
+                    draft.primary_muscles.retain(|m| *m != muscle);
+                    draft.secondary_muscles.retain(|m| *m != muscle);
+                    match role {
+                        MuscleRole::Primary => draft.primary_muscles.push(muscle),
+                        MuscleRole::Secondary => draft.secondary_muscles.push(muscle),
+                        MuscleRole::None => {}
+                    }
+                }
+            }
+        });
+
+        // Aliases and merging act on the saved exercise straight away.
+        if draft.id != 0 {
+            ui.add_space(4.0);
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Aliases:");
+                if draft.aliases.is_empty() {
+                    ui.label("none");
+                }
+                for alias in &draft.aliases {
+                    ui.label(alias);
+                    if ui.small_button("Remove").clicked() {
+                        alias_action = Some(AliasAction::Remove(alias.clone()));
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut app.new_alias).desired_width(160.0).hint_text("another spelling"));
+                if ui.add_enabled(!app.new_alias.trim().is_empty(), egui::Button::new("Add Alias")).clicked() {
+                    alias_action = Some(AliasAction::Add(app.new_alias.clone()));
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label("Merge into:");
+                let others: Vec<Exercise> = app.exercise_library.iter().filter(|e| e.id != draft.id).cloned().collect();
+                let any = ExerciseFilter { include_archived: true, ..ExerciseFilter::default() };
+                exercise_picker::combo(ui, "exercise_merge_into", &others, &any, &mut app.merge_into, Some("-"));
+                let merge = ui
+                    .add_enabled(app.merge_into.is_some(), egui::Button::new("Merge"))
+                    .on_hover_text("move this exercise's history to the other one and delete it");
+                if merge.clicked() {
+                    app.pending_merge = app.merge_into.map(|into| (draft.id, into));
+                }
+            });
+        }
+
+        ui.horizontal(|ui| {
+            save = ui.button("Save").clicked();
+            cancel = ui.button("Cancel").clicked();
+        });
+    });
+
+    if let Some(action) = alias_action {
+        let id = draft.id;
+        let result = app.with_db(|conn| match &action {
+            AliasAction::Add(alias) => exercises::add_alias(conn, id, alias),
+            AliasAction::Remove(alias) => exercises::remove_alias(conn, id, alias),
+        });
+        match result {
+            Ok(()) => {
+                app.fetch_exercises_for_dropdown();
+                // Keep the rest of the draft as typed; only the aliases are reloaded.
+                let aliases = app.exercise_library.iter().find(|e| e.id == id).map(|e| e.aliases.clone());
+                if let (Some(draft), Some(aliases)) = (app.editing_exercise.as_mut(), aliases) {
+                    draft.aliases = aliases;
+                }
+                if let AliasAction::Add(_) = action {
+                    app.new_alias.clear();
+                }
+            }
+            Err(e) => {
+                error!("error updating aliases: {}", e);
+                app.push_status(format!("error updating aliases: {}", e));
+            }
+        }
+        return;
+    }
+
+    if cancel {
+        app.editing_exercise = None;
+    } else if save {
+        let draft = draft.clone();
+        match app.with_db(|conn| exercises::save(conn, &draft)) {
+            Ok(_) => {
+                app.push_status(format!("exercise {} saved.", draft.name.trim()));
+                app.editing_exercise = None;
+                app.fetch_exercises_for_dropdown();
+            }
+            Err(e) => {
+                error!("error saving exercise: {}", e);
+                app.push_status(format!("error saving exercise: {}", e));
+            }
+        }
+    }
+}
+
+fn exercise_name(app: &MyApp, id: i64) -> String {
+    app.exercise_library.iter().find(|e| e.id == id).map_or_else(|| format!("#{}", id), |e| e.name.clone())
+}
+
+fn render_pending_merge(app: &mut MyApp, ui: &mut egui::Ui) {
+    let Some((from, into)) = app.pending_merge else { return };
+    let sets = app.with_db(|conn| exercises::set_count(conn, from)).unwrap_or(0);
+    ui.colored_label(
+        ui.visuals().warn_fg_color,
+        format!(
+            "merge {} into {}? its {} set(s) move over, its name becomes an alias and it is deleted.",
+            exercise_name(app, from),
+            exercise_name(app, into),
+            sets
+        ),
+    );
+    ui.horizontal(|ui| {
+        if ui.button("Confirm Merge").clicked() {
+            app.pending_merge = None;
+            merge(app, from, into);
+        }
+        if ui.button("Cancel").clicked() {
+            app.pending_merge = None;
+        }
+    });
+}
+
+fn merge(app: &mut MyApp, from: i64, into: i64) {
+    let (from_name, into_name) = (exercise_name(app, from), exercise_name(app, into));
+    let formula = app.settings.e1rm_formula;
+    match app.with_db(|conn| exercises::merge(conn, from, into, formula)) {
+        Ok(moved) => {
+            app.push_status(format!("merged {} into {}: {} set(s) moved.", from_name, into_name, moved));
+            if app.editing_exercise.as_ref().is_some_and(|e| e.id == from) {
+                app.editing_exercise = None;
+            }
+            app.fetch_exercises_for_dropdown();
+            app.fetch_personal_records();
+            if app.duplicate_suggestions.is_some() {
+                find_duplicates(app);
+            }
+        }
+        Err(e) => {
+            error!("error merging {} into {}: {}", from_name, into_name, e);
+            app.push_status(format!("error merging exercises: {}", e));
+        }
+    }
+}
+
+fn find_duplicates(app: &mut MyApp) {
+    match app.with_db(|conn| exercises::suggest_duplicates(conn)) {
+        Ok(suggestions) => {
+            if suggestions.is_empty() {
+                app.push_status("no likely duplicates found.");
+            }
+            app.duplicate_suggestions = Some(suggestions);
+        }
+        Err(e) => {
+            error!("error finding duplicate exercises: {}", e);
+            app.push_status(format!("error finding duplicate exercises: {}", e));
+        }
+    }
+}
+
+// Likely duplicates, each with the exercise holding more history offered as
+// the one to keep.
+fn render_duplicates(app: &mut MyApp, ui: &mut egui::Ui) {
+    let Some(suggestions) = &app.duplicate_suggestions else { return };
+    let mut merge = None;
+    let mut dismiss = None;
+    let mut close = false;
+    ui.add_space(5.0);
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            ui.label(egui::RichText::new("Likely duplicates").strong());
+            close = ui.small_button("Close").clicked();
+        });
+        if suggestions.is_empty() {
+            ui.label("none found.");
+            return;
+        }
+        egui::Grid::new("exercise_duplicates_grid").num_columns(4).spacing([16.0, 4.0]).striped(true).show(ui, |ui| {
+            for header in ["Merge", "Into", "Similarity", ""] {
+                ui.label(egui::RichText::new(header).strong());
+            }
+            ui.end_row();
+            for (idx, suggestion) in suggestions.iter().enumerate() {
+                ui.label(format!("{} ({} sets)", suggestion.merge.name, suggestion.merge_sets));
+                ui.label(format!("{} ({} sets)", suggestion.keep.name, suggestion.keep_sets));
+                ui.label(format!("{:.0}%", suggestion.score * 100.0));
+                ui.horizontal(|ui| {
+                    if ui.small_button("Merge").clicked() {
+                        merge = Some((suggestion.merge.id, suggestion.keep.id));
+                    }
+                    if ui.small_button("Swap").on_hover_text("keep the other one instead").clicked() {
+                        merge = Some((suggestion.keep.id, suggestion.merge.id));
+                    }
+                    if ui.small_button("Dismiss").clicked() {
+                        dismiss = Some(idx);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    });
+
+    if close {
+        app.duplicate_suggestions = None;
+    } else if let Some(idx) = dismiss
+        && let Some(suggestions) = app.duplicate_suggestions.as_mut()
+    {
+        suggestions.remove(idx);
+    }
+    if merge.is_some() {
+        app.pending_merge = merge;
+    }
+}
+
+fn import_catalog(app: &mut MyApp) {
+    let entries = match catalog::bundled() {
+        Ok(entries) => entries,
+        Err(e) => {
+            error!("error reading the exercise catalog: {}", e);
+            app.push_status(format!("error reading the exercise catalog: {}", e));
+            return;
+        }
+    };
+    match app.with_db(|conn| catalog::sync(conn, &entries)) {
+        Ok(report) => {
+            for note in &report.notes {
+                info!("{}", note);
+            }
+            app.push_status(report.summary());
+            app.fetch_exercises_for_dropdown();
+        }
+        Err(e) => {
+            error!("error importing the exercise catalog: {}", e);
+            app.push_status(format!("error importing the exercise catalog: {}", e));
+        }
+    }
+}
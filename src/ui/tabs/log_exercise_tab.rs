@@ -1,6 +1,27 @@
 use crate::app_state::MyApp;
+use crate::db::templates::{self, NewTemplateExercise, WorkoutTemplate};
+use crate::db::workouts::{self, NewExercise, NewSet};
+use crate::db::{exercises, ExerciseSet};
+use crate::progression::{self, ProgressionConfig};
+use crate::types::{ExerciseLogEntry, SetEntry, SetTarget};
+use crate::ui::exercise_picker;
+use crate::units::{self, WeightUnit};
 use eframe::egui;
 use egui_extras::DatePickerButton;
+use std::time::Instant;
+use log::error;
+
+const MAX_NAME_SUGGESTIONS: usize = 6;
+
+// Changes requested while drawing the list; applied after the loop so we don't
+// mutate `current_exercises_log` while iterating it.
+enum ListAction {
+    RemoveExercise(usize),
+    AddSet(usize),
+    CopyLastSet(usize),
+    RemoveSet(usize, usize),
+    ApplySuggestion(usize),
+}
 
 pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
     ui.heading("Log Exercise");
@@ -8,13 +29,548 @@ pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
 
     ui.horizontal(|ui| {
         ui.label("Workout Date:");
-        ui.add(DatePickerButton::new(&mut app.log_exercise_date));
+        if ui.add(DatePickerButton::new(&mut app.log_exercise_date)).changed() {
+            // A new workout is underway; don't offer the old one as a template.
+            app.last_logged_workout.clear();
+        }
     });
     ui.label(format!("selected date: {}", app.log_exercise_date.format("%Y-%m-%d")));
+    ui.add_space(5.0);
+    render_template_picker(app, ui);
+    ui.add_space(10.0);
+
+    let mut actions = Vec::new();
+    egui::ScrollArea::vertical().max_height((ui.available_height() - 120.0).max(120.0)).show(ui, |ui| {
+        for (exercise_idx, entry) in app.current_exercises_log.iter_mut().enumerate() {
+            ui.push_id(exercise_idx, |ui| {
+                ui.group(|ui| {
+                    render_exercise_entry(ui, exercise_idx, entry, &app.all_exercises_for_dropdown, app.settings.lift_unit, &mut actions);
+                });
+            });
+            ui.add_space(5.0);
+        }
+    });
+
+    for action in actions {
+        match action {
+            ListAction::RemoveExercise(idx) => {
+                app.current_exercises_log.remove(idx);
+            }
+            ListAction::AddSet(idx) => {
+                app.current_exercises_log[idx].sets.push(SetEntry::default());
+                start_rest(app, idx);
+            }
+            ListAction::CopyLastSet(idx) => {
+                let sets = &mut app.current_exercises_log[idx].sets;
+                let copy = sets.last().cloned().unwrap_or_default();
+                sets.push(copy);
+                start_rest(app, idx);
+            }
+            ListAction::RemoveSet(idx, set_idx) => {
+                app.current_exercises_log[idx].sets.remove(set_idx);
+            }
+            ListAction::ApplySuggestion(idx) => {
+                let unit = app.settings.lift_unit;
+                apply_suggestion(&mut app.current_exercises_log[idx], unit);
+            }
+        }
+    }
+    refresh_suggestions(app);
+
+    if ui.button("Add Exercise").clicked() {
+        app.current_exercises_log.push(ExerciseLogEntry::default());
+        app.last_logged_workout.clear();
+    }
+    render_library_picker(app, ui);
 
-    // todo: dynamic exercise list (current_exercises_log)
-    // todo: "log workout" button
+    ui.add_space(10.0);
+    ui.horizontal(|ui| {
+        ui.label("Workout Notes:");
+        ui.add(egui::TextEdit::singleline(&mut app.workout_notes).desired_width(250.0));
+    });
+    ui.add_space(5.0);
+
+    ui.horizontal(|ui| {
+        if ui.button("Log Workout").clicked() {
+            log_workout(app);
+        }
+        if ui.button("Clear").clicked() {
+            app.current_exercises_log.clear();
+            app.workout_notes.clear();
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("Template Name:");
+        ui.add(egui::TextEdit::singleline(&mut app.new_template_name).hint_text("e.g. Push Day").desired_width(150.0));
+        if ui
+            .button("Save as Template")
+            .on_hover_text("save the exercises above, or the workout you just logged, as a reusable routine")
+            .clicked()
+        {
+            save_as_template(app);
+        }
+    });
 
     ui.add_space(10.0);
     app.display_status_message(ui);
 }
+
+fn render_exercise_entry(
+    ui: &mut egui::Ui,
+    exercise_idx: usize,
+    entry: &mut ExerciseLogEntry,
+    all_exercises: &[(i64, String)],
+    unit: WeightUnit,
+    actions: &mut Vec<ListAction>,
+) {
+    ui.horizontal(|ui| {
+        ui.label("Exercise:");
+        ui.add(egui::TextEdit::singleline(&mut entry.exercise_name).hint_text("e.g. Bench Press").desired_width(200.0));
+        if ui.button("Remove Exercise").clicked() {
+            actions.push(ListAction::RemoveExercise(exercise_idx));
+        }
+    });
+
+    if let Some(target) = &entry.target {
+        ui.small(target.label());
+    }
+    if let Some(suggestion) = &entry.suggestion {
+        ui.horizontal_wrapped(|ui| {
+            let text = format!("suggested: {} x {}", unit.format(suggestion.weight_lbs), suggestion.reps);
+            let text = if suggestion.deload {
+                egui::RichText::new(text).color(egui::Color32::from_rgb(230, 160, 60))
+            } else {
+                egui::RichText::new(text).color(egui::Color32::LIGHT_GREEN)
+            };
+            ui.label(text);
+            if ui.small_button("Use").on_hover_text("fill every set with the suggestion").clicked() {
+                actions.push(ListAction::ApplySuggestion(exercise_idx));
+            }
+            ui.small(&suggestion.explanation);
+        });
+    }
+
+    // Autocomplete against known exercises; unknown names are created on log.
+    let typed = entry.exercise_name.trim().to_lowercase();
+    if !typed.is_empty() && !all_exercises.iter().any(|(_, name)| name.to_lowercase() == typed) {
+        let suggestions: Vec<&String> = all_exercises
+            .iter()
+            .map(|(_, name)| name)
+            .filter(|name| name.to_lowercase().contains(&typed))
+            .take(MAX_NAME_SUGGESTIONS)
+            .collect();
+        if suggestions.is_empty() {
+            ui.small("new exercise — it will be added when the workout is logged.");
+        } else {
+            ui.horizontal_wrapped(|ui| {
+                for name in suggestions {
+                    if ui.small_button(name).clicked() {
+                        entry.exercise_name = name.clone();
+                    }
+                }
+            });
+        }
+    }
+
+    egui::Grid::new("exercise_sets_grid")
+        .num_columns(6)
+        .spacing([8.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("Set");
+            ui.label("Reps");
+            ui.label(format!("Weight ({})", unit.suffix()));
+            ui.label("RPE");
+            ui.label("Notes");
+            ui.label("");
+            ui.end_row();
+
+            let reps_hint = entry.target.map(|t| t.reps_label()).unwrap_or_default();
+            let rpe_hint = entry.target.and_then(|t| t.rpe).map(|r| r.to_string()).unwrap_or_default();
+            for (set_idx, set) in entry.sets.iter_mut().enumerate() {
+                ui.label(format!("{}", set_idx + 1));
+                ui.add(egui::TextEdit::singleline(&mut set.reps).hint_text(&reps_hint).desired_width(40.0));
+                ui.add(egui::TextEdit::singleline(&mut set.weight).desired_width(60.0));
+                ui.add(egui::TextEdit::singleline(&mut set.rpe).hint_text(&rpe_hint).desired_width(40.0));
+                ui.add(egui::TextEdit::singleline(&mut set.notes).desired_width(120.0));
+                if ui.small_button("Remove").clicked() {
+                    actions.push(ListAction::RemoveSet(exercise_idx, set_idx));
+                }
+                ui.end_row();
+            }
+        });
+
+    ui.horizontal(|ui| {
+        if ui.button("Add Set").clicked() {
+            actions.push(ListAction::AddSet(exercise_idx));
+        }
+        if ui.button("Copy Previous Set").clicked() {
+            actions.push(ListAction::CopyLastSet(exercise_idx));
+        }
+    });
+}
+
+// Adding a set means the previous one is done, so the rest starts now.
+fn start_rest(app: &mut MyApp, idx: usize) {
+    if app.settings.auto_start_rest {
+        let name = app.current_exercises_log[idx].exercise_name.clone();
+        app.start_rest_timer(&name);
+    }
+}
+
+// Adds an exercise chosen from the library, narrowed by its attributes.
+fn render_library_picker(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.collapsing("Add from library", |ui| {
+        exercise_picker::filter_row(ui, "log_exercise_filter", &mut app.log_exercise_filter);
+        ui.horizontal(|ui| {
+            exercise_picker::combo(
+                ui,
+                "log_exercise_library_combo",
+                &app.exercise_library,
+                &app.log_exercise_filter,
+                &mut app.log_exercise_pick,
+                None,
+            );
+            let picked = app.log_exercise_pick.and_then(|id| app.exercise_library.iter().find(|e| e.id == id));
+            if ui.add_enabled(picked.is_some(), egui::Button::new("Add")).clicked()
+                && let Some(exercise) = picked
+            {
+                let entry = ExerciseLogEntry { exercise_name: exercise.name.clone(), ..ExerciseLogEntry::default() };
+                app.current_exercises_log.push(entry);
+                app.last_logged_workout.clear();
+                app.log_exercise_pick = None;
+            }
+        });
+    });
+}
+
+fn log_workout(app: &mut MyApp) {
+    let parsed = match parse_workout(&app.current_exercises_log, app.settings.lift_unit) {
+        Ok(parsed) => parsed,
+        Err(msg) => {
+            app.console_messages.push(format!("[STATUS] {}\n", msg));
+            app.last_status_time = Instant::now();
+            return;
+        }
+    };
+
+    let notes = app.workout_notes.trim();
+    let notes = if notes.is_empty() { None } else { Some(notes) };
+    let (date, formula) = (app.log_exercise_date, app.settings.e1rm_formula);
+    let result = app
+        .with_db(|conn| workouts::insert(conn, date, notes, &parsed, formula))
+        .map_err(|e| format!("error logging workout: {}", e));
+
+    match result {
+        Ok((session, new_records)) => {
+            let set_count: usize = parsed.iter().map(|e| e.sets.len()).sum();
+            app.console_messages.push(format!(
+                "[STATUS] workout logged for {}: {} exercise(s), {} set(s).\n",
+                session.session_date,
+                parsed.len(),
+                set_count
+            ));
+            for record in &new_records {
+                app.console_messages.push(format!("[PR] {}\n", record.announcement(app.settings.lift_unit)));
+            }
+            if !new_records.is_empty() {
+                app.status_message = format!("{} new personal record(s)!", new_records.len());
+                app.fetch_personal_records();
+            }
+            app.last_logged_workout = parsed;
+            app.current_exercises_log.clear();
+            app.workout_notes.clear();
+            app.fetch_exercises_for_dropdown();
+        }
+        Err(msg) => {
+            error!("{}", msg);
+            app.console_messages.push(format!("[STATUS] {}\n", msg));
+        }
+    }
+    app.last_status_time = Instant::now();
+}
+
+// Works out a suggestion for every entry whose name changed since the last
+// one. Only known exercises have history to go on.
+fn refresh_suggestions(app: &mut MyApp) {
+    let config = ProgressionConfig {
+        scheme: app.settings.progression_scheme,
+        increment_lbs: app.settings.progression_increment_lbs,
+        formula: app.settings.e1rm_formula,
+        unit: app.settings.lift_unit,
+    };
+    for idx in 0..app.current_exercises_log.len() {
+        let name = app.current_exercises_log[idx].exercise_name.trim().to_lowercase();
+        if app.current_exercises_log[idx].suggestion_for.as_deref() == Some(name.as_str()) {
+            continue;
+        }
+        // The name may be an alias.
+        let history = app.with_db(|conn| match exercises::find(conn, &name)? {
+            Some(id) => workouts::exercise_history(conn, id).map(Some),
+            None => Ok(None),
+        });
+        let suggestion = match history {
+            Ok(Some(history)) => progression::suggest(&history, app.current_exercises_log[idx].target, config),
+            Ok(None) => None,
+            Err(e) => {
+                error!("failed to load history for suggestion: {}", e);
+                None
+            }
+        };
+        let entry = &mut app.current_exercises_log[idx];
+        entry.suggestion = suggestion;
+        entry.suggestion_for = Some(name);
+    }
+}
+
+fn apply_suggestion(entry: &mut ExerciseLogEntry, unit: WeightUnit) {
+    let Some(suggestion) = &entry.suggestion else { return };
+    for set in &mut entry.sets {
+        set.weight = unit.format_input(suggestion.weight_lbs);
+        set.reps = suggestion.reps.to_string();
+    }
+}
+
+fn render_template_picker(app: &mut MyApp, ui: &mut egui::Ui) {
+    if app.workout_templates.is_empty() {
+        ui.small("no templates yet. log a workout and save it as a template to reuse it.");
+        return;
+    }
+    let selected_name = app
+        .selected_template_id
+        .and_then(|id| app.workout_templates.iter().find(|t| t.id == id))
+        .map(|t| t.name.clone())
+        .unwrap_or_else(|| "select...".to_string());
+    ui.horizontal(|ui| {
+        ui.label("Template:");
+        egui::ComboBox::from_id_salt("workout_template_combo")
+            .selected_text(selected_name)
+            .show_ui(ui, |ui| {
+                for template in &app.workout_templates {
+                    ui.selectable_value(&mut app.selected_template_id, Some(template.id), &template.name);
+                }
+            });
+        let Some(template) = app.selected_template_id.and_then(|id| app.workout_templates.iter().find(|t| t.id == id)).cloned()
+        else {
+            return;
+        };
+        if ui
+            .button("Start from Template")
+            .on_hover_text("replace the exercises below with this template's, using the weights you last lifted")
+            .clicked()
+        {
+            start_from_template(app, &template);
+        }
+        if ui.small_button("Delete").clicked() {
+            match app.with_db(|conn| templates::delete(conn, template.id)) {
+                Ok(()) => {
+                    app.push_status(format!("deleted template {}.", template.name));
+                    app.fetch_workout_templates();
+                }
+                Err(e) => {
+                    error!("error deleting template: {}", e);
+                    app.push_status(format!("error deleting template: {}", e));
+                }
+            }
+        }
+    });
+}
+
+fn start_from_template(app: &mut MyApp, template: &WorkoutTemplate) {
+    let last_sets = app.with_db(|conn| {
+        template.exercises.iter().map(|e| workouts::last_session_sets(conn, e.exercise_id)).collect::<Result<Vec<_>, _>>()
+    });
+    match last_sets {
+        Ok(last_sets) => {
+            app.current_exercises_log = entries_from_template(template, &last_sets, app.settings.lift_unit);
+            app.last_logged_workout.clear();
+            if let Some(notes) = &template.notes {
+                app.workout_notes = notes.clone();
+            }
+            app.push_status(format!("started from template {}.", template.name));
+        }
+        Err(e) => {
+            error!("error loading template: {}", e);
+            app.push_status(format!("error loading template: {}", e));
+        }
+    }
+}
+
+// Builds the entries for a template. `last_sets[i]` is what was lifted the last
+// time template exercise `i` was performed; weights are pre-filled from it set
+// by set, repeating its final set when the template asks for more. Reps are
+// only filled in when the target is a single number.
+pub(crate) fn entries_from_template(
+    template: &WorkoutTemplate,
+    last_sets: &[Vec<ExerciseSet>],
+    unit: WeightUnit,
+) -> Vec<ExerciseLogEntry> {
+    template
+        .exercises
+        .iter()
+        .zip(last_sets)
+        .map(|(exercise, last)| {
+            let target = SetTarget {
+                sets: exercise.target_sets,
+                rep_min: exercise.rep_min,
+                rep_max: exercise.rep_max,
+                rpe: exercise.target_rpe,
+            };
+            let sets = (0..exercise.target_sets as usize)
+                .map(|idx| SetEntry {
+                    reps: if target.rep_min == target.rep_max { target.rep_min.to_string() } else { String::new() },
+                    weight: last.get(idx).or(last.last()).map(|s| unit.format_input(s.weight_lbs)).unwrap_or_default(),
+                    ..SetEntry::default()
+                })
+                .collect();
+            ExerciseLogEntry { exercise_name: exercise.exercise_name.clone(), sets, target: Some(target), ..ExerciseLogEntry::default() }
+        })
+        .collect()
+}
+
+// Saves the exercises being edited, or the workout just logged when the list
+// is empty, under the entered name.
+fn save_as_template(app: &mut MyApp) {
+    let from_last_logged = app.current_exercises_log.is_empty();
+    let exercises = if from_last_logged {
+        app.last_logged_workout.clone()
+    } else {
+        match parse_workout(&app.current_exercises_log, app.settings.lift_unit) {
+            Ok(parsed) => parsed,
+            Err(msg) => {
+                app.push_status(msg);
+                return;
+            }
+        }
+    };
+    if exercises.is_empty() {
+        app.push_status("add exercises or log a workout before saving a template.");
+        return;
+    }
+
+    let targets: Vec<NewTemplateExercise> = exercises.iter().map(NewTemplateExercise::from_logged).collect();
+    let name = app.new_template_name.trim().to_string();
+    let notes = app.workout_notes.trim();
+    let notes = if notes.is_empty() { None } else { Some(notes.to_string()) };
+    match app.with_db(|conn| templates::save(conn, &name, notes.as_deref(), &targets)) {
+        Ok(id) => {
+            let source = if from_last_logged { " from the workout just logged" } else { "" };
+            app.push_status(format!("saved template {} with {} exercise(s){}.", name, targets.len(), source));
+            app.new_template_name.clear();
+            app.selected_template_id = Some(id);
+            app.fetch_workout_templates();
+        }
+        Err(e) => {
+            error!("error saving template: {}", e);
+            app.push_status(format!("error saving template: {}", e));
+        }
+    }
+}
+
+// Validates the text buffers and converts them into typed rows. The error is a
+// user-facing message naming the offending exercise/set.
+pub(crate) fn parse_workout(entries: &[ExerciseLogEntry], unit: WeightUnit) -> Result<Vec<NewExercise>, String> {
+    if entries.is_empty() {
+        return Err("add at least one exercise before logging.".to_string());
+    }
+
+    let mut parsed = Vec::with_capacity(entries.len());
+    for (idx, entry) in entries.iter().enumerate() {
+        let name = entry.exercise_name.trim();
+        if name.is_empty() {
+            return Err(format!("exercise #{} has no name.", idx + 1));
+        }
+        if entry.sets.is_empty() {
+            return Err(format!("{} has no sets.", name));
+        }
+
+        let mut sets = Vec::with_capacity(entry.sets.len());
+        for (set_idx, set) in entry.sets.iter().enumerate() {
+            let reps = match set.reps.trim().parse::<i64>() {
+                Ok(r) if r > 0 => r,
+                _ => return Err(format!("{} set {}: reps must be a positive whole number.", name, set_idx + 1)),
+            };
+            let weight_lbs = match units::parse_weight(&set.weight, unit) {
+                Ok(w) if w >= 0.0 => w,
+                _ => return Err(format!("{} set {}: weight must be zero or a positive number.", name, set_idx + 1)),
+            };
+            let rpe = match set.rpe.trim() {
+                "" => None,
+                text => match text.parse::<f64>() {
+                    Ok(r) if (1.0..=10.0).contains(&r) => Some(r),
+                    _ => return Err(format!("{} set {}: rpe must be between 1 and 10.", name, set_idx + 1)),
+                },
+            };
+            let notes = match set.notes.trim() {
+                "" => None,
+                text => Some(text.to_string()),
+            };
+            sets.push(NewSet { reps, weight_lbs, rpe, notes });
+        }
+        parsed.push(NewExercise { name: name.to_string(), sets });
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(reps: &str, weight: &str, rpe: &str) -> SetEntry {
+        SetEntry { reps: reps.into(), weight: weight.into(), rpe: rpe.into(), notes: String::new() }
+    }
+
+    #[test]
+    fn parse_rejects_bad_input() {
+        let entry = |sets| ExerciseLogEntry { exercise_name: "Squat".into(), sets, ..ExerciseLogEntry::default() };
+        let lbs = WeightUnit::Lbs;
+        assert!(parse_workout(&[], lbs).is_err());
+        assert!(parse_workout(&[entry(vec![set("0", "225", "")])], lbs).is_err());
+        assert!(parse_workout(&[entry(vec![set("5", "-5", "")])], lbs).is_err());
+        assert!(parse_workout(&[entry(vec![set("5", "225", "11")])], lbs).is_err());
+        let ok = parse_workout(&[entry(vec![set("5", "225", "8.5")])], lbs).unwrap();
+        assert_eq!(ok[0].sets[0], NewSet { reps: 5, weight_lbs: 225.0, rpe: Some(8.5), notes: None });
+        // Bare numbers follow the lift unit; a suffix overrides it.
+        let kg = parse_workout(&[entry(vec![set("5", "100", ""), set("5", "225lbs", "")])], WeightUnit::Kg).unwrap();
+        assert!((kg[0].sets[0].weight_lbs - 220.462262185).abs() < 1e-6);
+        assert_eq!(kg[0].sets[1].weight_lbs, 225.0);
+    }
+
+    #[test]
+    fn template_prefills_targets_and_last_weights() {
+        use crate::db::templates::TemplateExercise;
+        let exercise = |id, name: &str, rep_min, rep_max| TemplateExercise {
+            exercise_id: id,
+            exercise_name: name.into(),
+            target_sets: 3,
+            rep_min,
+            rep_max,
+            target_rpe: Some(8.0),
+        };
+        let template = WorkoutTemplate {
+            id: 1,
+            name: "Push".into(),
+            notes: None,
+            exercises: vec![exercise(1, "Bench", 8, 10), exercise(2, "Dips", 12, 12)],
+        };
+        let last_bench = |order, weight_lbs| ExerciseSet {
+            id: order,
+            workout_session_id: 1,
+            exercise_id: 1,
+            set_order: order,
+            reps: 8,
+            weight_lbs,
+            rpe: None,
+            notes: None,
+        };
+        let last_sets = vec![vec![last_bench(1, 185.0), last_bench(2, 175.0)], Vec::new()];
+
+        let entries = entries_from_template(&template, &last_sets, WeightUnit::Lbs);
+        let weights: Vec<_> = entries[0].sets.iter().map(|s| s.weight.as_str()).collect();
+        assert_eq!(weights, ["185", "175", "175"]);
+        assert!(entries[0].sets.iter().all(|s| s.reps.is_empty()));
+        assert_eq!(entries[0].target.unwrap().label(), "target: 3 x 8-10 @ RPE 8");
+        // Never performed: no weight to suggest, but a fixed rep target is filled in.
+        assert!(entries[1].sets.iter().all(|s| s.weight.is_empty() && s.reps == "12"));
+    }
+}
@@ -1,12 +1,23 @@
-use crate::app_state::MyApp;
+use crate::app_state::{MyApp, WEIGHT_LOG_UNDO_LIMIT};
+use crate::db::nutrition::{self, NutritionLog};
+use crate::db::weight_logs::{self, EditOutcome};
+use crate::db::WeightLog;
+use crate::units;
+use crate::types::{NutritionInput, PendingDuplicateWeighIn, WeightLogEdit, WeightLogUndo};
 use eframe::egui;
 use egui_extras::DatePickerButton; // Added for date picker
-use rusqlite;
-use chrono;
-use std::time::Instant;
+use chrono::{self, NaiveDate};
 use log::error;
 
-pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+// Row-level actions collected while drawing the grid and applied afterwards.
+enum RowAction {
+    StartEdit(WeightLog),
+    SaveEdit,
+    CancelEdit,
+    RequestDelete(WeightLog),
+}
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
     ui.heading("Log Weight");
     if app.active_diet_cycle_id.is_none() {
         ui.label("no active diet cycle. please set one up.");
@@ -21,7 +32,7 @@ pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
         }
 
         ui.label(format!("active diet cycle id: {}", app.active_diet_cycle_id.unwrap()));
-        
+
         ui.add_space(10.0);
 
         // Date Picker for Weigh-in Date
@@ -32,86 +43,403 @@ pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
         ui.add_space(5.0); // Add a little space after the date picker
 
         ui.horizontal(|ui| {
-            ui.label("Weight (lbs):");
-            ui.add(egui::TextEdit::singleline(&mut app.log_weight_input_lbs).desired_width(100.0));
+            ui.label(format!("Weight ({}):", app.settings.bodyweight_unit.suffix()));
+            ui.add(
+                egui::TextEdit::singleline(&mut app.log_weight_input)
+                    .desired_width(100.0)
+                    .hint_text(app.settings.bodyweight_unit.format_input(180.0)),
+            );
         });
 
-        if ui.button("Log Weight").clicked() {
-            if let Some(active_cycle_id) = app.active_diet_cycle_id {
-                match app.log_weight_input_lbs.trim().parse::<f64>() {
-                    Ok(weight_val) => {
-                        if weight_val > 0.0 {
-                            let mut log_successful = false;
-                            let mut db_error_message: Option<String> = None;
-
-                            if let Some(conn_mutex) = &app.db_conn {
-                                match conn_mutex.lock() {
-                                    Ok(conn) => {
-                                        // Use the selected weigh-in date
-                                        let log_date_str = app.selected_weigh_in_date.format("%Y-%m-%d").to_string();
-                                        match conn.execute(
-                                            "INSERT INTO weight_logs (diet_cycle_id, log_date, weight_lbs) VALUES (?1, ?2, ?3)",
-                                            rusqlite::params![active_cycle_id, log_date_str, weight_val],
-                                        ) {
-                                            Ok(_) => {
-                                                log_successful = true;
-                                            }
-                                            Err(e) => {
-                                                db_error_message = Some(format!("error logging weight: {}", e));
-                                                error!("error logging weight: {}", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        db_error_message = Some(format!("failed to acquire database lock: {}", e));
-                                        error!("failed to acquire database lock: {}", e);
-                                    }
-                                }
-                            }
-
-                            if log_successful {
-                                app.console_messages.push(format!("[STATUS] weight {} lbs logged successfully.\n", weight_val));
-                                app.log_weight_input_lbs.clear();
-                                app.fetch_recent_weight_logs();
-                            } else if let Some(msg) = db_error_message {
-                                app.console_messages.push(format!("[STATUS] {}\n", msg));
-                            } else {
-                                // This case might need more specific error handling if there are other failure modes
-                                app.console_messages.push(format!("[STATUS] failed to log weight.\n"));
-                            }
-                        } else {
-                            app.console_messages.push(format!("[STATUS] weight must be a positive number.\n"));
-                        }
-                    }
-                    Err(_) => {
-                        app.console_messages.push(format!("[STATUS] invalid weight input. please enter a number.\n"));
-                    }
+        ui.horizontal(|ui| {
+            if ui.add_enabled(app.pending_duplicate_weigh_in.is_none(), egui::Button::new("Log Weight")).clicked() {
+                log_weight(app);
+            }
+            if let Some(last) = app.weight_log_undo_stack.last() {
+                if ui.button(format!("Undo {}", last.describe())).clicked() {
+                    undo_last(app);
                 }
             } else {
-                app.console_messages.push(format!("[STATUS] no active diet cycle to log weight against.\n"));
+                ui.add_enabled(false, egui::Button::new("Undo"));
             }
-            app.last_status_time = Instant::now();
-        }
+        });
+
+        render_duplicate_prompt(app, ui);
+        render_nutrition_entry(app, ui);
 
         if !app.recent_weight_logs.is_empty() {
             ui.add_space(10.0);
             ui.separator();
             ui.add_space(10.0);
             ui.label("Recent Weight Logs:");
+            let mut actions = Vec::new();
             egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
                 egui::Grid::new("recent_weight_logs_grid")
-                    .num_columns(2)
+                    .num_columns(4)
                     .spacing([40.0, 4.0])
                     .striped(true)
                     .show(ui, |ui| {
-                        for (date, weight) in &app.recent_weight_logs {
-                            ui.label(date);
-                            ui.label(format!("{:.1} lbs", weight));
+                        for entry in &app.recent_weight_logs {
+                            match &mut app.editing_weight_log {
+                                Some(edit) if edit.id == entry.id => {
+                                    ui.add(egui::TextEdit::singleline(&mut edit.log_date).desired_width(90.0));
+                                    ui.add(egui::TextEdit::singleline(&mut edit.weight).desired_width(80.0));
+                                    if ui.small_button("Save").clicked() {
+                                        actions.push(RowAction::SaveEdit);
+                                    }
+                                    if ui.small_button("Cancel").clicked() {
+                                        actions.push(RowAction::CancelEdit);
+                                    }
+                                }
+                                _ => {
+                                    ui.label(app.settings.date_format.format_stored(&entry.log_date));
+                                    ui.label(app.settings.bodyweight_unit.format(entry.weight_lbs));
+                                    if ui.small_button("Edit").clicked() {
+                                        actions.push(RowAction::StartEdit(entry.clone()));
+                                    }
+                                    if ui.small_button("Delete").clicked() {
+                                        actions.push(RowAction::RequestDelete(entry.clone()));
+                                    }
+                                }
+                            }
                             ui.end_row();
                         }
                     });
             });
+
+            for action in actions {
+                match action {
+                    RowAction::StartEdit(entry) => {
+                        app.editing_weight_log = Some(WeightLogEdit {
+                            id: entry.id,
+                            log_date: entry.log_date,
+                            weight: app.settings.bodyweight_unit.format_input(entry.weight_lbs),
+                        });
+                    }
+                    RowAction::SaveEdit => save_edit(app),
+                    RowAction::CancelEdit => app.editing_weight_log = None,
+                    RowAction::RequestDelete(entry) => app.pending_weight_log_delete = Some(entry),
+                }
+            }
         }
     }
+    render_delete_confirmation(app, ctx);
     app.display_status_message(ui);
 }
+
+fn log_weight(app: &mut MyApp) {
+    let Some(active_cycle_id) = app.active_diet_cycle_id else {
+        app.push_status("no active diet cycle to log weight against.");
+        return;
+    };
+    let weight_val = match units::parse_weight(&app.log_weight_input, app.settings.bodyweight_unit) {
+        Ok(w) if w > 0.0 => w,
+        Ok(_) => {
+            app.push_status("weight must be a positive number.");
+            return;
+        }
+        Err(msg) => {
+            app.push_status(msg);
+            return;
+        }
+    };
+
+    let log_date_str = app.selected_weigh_in_date.format("%Y-%m-%d").to_string();
+    match app.with_db(|conn| weight_logs::find_on_date(conn, &log_date_str)) {
+        Ok(Some(existing)) => {
+            app.pending_duplicate_weigh_in = Some(PendingDuplicateWeighIn { existing, new_weight_lbs: weight_val });
+            return;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            error!("error checking for existing weigh-in: {}", e);
+            app.push_status(format!("error checking for existing weigh-in: {}", e));
+            return;
+        }
+    }
+
+    insert_and_report(app, active_cycle_id, &log_date_str, weight_val);
+}
+
+fn insert_and_report(app: &mut MyApp, cycle_id: i64, log_date: &str, weight_lbs: f64) {
+    match app.with_db(|conn| weight_logs::insert(conn, Some(cycle_id), log_date, weight_lbs)) {
+        Ok(_) => {
+            app.push_status(format!("weight {} logged successfully.", app.settings.bodyweight_unit.format(weight_lbs)));
+            app.log_weight_input.clear();
+            app.fetch_recent_weight_logs();
+            app.fetch_energy_data();
+        }
+        Err(e) => {
+            error!("error logging weight: {}", e);
+            app.push_status(format!("error logging weight: {}", e));
+        }
+    }
+}
+
+// Quick food entry for the selected date, with the running TDEE estimate.
+fn render_nutrition_entry(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.add_space(10.0);
+    ui.horizontal(|ui| {
+        let input = &mut app.nutrition_input;
+        ui.label("Calories:");
+        ui.add(egui::TextEdit::singleline(&mut input.calories).desired_width(60.0).hint_text("kcal"));
+        ui.label("P:");
+        ui.add(egui::TextEdit::singleline(&mut input.protein).desired_width(40.0).hint_text("g"));
+        ui.label("C:");
+        ui.add(egui::TextEdit::singleline(&mut input.carbs).desired_width(40.0).hint_text("g"));
+        ui.label("F:");
+        ui.add(egui::TextEdit::singleline(&mut input.fat).desired_width(40.0).hint_text("g"));
+        if ui.button("Log Food").on_hover_text("replaces anything already logged for the date").clicked() {
+            log_nutrition(app);
+        }
+    });
+    ui.horizontal(|ui| {
+        match app.tdee_series.last() {
+            Some((_, tdee)) => ui.label(format!("estimated TDEE: {:.0} kcal/day", tdee)),
+            None => ui.label(format!(
+                "estimated TDEE: needs {} days of food logs in two weeks, with weigh-ins",
+                crate::tdee::MIN_LOGGED_DAYS
+            )),
+        };
+        if let Some(target) = app.active_calorie_target {
+            ui.separator();
+            ui.label(format!("cycle target: {} kcal", target));
+        }
+    });
+}
+
+fn log_nutrition(app: &mut MyApp) {
+    let date = app.selected_weigh_in_date.format("%Y-%m-%d").to_string();
+    let entry = match parse_nutrition(&app.nutrition_input, &date) {
+        Ok(entry) => entry,
+        Err(msg) => {
+            app.push_status(msg);
+            return;
+        }
+    };
+    let result = app.with_db(|conn| {
+        let previous = nutrition::find_on_date(conn, &entry.log_date)?;
+        nutrition::upsert(conn, &entry)?;
+        Ok(previous)
+    });
+    match result {
+        Ok(previous) => {
+            match previous {
+                Some(old) => app.push_status(format!("replaced {} kcal with {} kcal for {}.", old.calories, entry.calories, date)),
+                None => app.push_status(format!("logged {} kcal for {}.", entry.calories, date)),
+            }
+            app.nutrition_input = NutritionInput::default();
+            app.fetch_energy_data();
+        }
+        Err(e) => {
+            error!("error logging food: {}", e);
+            app.push_status(format!("error logging food: {}", e));
+        }
+    }
+}
+
+// Calories are required; blank macros are left unset.
+pub(crate) fn parse_nutrition(input: &NutritionInput, log_date: &str) -> Result<NutritionLog, String> {
+    let calories = match input.calories.trim().parse::<i64>() {
+        Ok(kcal) if (0..=20_000).contains(&kcal) => kcal,
+        _ => return Err("calories must be a whole number between 0 and 20000.".to_string()),
+    };
+    let grams = |name: &str, text: &str| -> Result<Option<f64>, String> {
+        let text = text.trim().trim_end_matches('g').trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        match text.parse::<f64>() {
+            Ok(g) if g >= 0.0 => Ok(Some(g)),
+            _ => Err(format!("{} must be grams, zero or more.", name)),
+        }
+    };
+    Ok(NutritionLog {
+        id: 0,
+        log_date: log_date.to_string(),
+        calories,
+        protein_g: grams("protein", &input.protein)?,
+        carbs_g: grams("carbs", &input.carbs)?,
+        fat_g: grams("fat", &input.fat)?,
+    })
+}
+
+fn render_duplicate_prompt(app: &mut MyApp, ui: &mut egui::Ui) {
+    let Some(pending) = app.pending_duplicate_weigh_in.clone() else { return };
+
+    let unit = app.settings.bodyweight_unit;
+    ui.add_space(5.0);
+    ui.group(|ui| {
+        ui.label(format!(
+            "a weigh-in of {} already exists for {}. replace it with {}?",
+            unit.format(pending.existing.weight_lbs),
+            pending.existing.log_date,
+            unit.format(pending.new_weight_lbs)
+        ));
+        ui.horizontal(|ui| {
+            if ui.button("Replace").clicked() {
+                let mut replacement = pending.existing.clone();
+                replacement.weight_lbs = pending.new_weight_lbs;
+                replacement.diet_cycle_id = app.active_diet_cycle_id.or(replacement.diet_cycle_id);
+                match app.with_db(|conn| weight_logs::update(conn, &replacement)) {
+                    Ok(()) => {
+                        push_undo(app, WeightLogUndo::Edited(pending.existing.clone()));
+                        app.push_status(format!(
+                            "replaced weigh-in for {} with {}.",
+                            replacement.log_date,
+                            unit.format(replacement.weight_lbs)
+                        ));
+                        app.log_weight_input.clear();
+                        app.fetch_recent_weight_logs();
+                        app.fetch_energy_data();
+                    }
+                    Err(e) => {
+                        error!("error replacing weigh-in: {}", e);
+                        app.push_status(format!("error replacing weigh-in: {}", e));
+                    }
+                }
+                app.pending_duplicate_weigh_in = None;
+            }
+            if ui.button("Cancel").clicked() {
+                app.pending_duplicate_weigh_in = None;
+            }
+        });
+    });
+}
+
+fn render_delete_confirmation(app: &mut MyApp, ctx: &egui::Context) {
+    let Some(entry) = app.pending_weight_log_delete.clone() else { return };
+
+    egui::Window::new("Delete Weigh-in?")
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(format!("delete {} on {}?", app.settings.bodyweight_unit.format(entry.weight_lbs), entry.log_date));
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Delete").clicked() {
+                    match app.with_db(|conn| weight_logs::delete(conn, entry.id)) {
+                        Ok(()) => {
+                            push_undo(app, WeightLogUndo::Deleted(entry.clone()));
+                            app.push_status(format!("deleted weigh-in for {}.", entry.log_date));
+                            if app.editing_weight_log.as_ref().is_some_and(|e| e.id == entry.id) {
+                                app.editing_weight_log = None;
+                            }
+                            app.fetch_recent_weight_logs();
+                            app.fetch_energy_data();
+                        }
+                        Err(e) => {
+                            error!("error deleting weigh-in: {}", e);
+                            app.push_status(format!("error deleting weigh-in: {}", e));
+                        }
+                    }
+                    app.pending_weight_log_delete = None;
+                }
+                if ui.button("Cancel").clicked() {
+                    app.pending_weight_log_delete = None;
+                }
+            });
+        });
+}
+
+fn save_edit(app: &mut MyApp) {
+    let Some(edit) = app.editing_weight_log.clone() else { return };
+    let Some(original) = app.recent_weight_logs.iter().find(|e| e.id == edit.id).cloned() else {
+        app.editing_weight_log = None;
+        return;
+    };
+
+    let Ok(date) = NaiveDate::parse_from_str(edit.log_date.trim(), "%Y-%m-%d") else {
+        app.push_status("invalid date format. use yyyy-mm-dd.");
+        return;
+    };
+    let weight_lbs = match units::parse_weight(&edit.weight, app.settings.bodyweight_unit) {
+        Ok(w) if w > 0.0 => w,
+        Ok(_) => {
+            app.push_status("weight must be a positive number.");
+            return;
+        }
+        Err(msg) => {
+            app.push_status(msg);
+            return;
+        }
+    };
+
+    let unchanged = date.format("%Y-%m-%d").to_string() == original.log_date && weight_lbs == original.weight_lbs;
+    if unchanged {
+        app.editing_weight_log = None;
+        return;
+    }
+    let today = chrono::Local::now().date_naive();
+    match app.with_db(|conn| weight_logs::edit(conn, &original, date, weight_lbs, today)) {
+        Ok(EditOutcome::Saved(updated)) => {
+            push_undo(app, WeightLogUndo::Edited(original));
+            app.push_status(format!(
+                "updated weigh-in: {} at {}.",
+                updated.log_date,
+                app.settings.bodyweight_unit.format(updated.weight_lbs)
+            ));
+            app.editing_weight_log = None;
+            app.fetch_recent_weight_logs();
+            app.fetch_energy_data();
+        }
+        Ok(EditOutcome::DateTaken(existing)) => {
+            app.push_status(format!(
+                "there is already a weigh-in on {} ({}). edit or delete that one instead.",
+                existing.log_date,
+                app.settings.bodyweight_unit.format(existing.weight_lbs)
+            ));
+        }
+        Err(e) => {
+            error!("error updating weigh-in: {}", e);
+            app.push_status(format!("error updating weigh-in: {}", e));
+        }
+    }
+}
+
+fn push_undo(app: &mut MyApp, undo: WeightLogUndo) {
+    app.weight_log_undo_stack.push(undo);
+    if app.weight_log_undo_stack.len() > WEIGHT_LOG_UNDO_LIMIT {
+        app.weight_log_undo_stack.remove(0);
+    }
+}
+
+fn undo_last(app: &mut MyApp) {
+    let Some(undo) = app.weight_log_undo_stack.pop() else { return };
+    let result = app.with_db(|conn| match &undo {
+        WeightLogUndo::Deleted(entry) => weight_logs::restore(conn, entry),
+        WeightLogUndo::Edited(entry) => weight_logs::update(conn, entry),
+    });
+    match result {
+        Ok(()) => {
+            app.push_status(format!("undid {}.", undo.describe()));
+            app.editing_weight_log = None;
+            app.fetch_recent_weight_logs();
+            app.fetch_energy_data();
+        }
+        Err(e) => {
+            error!("error undoing {}: {}", undo.describe(), e);
+            app.push_status(format!("error undoing {}: {}", undo.describe(), e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_calories_with_optional_macros() {
+        let mut input = NutritionInput { calories: "2400".into(), protein: "180g".into(), ..Default::default() };
+        let entry = parse_nutrition(&input, "2025-03-01").unwrap();
+        assert_eq!(entry.calories, 2400);
+        assert_eq!((entry.protein_g, entry.carbs_g, entry.fat_g), (Some(180.0), None, None));
+
+        input.fat = "-5".into();
+        assert!(parse_nutrition(&input, "2025-03-01").is_err());
+        input.fat.clear();
+        input.calories = "lots".into();
+        assert!(parse_nutrition(&input, "2025-03-01").is_err());
+    }
+}
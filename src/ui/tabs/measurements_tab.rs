@@ -0,0 +1,253 @@
+use crate::app_state::MyApp;
+use crate::body_composition::{self, BodyFatSource};
+use crate::db::measurements::{self, Measurement};
+use crate::types::MeasurementKind;
+use crate::units::{self, LengthUnit};
+use crate::ui::plot_helpers;
+use eframe::egui;
+use egui_extras::DatePickerButton;
+use egui_plot::{Legend, Line, Plot, Points};
+use chrono::NaiveDate;
+use log::error;
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.heading("Body Measurements");
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        ui.label("Date:");
+        ui.add(DatePickerButton::new(&mut app.measurement_date).id_salt("measurement_date"));
+    });
+    ui.small("fill in whichever measurements you took; blank ones are skipped.");
+    let length_unit = app.settings.length_unit;
+    egui::Grid::new("measurement_inputs_grid").num_columns(4).spacing([12.0, 4.0]).show(ui, |ui| {
+        for (idx, kind) in MeasurementKind::ALL.iter().enumerate() {
+            let suffix = if kind.is_length() { length_unit.suffix() } else { "%" };
+            ui.label(format!("{} ({}):", kind.label(), suffix));
+            ui.add(egui::TextEdit::singleline(&mut app.measurement_inputs[idx]).desired_width(70.0));
+            if idx % 2 == 1 {
+                ui.end_row();
+            }
+        }
+    });
+    if ui.button("Log Measurements").clicked() {
+        log_measurements(app);
+    }
+
+    ui.add_space(10.0);
+    ui.separator();
+    render_derived(app, ui);
+    ui.separator();
+    ui.add_space(5.0);
+
+    ui.columns(2, |columns| {
+        render_recent(app, &mut columns[0]);
+        render_chart(app, &mut columns[1], ctx);
+    });
+
+    app.display_status_message(ui);
+}
+
+fn render_derived(app: &mut MyApp, ui: &mut egui::Ui) {
+    let composition = body_composition::derive(
+        &app.latest_measurements,
+        app.latest_weight_lbs,
+        app.settings.sex,
+        app.settings.height_in,
+    );
+    let weight_unit = app.settings.bodyweight_unit;
+    egui::Grid::new("derived_metrics_grid").num_columns(2).spacing([20.0, 4.0]).show(ui, |ui| {
+        ui.label("Navy body fat:");
+        match composition.navy_body_fat {
+            Some(bf) => ui.label(format!("{:.1}%", bf)),
+            None if app.settings.height_in <= 0.0 => ui.label("set your height in Settings"),
+            None => ui.label("needs waist and neck (and hips for women)"),
+        };
+        ui.end_row();
+
+        ui.label("Lean mass:");
+        match (composition.lean_mass_lbs, composition.body_fat) {
+            (Some(lean), Some((bf, source))) => {
+                let source = match source {
+                    BodyFatSource::Logged => "logged",
+                    BodyFatSource::Navy => "Navy estimate",
+                };
+                ui.label(format!("{} at {:.1}% body fat ({})", weight_unit.format(lean), bf, source))
+            }
+            _ if app.latest_weight_lbs.is_none() => ui.label("needs a weigh-in"),
+            _ => ui.label("needs a body fat figure"),
+        };
+        ui.end_row();
+
+        if let Some(fat) = composition.fat_mass_lbs {
+            ui.label("Fat mass:");
+            ui.label(weight_unit.format(fat));
+            ui.end_row();
+        }
+    });
+}
+
+fn render_recent(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Recent").strong());
+    if app.recent_measurements.is_empty() {
+        ui.label("no measurements yet.");
+        return;
+    }
+    let mut delete = None;
+    egui::ScrollArea::vertical().id_salt("recent_measurements_scroll").max_height(250.0).show(ui, |ui| {
+        egui::Grid::new("recent_measurements_grid").num_columns(4).spacing([12.0, 4.0]).striped(true).show(ui, |ui| {
+            for entry in &app.recent_measurements {
+                ui.label(app.settings.date_format.format_stored(&entry.measured_on));
+                ui.label(entry.kind.label());
+                ui.label(format_value(entry.kind, entry.value, app.settings.length_unit));
+                if ui.small_button("Delete").clicked() {
+                    delete = Some(entry.clone());
+                }
+                ui.end_row();
+            }
+        });
+    });
+
+    if let Some(entry) = delete {
+        match app.with_db(|conn| measurements::delete(conn, entry.id)) {
+            Ok(()) => {
+                app.push_status(format!("deleted {} from {}.", entry.kind.label().to_lowercase(), entry.measured_on));
+                app.fetch_measurements();
+            }
+            Err(e) => {
+                error!("error deleting measurement: {}", e);
+                app.push_status(format!("error deleting measurement: {}", e));
+            }
+        }
+    }
+}
+
+fn render_chart(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    let mut kind = app.measurement_chart_kind;
+    egui::ComboBox::from_id_salt("measurement_chart_combo")
+        .selected_text(kind.label())
+        .show_ui(ui, |ui| {
+            for option in MeasurementKind::ALL {
+                ui.selectable_value(&mut kind, option, option.label());
+            }
+        });
+    if kind != app.measurement_chart_kind {
+        app.measurement_chart_kind = kind;
+        app.fetch_measurements();
+    }
+
+    if app.measurement_chart_data.is_empty() {
+        ui.label(format!("no {} measurements to chart.", kind.label().to_lowercase()));
+        return;
+    }
+
+    let y_label = if kind.is_length() { app.settings.length_unit.suffix() } else { "%" };
+    let mut plot = Plot::new("measurement_plot")
+        .legend(Legend::default())
+        .height(250.0)
+        .x_axis_formatter(plot_helpers::format_date_axis(app.settings.date_format))
+        .label_formatter(plot_helpers::format_point_label(app.settings.date_format))
+        .y_axis_label(y_label);
+    if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::R)) {
+        plot = plot.reset();
+    }
+    let y_range = plot_helpers::padded_y_range(&app.measurement_chart_data);
+    plot.show(ui, |plot_ui| {
+        if let Some((y_min, y_max)) = y_range {
+            plot_helpers::add_phase_shading(plot_ui, &app.measurement_phase_spans, y_min, y_max);
+        }
+        let points: Vec<[f64; 2]> = app.measurement_chart_data.iter().map(|&(x, y)| [x, y]).collect();
+        plot_ui.line(Line::new(points.clone()).width(1.5).name(kind.label()));
+        plot_ui.points(Points::new(points).radius(2.5));
+    });
+}
+
+fn format_value(kind: MeasurementKind, value: f64, unit: LengthUnit) -> String {
+    if kind.is_length() { unit.format(value) } else { format!("{:.1}%", value) }
+}
+
+fn log_measurements(app: &mut MyApp) {
+    let values = match parse_measurements(&app.measurement_inputs, app.settings.length_unit) {
+        Ok(values) => values,
+        Err(msg) => {
+            app.push_status(msg);
+            return;
+        }
+    };
+    let date = app.measurement_date.format("%Y-%m-%d").to_string();
+    match app.with_db(|conn| measurements::insert_all(conn, &date, &values)) {
+        Ok(()) => {
+            app.push_status(format!("logged {} measurement(s) for {}.", values.len(), date));
+            app.measurement_inputs = Default::default();
+            app.fetch_measurements();
+        }
+        Err(e) => {
+            error!("error logging measurements: {}", e);
+            app.push_status(format!("error logging measurements: {}", e));
+        }
+    }
+}
+
+// Reads the non-empty buffers (one per `MeasurementKind::ALL` entry) into
+// stored values: inches for lengths, percent for body fat.
+pub(crate) fn parse_measurements(inputs: &[String], unit: LengthUnit) -> Result<Vec<(MeasurementKind, f64)>, String> {
+    let mut values = Vec::new();
+    for (kind, text) in MeasurementKind::ALL.iter().zip(inputs) {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let value = if kind.is_length() {
+            units::parse_length(text, unit).map_err(|e| format!("{}: {}", kind.label().to_lowercase(), e))?
+        } else {
+            match text.trim_end_matches('%').trim().parse::<f64>() {
+                Ok(bf) if bf > 0.0 && bf < 75.0 => bf,
+                _ => return Err("body fat must be a percentage between 0 and 75.".to_string()),
+            }
+        };
+        values.push((*kind, value));
+    }
+    if values.is_empty() {
+        return Err("enter at least one measurement.".to_string());
+    }
+    Ok(values)
+}
+
+// Measurements as plot points in `unit` (body fat stays a percentage).
+pub(crate) fn measurement_series(history: &[Measurement], unit: LengthUnit) -> Vec<(f64, f64)> {
+    let mut series = Vec::with_capacity(history.len());
+    for entry in history {
+        match NaiveDate::parse_from_str(&entry.measured_on, "%Y-%m-%d") {
+            Ok(date) => {
+                let value = if entry.kind.is_length() { unit.convert_inches(entry.value) } else { entry.value };
+                series.push((plot_helpers::date_to_x(date), value));
+            }
+            Err(e) => error!("skipping measurement with bad date {}: {}", entry.measured_on, e),
+        }
+    }
+    series
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_only_filled_in_measurements() {
+        let mut inputs: Vec<String> = vec![String::new(); MeasurementKind::ALL.len()];
+        assert!(parse_measurements(&inputs, LengthUnit::Inches).is_err());
+
+        inputs[0] = "86.36".into(); // waist, in cm below
+        inputs[5] = "15in".into(); // neck keeps its own suffix
+        inputs[6] = "18.5%".into();
+        let values = parse_measurements(&inputs, LengthUnit::Centimeters).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].0, MeasurementKind::Waist);
+        assert!((values[0].1 - 34.0).abs() < 1e-9);
+        assert_eq!(values[1], (MeasurementKind::Neck, 15.0));
+        assert_eq!(values[2], (MeasurementKind::BodyFat, 18.5));
+
+        inputs[6] = "90".into();
+        assert!(parse_measurements(&inputs, LengthUnit::Inches).is_err());
+    }
+}
@@ -1,4 +1,11 @@
+pub mod dashboard_tab;
 pub mod log_exercise_tab;
 pub mod log_weight_tab;
+pub mod measurements_tab;
 pub mod weight_progress_tab;
-pub mod exercise_progress_tab; 
\ No newline at end of file
+pub mod exercise_progress_tab;
+pub mod records_tab;
+pub mod exercises_tab;
+pub mod diet_cycles_tab;
+pub mod data_tab;
+pub mod settings_tab;
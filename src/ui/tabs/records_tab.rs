@@ -0,0 +1,132 @@
+use crate::app_state::MyApp;
+use crate::db::records;
+use crate::ui::exercise_picker;
+use eframe::egui;
+use log::error;
+
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+    ui.heading("Personal Records");
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        if ui
+            .button("Recalculate from history")
+            .on_hover_text(format!("rebuild every record using the {} formula", app.settings.e1rm_formula.label()))
+            .clicked()
+        {
+            rebuild_records(app);
+        }
+    });
+    ui.add_space(10.0);
+
+    ui.columns(2, |columns| {
+        render_current(app, &mut columns[0]);
+        render_history(app, &mut columns[1]);
+    });
+
+    app.display_status_message(ui);
+}
+
+fn render_current(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("Current PRs").strong());
+    if app.current_personal_records.is_empty() {
+        ui.label("no records yet. log a workout or recalculate from history.");
+        return;
+    }
+
+    egui::ScrollArea::vertical().id_salt("current_prs_scroll").max_height(ui.available_height() - 40.0).show(ui, |ui| {
+        let mut start = 0;
+        while start < app.current_personal_records.len() {
+            let exercise_id = app.current_personal_records[start].exercise_id;
+            let end = app.current_personal_records[start..]
+                .iter()
+                .position(|r| r.exercise_id != exercise_id)
+                .map_or(app.current_personal_records.len(), |offset| start + offset);
+            let group = &app.current_personal_records[start..end];
+
+            egui::CollapsingHeader::new(&group[0].exercise_name)
+                .id_salt(("current_prs", exercise_id))
+                .default_open(true)
+                .show(ui, |ui| {
+                    egui::Grid::new(("current_prs_grid", exercise_id))
+                        .num_columns(3)
+                        .spacing([20.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for record in group {
+                                ui.label(record.label());
+                                ui.label(record.display_value(app.settings.lift_unit));
+                                ui.label(app.settings.date_format.format_stored(&record.achieved_on));
+                                ui.end_row();
+                            }
+                        });
+                });
+            start = end;
+        }
+    });
+}
+
+fn render_history(app: &mut MyApp, ui: &mut egui::Ui) {
+    ui.label(egui::RichText::new("PR History").strong());
+
+    exercise_picker::filter_row(ui, "records_filter", &mut app.records_filter);
+    let changed = exercise_picker::combo(
+        ui,
+        "records_history_exercise_combo",
+        &app.exercise_library,
+        &app.records_filter,
+        &mut app.records_selected_exercise_id,
+        Some("All exercises"),
+    );
+    if changed {
+        app.fetch_personal_records();
+    }
+
+    if app.personal_record_history.is_empty() {
+        ui.label("no records to show.");
+        return;
+    }
+
+    egui::ScrollArea::vertical().id_salt("pr_history_scroll").max_height(ui.available_height() - 40.0).show(ui, |ui| {
+        egui::Grid::new("pr_history_grid")
+            .num_columns(4)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                let mut last_date: Option<&str> = None;
+                for record in &app.personal_record_history {
+                    // Only print the date on the first row of each day to read as a timeline
+                    if last_date != Some(record.achieved_on.as_str()) {
+                        ui.label(app.settings.date_format.format_stored(&record.achieved_on));
+                    } else {
+                        ui.label("");
+                    }
+                    last_date = Some(record.achieved_on.as_str());
+                    ui.label(format!("{} {}", record.exercise_name, record.label()));
+                    ui.label(record.display_value(app.settings.lift_unit));
+                    match record.previous_value {
+                        Some(prev) => ui.label(
+                            egui::RichText::new(format!("+{}", records::format_value(record.record_type, record.value - prev, app.settings.lift_unit)))
+                                .color(egui::Color32::LIGHT_GREEN),
+                        ),
+                        None => ui.label("first"),
+                    };
+                    ui.end_row();
+                }
+            });
+    });
+}
+
+fn rebuild_records(app: &mut MyApp) {
+    let formula = app.settings.e1rm_formula;
+    match app.with_db(|conn| records::rebuild_all(conn, formula)) {
+        Ok(count) => {
+            app.push_status(format!("rebuilt personal records: {} record(s) from history.", count));
+            app.fetch_personal_records();
+        }
+        Err(e) => {
+            error!("error rebuilding personal records: {}", e);
+            app.push_status(format!("error rebuilding personal records: {}", e));
+        }
+    }
+}
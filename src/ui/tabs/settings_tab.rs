@@ -0,0 +1,184 @@
+use crate::app_state::MyApp;
+use crate::body_composition::Sex;
+use crate::e1rm::E1rmFormula;
+use crate::progression::ProgressionScheme;
+use crate::settings::{self, DateFormat, Settings, Theme};
+use crate::smoothing::SmoothingMethod;
+use crate::timer;
+use crate::units::{LengthUnit, WeightUnit};
+use eframe::egui;
+use log::LevelFilter;
+
+const LOG_LEVELS: [LevelFilter; 5] =
+    [LevelFilter::Error, LevelFilter::Warn, LevelFilter::Info, LevelFilter::Debug, LevelFilter::Trace];
+
+// Every change is saved and applied as soon as it's made; sliders and number
+// fields wait until the drag ends or they lose focus.
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    ui.heading("Settings");
+    ui.add_space(10.0);
+
+    let mut draft = app.settings_draft.take().unwrap_or_else(|| app.settings.clone());
+    let mut editing = false;
+    egui::Grid::new("settings_grid").num_columns(2).spacing([20.0, 8.0]).show(ui, |ui| {
+        ui.label("Lift unit:");
+        combo(ui, "settings_lift_unit", &mut draft.lift_unit, &WeightUnit::LIFT_UNITS, |u| u.suffix().to_string());
+        ui.end_row();
+
+        ui.label("Bodyweight unit:");
+        combo(ui, "settings_bodyweight_unit", &mut draft.bodyweight_unit, &WeightUnit::BODYWEIGHT_UNITS, |u| {
+            u.suffix().to_string()
+        });
+        ui.end_row();
+
+        ui.label("Measurement unit:");
+        combo(ui, "settings_length_unit", &mut draft.length_unit, &LengthUnit::ALL, |u| u.suffix().to_string());
+        ui.end_row();
+
+        ui.label("Body fat profile:");
+        ui.horizontal(|ui| {
+            combo(ui, "settings_sex", &mut draft.sex, &Sex::ALL, |s| s.to_string());
+            // Stored in inches, edited in the measurement unit; zero leaves it unset.
+            let unit = draft.length_unit;
+            let mut height = unit.convert_inches(draft.height_in);
+            let response = ui
+                .add(
+                    egui::DragValue::new(&mut height)
+                        .range(0.0..=unit.convert_inches(96.0))
+                        .speed(0.5)
+                        .max_decimals(1)
+                        .prefix("height ")
+                        .suffix(format!(" {}", unit.suffix())),
+                )
+                .on_hover_text("used for the Navy body fat estimate on the measurements tab");
+            editing |= in_progress(&response);
+            if response.changed() {
+                draft.height_in = unit.to_inches(height);
+            }
+        });
+        ui.end_row();
+
+        ui.label("Theme:");
+        combo(ui, "settings_theme", &mut draft.theme, &Theme::ALL, |t| t.to_string());
+        ui.end_row();
+
+        ui.label("Date format:");
+        combo(ui, "settings_date_format", &mut draft.date_format, &DateFormat::ALL, |f| f.label().to_string());
+        ui.end_row();
+
+        ui.label("Default smoothing:");
+        ui.horizontal(|ui| {
+            combo(ui, "settings_smoothing", &mut draft.smoothing, &[SmoothingMethod::Ema, SmoothingMethod::Sma], |m| {
+                m.to_string()
+            });
+            editing |= in_progress(&ui.add(egui::DragValue::new(&mut draft.smoothing_window).range(1..=120).suffix(" days")));
+        });
+        ui.end_row();
+
+        ui.label("e1RM formula:");
+        combo(ui, "settings_e1rm_formula", &mut draft.e1rm_formula, &E1rmFormula::ALL, |f| f.label().to_string());
+        ui.end_row();
+
+        ui.label("Progression:");
+        ui.horizontal(|ui| {
+            combo(ui, "settings_progression_scheme", &mut draft.progression_scheme, &ProgressionScheme::ALL, |s| {
+                s.label().to_string()
+            });
+            // Stored in pounds, edited in the lift unit.
+            let unit = draft.lift_unit;
+            let mut increment = unit.convert_lbs(draft.progression_increment_lbs);
+            let response = ui
+                .add(
+                    egui::DragValue::new(&mut increment)
+                        .range(unit.convert_lbs(0.5)..=unit.convert_lbs(50.0))
+                        .speed(0.25)
+                        .max_decimals(2)
+                        .prefix("+")
+                        .suffix(format!(" {}", unit.suffix())),
+                )
+                .on_hover_text("weight added per step; suggestions round to it");
+            editing |= in_progress(&response);
+            if response.changed() {
+                draft.progression_increment_lbs = unit.to_lbs(increment);
+            }
+        });
+        ui.end_row();
+
+        ui.label("Console log level:");
+        combo(ui, "settings_log_level", &mut draft.log_level, &LOG_LEVELS, |l| l.to_string().to_lowercase());
+        ui.end_row();
+
+        ui.label("Console height:");
+        editing |= in_progress(&ui.add(
+            egui::Slider::new(&mut draft.console_height, settings::MIN_CONSOLE_HEIGHT..=settings::MAX_CONSOLE_HEIGHT)
+                .suffix(" px"),
+        ));
+        ui.end_row();
+
+        ui.label("Backups kept:");
+        let response = ui
+            .add(egui::DragValue::new(&mut draft.backup_keep).range(1..=settings::MAX_BACKUP_KEEP).suffix(" of each kind"))
+            .on_hover_text("startup, exit and other snapshots are rotated separately");
+        editing |= in_progress(&response);
+        ui.end_row();
+
+        ui.label("Rest timer:");
+        ui.horizontal(|ui| {
+            let response = ui
+                .add(egui::DragValue::new(&mut draft.default_rest_secs).range(5..=timer::MAX_REST_SECS).suffix(" s"))
+                .on_hover_text("for exercises without their own rest time");
+            editing |= in_progress(&response);
+            ui.checkbox(&mut draft.auto_start_rest, "start when a set is added");
+            ui.checkbox(&mut draft.timer_bell, "terminal bell")
+                .on_hover_text("ring the bell of the terminal the app was started from when time is up");
+        });
+        ui.end_row();
+
+        ui.label("Window size:");
+        ui.horizontal(|ui| {
+            editing |= in_progress(&ui.add(egui::DragValue::new(&mut draft.window_width).range(400.0..=4000.0).speed(5.0).suffix(" w")));
+            editing |= in_progress(&ui.add(egui::DragValue::new(&mut draft.window_height).range(400.0..=4000.0).speed(5.0).suffix(" h")));
+            if ui.small_button("Use current").on_hover_text("remember the window's current size").clicked() {
+                let size = ctx.screen_rect().size();
+                draft.window_width = size.x.round();
+                draft.window_height = size.y.round();
+            }
+        });
+        ui.end_row();
+    });
+
+    ui.add_space(10.0);
+    if ui.button("Reset to Defaults").clicked() {
+        draft = Settings::default();
+        editing = false;
+        app.push_status("settings reset to defaults.");
+    }
+
+    if editing {
+        app.settings_draft = Some(draft);
+    } else if draft != app.settings {
+        app.apply_settings(ctx, draft);
+    }
+
+    app.display_status_message(ui);
+}
+
+// Still being dragged or typed into; saving waits for `drag_stopped` or
+// `lost_focus`.
+fn in_progress(response: &egui::Response) -> bool {
+    response.dragged() || response.has_focus()
+}
+
+fn combo<T: PartialEq + Copy>(
+    ui: &mut egui::Ui,
+    id: &str,
+    value: &mut T,
+    options: &[T],
+    label: impl Fn(&T) -> String,
+) {
+    egui::ComboBox::from_id_salt(id).selected_text(label(value)).show_ui(ui, |ui| {
+        for option in options {
+            ui.selectable_value(value, *option, label(option));
+        }
+    });
+}
@@ -1,8 +1,218 @@
 use crate::app_state::MyApp;
+use crate::smoothing::SmoothingMethod;
+use crate::db::nutrition::NutritionLog;
+use crate::db::{DietCycle, WeightLog};
+use crate::types::{WeightProgressFilter, WeightProgressView};
+use crate::units::WeightUnit;
+use crate::ui::plot_helpers::{self, PhaseSpan};
 use eframe::egui;
+use egui_plot::{Legend, Line, Plot, Points};
+use chrono::NaiveDate;
+use log::error;
 
-pub fn render(app: &mut MyApp, ui: &mut egui::Ui, _ctx: &egui::Context) {
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
     ui.heading("Weight Progress");
-    // todo
+    ui.add_space(10.0);
+
+    ui.horizontal(|ui| {
+        ui.label("View:");
+        ui.selectable_value(&mut app.weight_progress_view, WeightProgressView::Weight, "Weight");
+        ui.selectable_value(&mut app.weight_progress_view, WeightProgressView::Energy, "Energy");
+    });
+    if app.weight_progress_view == WeightProgressView::Energy {
+        render_energy(app, ui, ctx);
+        app.display_status_message(ui);
+        return;
+    }
+
+    let mut changed = false;
+    ui.horizontal(|ui| {
+        ui.label("Show:");
+        let selected_text = filter_label(app, app.weight_progress_filter);
+        egui::ComboBox::from_id_salt("weight_progress_filter_combo")
+            .selected_text(selected_text)
+            .show_ui(ui, |ui| {
+                changed |= ui
+                    .selectable_value(&mut app.weight_progress_filter, WeightProgressFilter::ActiveCycle, "Active cycle")
+                    .changed();
+                changed |= ui
+                    .selectable_value(&mut app.weight_progress_filter, WeightProgressFilter::AllTime, "All time")
+                    .changed();
+                for (id, label) in &app.diet_cycle_choices {
+                    changed |= ui
+                        .selectable_value(&mut app.weight_progress_filter, WeightProgressFilter::Cycle(*id), label)
+                        .changed();
+                }
+            });
+
+        ui.label("Smoothing:");
+        egui::ComboBox::from_id_salt("weight_smoothing_combo")
+            .selected_text(app.weight_smoothing.to_string())
+            .show_ui(ui, |ui| {
+                changed |= ui.selectable_value(&mut app.weight_smoothing, SmoothingMethod::Ema, "EMA").changed();
+                changed |= ui.selectable_value(&mut app.weight_smoothing, SmoothingMethod::Sma, "SMA").changed();
+            });
+        changed |= ui
+            .add(egui::DragValue::new(&mut app.weight_smoothing_window).range(1..=60).suffix(" days"))
+            .changed();
+    });
+
+    if changed {
+        app.fetch_weight_progress_data();
+    }
+
+    ui.small("drag to pan, scroll to zoom, double-click or press R to reset the view.");
+    ui.add_space(5.0);
+
+    if app.weight_progress_data.is_empty() {
+        ui.label("no weigh-ins to show for this selection.");
+        app.display_status_message(ui);
+        return;
+    }
+
+    let mut plot = Plot::new("weight_progress_plot")
+        .legend(Legend::default())
+        .height((ui.available_height() - 30.0).max(200.0))
+        .x_axis_formatter(plot_helpers::format_date_axis(app.settings.date_format))
+        .label_formatter(plot_helpers::format_point_label(app.settings.date_format))
+        .y_axis_label(app.settings.bodyweight_unit.suffix());
+    if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::R)) {
+        plot = plot.reset();
+    }
+
+    let y_range = plot_helpers::padded_y_range(&app.weight_progress_data);
+    plot.show(ui, |plot_ui| {
+        if let Some((y_min, y_max)) = y_range {
+            plot_helpers::add_phase_shading(plot_ui, &app.weight_phase_spans, y_min, y_max);
+        }
+        plot_ui.points(
+            Points::new(app.weight_progress_data.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                .radius(2.5)
+                .name("Weigh-ins"),
+        );
+        plot_ui.line(
+            Line::new(app.smoothed_weight_progress_data.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                .width(2.0)
+                .name(format!("Trend ({} {}d)", app.weight_smoothing, app.weight_smoothing_window)),
+        );
+    });
+
     app.display_status_message(ui);
-} 
\ No newline at end of file
+}
+
+// Logged intake against the TDEE estimate and each cycle's calorie target.
+fn render_energy(app: &mut MyApp, ui: &mut egui::Ui, ctx: &egui::Context) {
+    match app.tdee_series.last() {
+        Some((_, tdee)) => ui.label(format!("estimated TDEE: {:.0} kcal/day", tdee)),
+        None => ui.label("not enough food logs and weigh-ins for a TDEE estimate yet."),
+    };
+    ui.small("the estimate is logged intake adjusted by the weight trend's change over two weeks.");
+    ui.add_space(5.0);
+
+    if app.intake_series.is_empty() {
+        ui.label("no food logged yet; add calories on the log weight tab.");
+        return;
+    }
+
+    let mut plot = Plot::new("energy_plot")
+        .legend(Legend::default())
+        .height((ui.available_height() - 30.0).max(200.0))
+        .x_axis_formatter(plot_helpers::format_date_axis(app.settings.date_format))
+        .label_formatter(plot_helpers::format_point_label(app.settings.date_format))
+        .y_axis_label("kcal");
+    if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(egui::Key::R)) {
+        plot = plot.reset();
+    }
+    plot.show(ui, |plot_ui| {
+        plot_ui.points(
+            Points::new(app.intake_series.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                .radius(2.5)
+                .name("Intake"),
+        );
+        plot_ui.line(
+            Line::new(app.tdee_series.iter().map(|&(x, y)| [x, y]).collect::<Vec<_>>())
+                .width(2.0)
+                .name("TDEE estimate"),
+        );
+        for &(start_x, end_x, kcal) in &app.calorie_target_lines {
+            plot_ui.line(
+                Line::new(vec![[start_x, kcal], [end_x, kcal]])
+                    .style(egui_plot::LineStyle::dashed_loose())
+                    .name("Target"),
+            );
+        }
+    });
+}
+
+fn filter_label(app: &MyApp, filter: WeightProgressFilter) -> String {
+    match filter {
+        WeightProgressFilter::ActiveCycle => "Active cycle".to_string(),
+        WeightProgressFilter::AllTime => "All time".to_string(),
+        WeightProgressFilter::Cycle(id) => app
+            .diet_cycle_choices
+            .iter()
+            .find(|(cycle_id, _)| *cycle_id == id)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_else(|| format!("Cycle {}", id)),
+    }
+}
+
+// Weigh-ins as plot points in `unit`; rows with unparseable dates are skipped.
+pub(crate) fn weight_series(logs: &[WeightLog], unit: WeightUnit) -> Vec<(f64, f64)> {
+    let mut series = Vec::with_capacity(logs.len());
+    for log in logs {
+        match NaiveDate::parse_from_str(&log.log_date, "%Y-%m-%d") {
+            Ok(date) => series.push((plot_helpers::date_to_x(date), unit.convert_lbs(log.weight_lbs))),
+            Err(e) => error!("skipping weight log with bad date {}: {}", log.log_date, e),
+        }
+    }
+    series
+}
+
+// Every diet cycle's span; open cycles run to today (or their planned end if later).
+pub(crate) fn phase_spans(cycles: &[DietCycle], today: NaiveDate) -> Vec<PhaseSpan> {
+    let parse = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+    let mut spans = Vec::new();
+    for cycle in cycles {
+        let Some(start) = parse(&cycle.start_date) else { continue };
+        let end = match cycle.actual_end_date.as_deref().and_then(parse) {
+            Some(end) => end,
+            None => parse(&cycle.planned_end_date).map_or(today, |planned| planned.max(today)),
+        };
+        spans.push(PhaseSpan {
+            start_x: plot_helpers::date_to_x(start),
+            end_x: plot_helpers::date_to_x(end) + 1.0,
+            phase: cycle.phase,
+        });
+    }
+    spans
+}
+
+// Daily calories as plot points; rows with unparseable dates are skipped.
+pub(crate) fn intake_series(logs: &[NutritionLog]) -> Vec<(f64, f64)> {
+    let mut series = Vec::with_capacity(logs.len());
+    for log in logs {
+        match NaiveDate::parse_from_str(&log.log_date, "%Y-%m-%d") {
+            Ok(date) => series.push((plot_helpers::date_to_x(date), log.calories as f64)),
+            Err(e) => error!("skipping food log with bad date {}: {}", log.log_date, e),
+        }
+    }
+    series
+}
+
+// A flat segment across each cycle's span at its calorie target.
+pub(crate) fn calorie_target_lines(cycles: &[DietCycle], today: NaiveDate) -> Vec<(f64, f64, f64)> {
+    cycles
+        .iter()
+        .filter_map(|cycle| {
+            let target = cycle.calorie_target?;
+            let span = phase_spans(std::slice::from_ref(cycle), today).pop()?;
+            Some((span.start_x, span.end_x, target as f64))
+        })
+        .collect()
+}
+
+// Newest first, labelled for the filter combo.
+pub(crate) fn cycle_choices(cycles: &[DietCycle]) -> Vec<(i64, String)> {
+    cycles.iter().rev().map(|c| (c.id, format!("{} from {}", c.phase, c.start_date))).collect()
+}
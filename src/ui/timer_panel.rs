@@ -0,0 +1,141 @@
+use crate::app_state::MyApp;
+use crate::timer::{self, IntervalPlan, Phase, Timer, TimerEvent, TimerMode};
+use eframe::egui;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+// Rest and interval timer, shown on every tab just above the console.
+pub fn render(app: &mut MyApp, ctx: &egui::Context) {
+    let now = Instant::now();
+    let event = app.timer.as_mut().and_then(|t| t.poll(now));
+    if let (Some(event), Some(timer)) = (event, &app.timer) {
+        alert(app, ctx, alert_message(timer, event), event == TimerEvent::Finished);
+    }
+
+    egui::TopBottomPanel::bottom("timer_panel").show(ctx, |ui| {
+        ui.add_space(4.0);
+        match &app.timer {
+            Some(timer) if !timer.status(now).finished => render_running(app, ui, now),
+            _ => render_idle(app, ui),
+        }
+        if let Some(message) = app.timer_alert.clone() {
+            ui.horizontal(|ui| {
+                ui.colored_label(ui.visuals().warn_fg_color, egui::RichText::new(message).strong());
+                if ui.small_button("Dismiss").clicked() {
+                    app.timer_alert = None;
+                }
+            });
+        }
+        ui.add_space(4.0);
+    });
+
+    // Keep the countdown moving even when nothing else is happening.
+    if app.timer.as_ref().is_some_and(|t| !t.is_paused() && !t.status(now).finished) {
+        ctx.request_repaint_after(Duration::from_millis(250));
+    }
+}
+
+fn alert_message(timer: &Timer, event: TimerEvent) -> String {
+    match (timer.mode, event) {
+        (TimerMode::Rest, _) => format!("{} over.", timer.label),
+        (_, TimerEvent::Finished) => format!("{} done.", timer.label),
+        (_, TimerEvent::PhaseStarted(phase, round)) => {
+            let rounds = timer.status(Instant::now()).rounds;
+            format!("{} {}: round {}/{}.", timer.label, phase.label(), round, rounds)
+        }
+    }
+}
+
+fn alert(app: &mut MyApp, ctx: &egui::Context, message: String, finished: bool) {
+    if finished {
+        ctx.send_viewport_cmd(egui::ViewportCommand::RequestUserAttention(egui::UserAttentionType::Informational));
+    }
+    if app.settings.timer_bell {
+        // Only heard when the app was started from a terminal.
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+    app.timer_alert = Some(message);
+}
+
+fn render_running(app: &mut MyApp, ui: &mut egui::Ui, now: Instant) {
+    let Some(timer) = app.timer.as_mut() else { return };
+    let status = timer.status(now);
+    let mut stop = false;
+    ui.horizontal(|ui| {
+        let heading = match timer.mode {
+            TimerMode::Rest => timer.label.clone(),
+            _ => format!("{} {} {}/{}", timer.label, status.phase.label(), status.round, status.rounds),
+        };
+        ui.label(egui::RichText::new(heading).strong());
+        let color = match status.phase {
+            Phase::Work => egui::Color32::LIGHT_GREEN,
+            Phase::Rest => ui.visuals().text_color(),
+        };
+        ui.label(egui::RichText::new(timer::format_remaining(status.remaining)).monospace().size(22.0).color(color));
+        ui.add(egui::ProgressBar::new(status.progress).desired_width(160.0));
+        if timer.mode != TimerMode::Rest {
+            ui.label(format!("{} left", timer::format_remaining(timer.total_remaining(now))));
+        }
+
+        if timer.is_paused() {
+            if ui.button("Resume").clicked() {
+                timer.resume(now);
+            }
+        } else if ui.button("Pause").clicked() {
+            timer.pause(now);
+        }
+        if ui.small_button("+30s").clicked() {
+            timer.extend(30, now);
+        }
+        if ui.small_button("-15s").clicked() {
+            timer.extend(-15, now);
+        }
+        stop = ui.button("Stop").clicked();
+    });
+    if stop {
+        app.timer = None;
+    }
+}
+
+fn render_idle(app: &mut MyApp, ui: &mut egui::Ui) {
+    let mut start = false;
+    ui.horizontal(|ui| {
+        ui.label("Timer:");
+        egui::ComboBox::from_id_salt("timer_mode").selected_text(app.timer_mode.label()).show_ui(ui, |ui| {
+            for mode in TimerMode::ALL {
+                ui.selectable_value(&mut app.timer_mode, mode, mode.label());
+            }
+        });
+        match app.timer_mode {
+            TimerMode::Rest => {
+                ui.label(format!("{}s", app.settings.default_rest_secs))
+                    .on_hover_text("the default rest; change it in settings or per exercise in the library");
+            }
+            TimerMode::Emom => {
+                ui.add(egui::DragValue::new(&mut app.emom_minutes).range(1..=60).suffix(" min"));
+            }
+            TimerMode::Tabata => {
+                let plan = IntervalPlan::tabata();
+                ui.label(format!("{}s on, {}s off, {} rounds", plan.work_secs, plan.rest_secs, plan.rounds));
+            }
+            TimerMode::Custom => {
+                let plan = &mut app.custom_intervals;
+                ui.add(egui::DragValue::new(&mut plan.work_secs).range(1..=timer::MAX_REST_SECS).prefix("on ").suffix(" s"));
+                ui.add(egui::DragValue::new(&mut plan.rest_secs).range(0..=timer::MAX_REST_SECS).prefix("off ").suffix(" s"));
+                ui.add(egui::DragValue::new(&mut plan.rounds).range(1..=99).suffix(" rounds"));
+            }
+        }
+        start = ui.button("Start").clicked();
+    });
+    if start {
+        let now = Instant::now();
+        app.timer = Some(match app.timer_mode {
+            TimerMode::Rest => Timer::rest("Rest timer", app.settings.default_rest_secs, now),
+            TimerMode::Emom => Timer::intervals(TimerMode::Emom, IntervalPlan::emom(app.emom_minutes), now),
+            TimerMode::Tabata => Timer::intervals(TimerMode::Tabata, IntervalPlan::tabata(), now),
+            TimerMode::Custom => Timer::intervals(TimerMode::Custom, app.custom_intervals, now),
+        });
+        app.timer_alert = None;
+    }
+}
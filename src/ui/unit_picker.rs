@@ -0,0 +1,32 @@
+use crate::app_state::MyApp;
+use crate::units::WeightUnit;
+use eframe::egui;
+
+// Lift and bodyweight unit combos shown beside the tab bar.
+pub fn render(app: &mut MyApp, ui: &mut egui::Ui) {
+    let (mut lift, mut bodyweight) = (app.settings.lift_unit, app.settings.bodyweight_unit);
+    // Right-to-left layout, so the bodyweight combo is drawn first.
+    egui::ComboBox::from_id_salt("bodyweight_unit_combo")
+        .selected_text(bodyweight.suffix())
+        .width(50.0)
+        .show_ui(ui, |ui| {
+            for unit in WeightUnit::BODYWEIGHT_UNITS {
+                ui.selectable_value(&mut bodyweight, unit, unit.suffix());
+            }
+        });
+    ui.label("Body:");
+    egui::ComboBox::from_id_salt("lift_unit_combo")
+        .selected_text(lift.suffix())
+        .width(50.0)
+        .show_ui(ui, |ui| {
+            for unit in WeightUnit::LIFT_UNITS {
+                ui.selectable_value(&mut lift, unit, unit.suffix());
+            }
+        });
+    ui.label("Lifts:");
+
+    if (lift, bodyweight) != (app.settings.lift_unit, app.settings.bodyweight_unit) {
+        let ctx = ui.ctx().clone();
+        app.set_units(&ctx, lift, bodyweight);
+    }
+}
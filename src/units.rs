@@ -0,0 +1,243 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+// Weights are stored in pounds; these only change how they're shown and typed.
+pub const LBS_PER_KG: f64 = 2.204_622_621_85;
+pub const LBS_PER_STONE: f64 = 14.0;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum WeightUnit {
+    #[default]
+    Lbs,
+    Kg,
+    // Bodyweight only; lifts are never shown in stone.
+    Stone,
+}
+
+impl WeightUnit {
+    pub const LIFT_UNITS: [WeightUnit; 2] = [WeightUnit::Lbs, WeightUnit::Kg];
+    pub const BODYWEIGHT_UNITS: [WeightUnit; 3] = [WeightUnit::Lbs, WeightUnit::Kg, WeightUnit::Stone];
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            WeightUnit::Lbs => "lbs",
+            WeightUnit::Kg => "kg",
+            WeightUnit::Stone => "st",
+        }
+    }
+
+    pub fn convert_lbs(self, lbs: f64) -> f64 {
+        match self {
+            WeightUnit::Lbs => lbs,
+            WeightUnit::Kg => lbs / LBS_PER_KG,
+            WeightUnit::Stone => lbs / LBS_PER_STONE,
+        }
+    }
+
+    pub fn to_lbs(self, value: f64) -> f64 {
+        match self {
+            WeightUnit::Lbs => value,
+            WeightUnit::Kg => value * LBS_PER_KG,
+            WeightUnit::Stone => value * LBS_PER_STONE,
+        }
+    }
+
+    // e.g. "82.5 kg", or "12 st 7.0 lb" for stone.
+    pub fn format(&self, lbs: f64) -> String {
+        match self {
+            WeightUnit::Stone => {
+                let (stones, pounds) = split_stone(lbs);
+                format!("{} st {:.1} lb", stones, pounds)
+            }
+            _ => format!("{:.1} {}", self.convert_lbs(lbs), self.suffix()),
+        }
+    }
+
+    // Like `format` but shaped for a text field, so `parse_weight` reads it back.
+    pub fn format_input(&self, lbs: f64) -> String {
+        match self {
+            WeightUnit::Stone => {
+                let (stones, pounds) = split_stone(lbs);
+                format!("{}st {:.1}lb", stones, pounds)
+            }
+            _ => format!("{}", (self.convert_lbs(lbs) * 100.0).round() / 100.0),
+        }
+    }
+}
+
+// Whole stones and the leftover pounds, rounded to a tenth of a pound.
+fn split_stone(lbs: f64) -> (i64, f64) {
+    let tenths = (lbs * 10.0).round() as i64;
+    let per_stone = (LBS_PER_STONE * 10.0) as i64;
+    (tenths / per_stone, (tenths % per_stone) as f64 / 10.0)
+}
+
+// Stored in the settings table.
+impl Display for WeightUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl FromStr for WeightUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "lb" | "lbs" | "pound" | "pounds" => Ok(WeightUnit::Lbs),
+            "kg" | "kgs" | "kilo" | "kilos" => Ok(WeightUnit::Kg),
+            "st" | "stone" | "stones" => Ok(WeightUnit::Stone),
+            other => Err(format!("unknown weight unit: {}", other)),
+        }
+    }
+}
+
+// Reads a weight typed as "82.5", "82.5kg", "180 lbs" or "12st 7" and returns
+// it in pounds. A bare number is taken to be in `default_unit`.
+pub fn parse_weight(text: &str, default_unit: WeightUnit) -> Result<f64, String> {
+    let invalid = || format!("couldn't read \"{}\" as a weight. try e.g. 82.5kg or 180lbs.", text.trim());
+
+    // Split into (number, unit) pairs: "12st 7lb" -> [(12, "st"), (7, "lb")].
+    let mut pairs: Vec<(f64, String)> = Vec::new();
+    let mut rest = text.trim();
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+        if number_len == 0 {
+            return Err(invalid());
+        }
+        let value: f64 = rest[..number_len].parse().map_err(|_| invalid())?;
+        rest = rest[number_len..].trim_start();
+        let unit_len = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        pairs.push((value, rest[..unit_len].to_string()));
+        rest = rest[unit_len..].trim_start();
+    }
+
+    let unit_of = |suffix: &str| -> Result<WeightUnit, String> {
+        if suffix.is_empty() { Ok(default_unit) } else { suffix.parse().map_err(|_| invalid()) }
+    };
+    let lbs = match pairs.as_slice() {
+        [(value, suffix)] => unit_of(suffix)?.to_lbs(*value),
+        [(stones, st), (pounds, lb)] => {
+            if unit_of(st)? != WeightUnit::Stone || !(lb.is_empty() || unit_of(lb)? == WeightUnit::Lbs) {
+                return Err(invalid());
+            }
+            stones * LBS_PER_STONE + pounds
+        }
+        _ => return Err(invalid()),
+    };
+    if lbs.is_finite() { Ok(lbs) } else { Err(invalid()) }
+}
+
+// Body measurements are stored in inches.
+pub const CM_PER_INCH: f64 = 2.54;
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum LengthUnit {
+    #[default]
+    Inches,
+    Centimeters,
+}
+
+impl LengthUnit {
+    pub const ALL: [LengthUnit; 2] = [LengthUnit::Inches, LengthUnit::Centimeters];
+
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            LengthUnit::Inches => "in",
+            LengthUnit::Centimeters => "cm",
+        }
+    }
+
+    pub fn convert_inches(self, inches: f64) -> f64 {
+        match self {
+            LengthUnit::Inches => inches,
+            LengthUnit::Centimeters => inches * CM_PER_INCH,
+        }
+    }
+
+    pub fn to_inches(self, value: f64) -> f64 {
+        match self {
+            LengthUnit::Inches => value,
+            LengthUnit::Centimeters => value / CM_PER_INCH,
+        }
+    }
+
+    pub fn format(&self, inches: f64) -> String {
+        format!("{:.1} {}", self.convert_inches(inches), self.suffix())
+    }
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.suffix())
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "in" | "inch" | "inches" | "\"" => Ok(LengthUnit::Inches),
+            "cm" | "cms" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => Ok(LengthUnit::Centimeters),
+            other => Err(format!("unknown length unit: {}", other)),
+        }
+    }
+}
+
+// Reads "32.5", "32.5in" or "82 cm" and returns inches. A bare number is
+// taken to be in `default_unit`.
+pub fn parse_length(text: &str, default_unit: LengthUnit) -> Result<f64, String> {
+    let invalid = || format!("couldn't read \"{}\" as a length. try e.g. 32.5in or 82cm.", text.trim());
+    let text = text.trim();
+    let number_len = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let value: f64 = text[..number_len].parse().map_err(|_| invalid())?;
+    let suffix = text[number_len..].trim();
+    let unit = if suffix.is_empty() { default_unit } else { suffix.parse().map_err(|_| invalid())? };
+    let inches = unit.to_inches(value);
+    if inches.is_finite() && inches > 0.0 { Ok(inches) } else { Err(invalid()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn parses_suffixes_and_defaults() {
+        assert!(close(parse_weight("180", WeightUnit::Lbs).unwrap(), 180.0));
+        assert!(close(parse_weight("100", WeightUnit::Kg).unwrap(), 220.462262185));
+        assert!(close(parse_weight("82.5kg", WeightUnit::Lbs).unwrap(), 82.5 * LBS_PER_KG));
+        assert!(close(parse_weight(" 180 LBS ", WeightUnit::Kg).unwrap(), 180.0));
+        assert!(close(parse_weight("12st 7", WeightUnit::Lbs).unwrap(), 175.0));
+        assert!(close(parse_weight("12 stone 7 lb", WeightUnit::Kg).unwrap(), 175.0));
+        assert!(close(parse_weight("12.5", WeightUnit::Stone).unwrap(), 175.0));
+        for bad in ["", "kg", "12 furlongs", "1.2.3", "12st 7kg", "5 5 5"] {
+            assert!(parse_weight(bad, WeightUnit::Lbs).is_err(), "{} should not parse", bad);
+        }
+    }
+
+    #[test]
+    fn formats_round_trip_through_parse() {
+        assert_eq!(WeightUnit::Kg.format(220.462262185), "100.0 kg");
+        assert_eq!(WeightUnit::Stone.format(175.0), "12 st 7.0 lb");
+        for unit in WeightUnit::BODYWEIGHT_UNITS {
+            let text = unit.format_input(183.4);
+            assert!((parse_weight(&text, unit).unwrap() - 183.4).abs() < 0.05, "{} via {}", text, unit);
+        }
+    }
+
+    #[test]
+    fn parses_lengths() {
+        assert!(close(parse_length("32.5", LengthUnit::Inches).unwrap(), 32.5));
+        assert!(close(parse_length("82.55cm", LengthUnit::Inches).unwrap(), 32.5));
+        assert!(close(parse_length("82.55", LengthUnit::Centimeters).unwrap(), 32.5));
+        assert!(close(parse_length("32.5 in", LengthUnit::Centimeters).unwrap(), 32.5));
+        for bad in ["", "cm", "0", "32 ft"] {
+            assert!(parse_length(bad, LengthUnit::Inches).is_err(), "{} should not parse", bad);
+        }
+    }
+}
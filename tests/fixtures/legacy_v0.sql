@@ -0,0 +1,63 @@
+-- A database created by the original one-shot db_init::init batch.
+-- It has the full schema but PRAGMA user_version was never set.
+CREATE TABLE diet_cycles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    phase TEXT NOT NULL,
+    start_date TEXT NOT NULL,
+    planned_end_date TEXT NOT NULL,
+    actual_end_date TEXT,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE weight_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    diet_cycle_id INTEGER,
+    log_date TEXT NOT NULL,
+    weight_lbs REAL NOT NULL,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE exercises (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    description TEXT,
+    default_metric_to_track TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE workout_sessions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_date TEXT NOT NULL,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE exercise_sets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    workout_session_id INTEGER NOT NULL,
+    exercise_id INTEGER NOT NULL,
+    set_order INTEGER NOT NULL,
+    reps INTEGER NOT NULL,
+    weight_lbs REAL NOT NULL,
+    rpe REAL,
+    notes TEXT,
+    created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO diet_cycles (phase, start_date, planned_end_date) VALUES ('Cut', '2025-01-06', '2025-04-06');
+
+INSERT INTO weight_logs (diet_cycle_id, log_date, weight_lbs) VALUES
+    (1, '2025-01-06', 190.2),
+    (1, '2025-01-07', 189.8),
+    (1, '2025-01-08', 189.4);
+
+INSERT INTO exercises (name, default_metric_to_track) VALUES ('Bench Press', 'Weight'), ('Squat', 'Weight');
+
+INSERT INTO workout_sessions (session_date) VALUES ('2025-01-07');
+
+INSERT INTO exercise_sets (workout_session_id, exercise_id, set_order, reps, weight_lbs, rpe) VALUES
+    (1, 1, 1, 5, 185.0, 7.5),
+    (1, 1, 2, 5, 185.0, 8.0),
+    (1, 2, 1, 5, 245.0, 8.0),
+    (1, 2, 2, 5, 245.0, 8.5);